@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "security_events"
+(
+    "id"          UUID PRIMARY KEY,
+    "user_id"     UUID        NOT NULL,
+    "session_id"  UUID        NULL,
+    "token_id"    UUID        NULL,
+    "kind"        varchar(64) NOT NULL,
+    "occurred_at" timestamp   NOT NULL
+);
+
+ALTER TABLE "security_events"
+    ADD FOREIGN KEY ("user_id") REFERENCES "users" ("user_id");
+
+ALTER TABLE "security_events"
+    ADD FOREIGN KEY ("session_id") REFERENCES "user_sessions" ("id");
@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE "refresh_tokens"
+    ADD COLUMN "parent_reusable_until"   timestamp NULL,
+    ADD COLUMN "access_token_id"         UUID      NULL,
+    ADD COLUMN "access_token_issued_at"  timestamp NULL,
+    ADD COLUMN "access_token_expiration" timestamp NULL;
@@ -0,0 +1,13 @@
+-- Add migration script here
+ALTER TABLE "refresh_tokens"
+    ADD COLUMN "issuer"   varchar NULL,
+    ADD COLUMN "audience" varchar NULL;
+
+-- Tokens issued before the issuer and audience became configurable used the user id as audience.
+UPDATE "refresh_tokens"
+SET "issuer"   = 'rust_backend_setup',
+    "audience" = "user_id"::text;
+
+ALTER TABLE "refresh_tokens"
+    ALTER COLUMN "issuer" SET NOT NULL,
+    ALTER COLUMN "audience" SET NOT NULL;
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE "user_sessions"
+    ADD COLUMN "user_agent" varchar NULL,
+    ADD COLUMN "ip_address" varchar NULL;
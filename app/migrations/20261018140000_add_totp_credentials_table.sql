@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "totp_credentials"
+(
+    "user_id"          UUID PRIMARY KEY,
+    "encrypted_secret" varchar   NOT NULL,
+    "created_at"       timestamp NOT NULL,
+    "confirmed_at"     timestamp NULL,
+    "last_used_step"   bigint    NULL
+);
+
+ALTER TABLE "totp_credentials"
+    ADD FOREIGN KEY ("user_id") REFERENCES "users" ("user_id");
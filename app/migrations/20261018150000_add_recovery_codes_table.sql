@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "recovery_codes"
+(
+    "id"         UUID PRIMARY KEY,
+    "user_id"    UUID      NOT NULL,
+    "code_hash"  varchar   NOT NULL,
+    "created_at" timestamp NOT NULL,
+    "used_at"    timestamp NULL
+);
+
+ALTER TABLE "recovery_codes"
+    ADD FOREIGN KEY ("user_id") REFERENCES "users" ("user_id");
+
+CREATE INDEX IF NOT EXISTS "recovery_codes_user_id" ON "recovery_codes" ("user_id");
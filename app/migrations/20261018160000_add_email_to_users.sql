@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE "users"
+    ADD COLUMN IF NOT EXISTS "email" varchar NULL;
@@ -0,0 +1,15 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "password_reset_tokens"
+(
+    "id"         UUID PRIMARY KEY,
+    "user_id"    UUID      NOT NULL,
+    "token_hash" varchar   NOT NULL,
+    "created_at" timestamp NOT NULL,
+    "expires_at" timestamp NOT NULL,
+    "used_at"    timestamp NULL
+);
+
+ALTER TABLE "password_reset_tokens"
+    ADD FOREIGN KEY ("user_id") REFERENCES "users" ("user_id");
+
+CREATE INDEX IF NOT EXISTS "password_reset_tokens_user_id" ON "password_reset_tokens" ("user_id");
@@ -0,0 +1,9 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "failed_logins"
+(
+    "username"          varchar PRIMARY KEY,
+    "failed_attempts"   integer   NOT NULL,
+    "window_started_at" timestamp NOT NULL,
+    "lockouts"          integer   NOT NULL,
+    "locked_until"      timestamp NULL
+);
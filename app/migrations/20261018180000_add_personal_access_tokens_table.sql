@@ -0,0 +1,17 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS "personal_access_tokens"
+(
+    "id"         UUID PRIMARY KEY,
+    "user_id"    UUID      NOT NULL,
+    "name"       varchar   NOT NULL,
+    "scopes"     varchar[] NOT NULL,
+    "token_hash" varchar   NOT NULL,
+    "created_at" timestamp NOT NULL,
+    "expires_at" timestamp NULL,
+    "revoked_at" timestamp NULL
+);
+
+ALTER TABLE "personal_access_tokens"
+    ADD FOREIGN KEY ("user_id") REFERENCES "users" ("user_id");
+
+CREATE INDEX IF NOT EXISTS "personal_access_tokens_user_id" ON "personal_access_tokens" ("user_id");
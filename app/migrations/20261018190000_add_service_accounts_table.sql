@@ -0,0 +1,15 @@
+-- Add migration script here
+-- service accounts are users without a password, which obtain access tokens with their client credentials instead
+ALTER TABLE "users"
+    ALTER COLUMN "password_hash" DROP NOT NULL;
+
+CREATE TABLE IF NOT EXISTS "service_accounts"
+(
+    "user_id"            UUID PRIMARY KEY,
+    "client_id"          varchar   NOT NULL UNIQUE,
+    "client_secret_hash" varchar   NOT NULL,
+    "created_at"         timestamp NOT NULL
+);
+
+ALTER TABLE "service_accounts"
+    ADD FOREIGN KEY ("user_id") REFERENCES "users" ("user_id");
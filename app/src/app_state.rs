@@ -1,22 +1,90 @@
-use pasetors::keys::SymmetricKey;
-use pasetors::version4::V4;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::Duration;
+use password_hash::SaltString;
+use rand::distributions::{Alphanumeric, DistString};
+use secrecy::Secret;
 use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
 
-use infrastructure::paseto::paseto_token_encryptor::LocalPasetoV4TokenEncryptor;
+use domain::sessions::session_concurrency::SessionConcurrency;
+use domain::sessions::session_policy::SessionPolicy;
+use domain::user::login_lockout::LockoutPolicy;
+use domain::user::password::PasswordPolicy;
+use infrastructure::paseto::paseto_secret_encryptor::LocalPasetoV4SecretEncryptor;
+use security::hash::argon2::Argon2Scheme;
+use security::hash::scheme::Scheme;
+use security::otp::totp::TotpSecret;
 
+use crate::configuration::application::ApplicationConfig;
 use crate::configuration::configuration::Configuration;
+use crate::configuration::password_reset::PasswordResetConfig;
+use crate::email_client::email_client::EmailClient;
+use crate::extractors::authenticated_user::active_session_cache::ActiveSessionCache;
+use crate::extractors::authenticated_user::ended_session_cache::EndedSessionCache;
+use crate::failed_logins::failed_login_store::FailedLoginStore;
+use crate::middleware::rate_limiter::RateLimiters;
 use crate::queries::database::Database;
+use crate::session_token_encryptor::SessionTokenEncryptor;
+
+/// The purpose TOTP secrets are encrypted for, so they cannot be mistaken for other encrypted data.
+const TOTP_SECRET_PURPOSE: &str = "totp_secret";
 
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub db: Database,
-    pub encryption_key: SymmetricKey<V4>,
+    pub token_encryptor: SessionTokenEncryptor,
+    pub session_concurrency: SessionConcurrency,
+
+    /// ended_sessions is only set when the revocation check of access tokens is enabled.
+    pub ended_sessions: Option<Arc<EndedSessionCache>>,
+
+    /// active_sessions is only set when the revocation check also notices sessions ended by other instances.
+    pub active_sessions: Option<Arc<ActiveSessionCache>>,
+
+    /// expired_access_token_grace_period is only set when the detection of expired access tokens is enabled.
+    pub expired_access_token_grace_period: Option<Duration>,
+
+    pub session_policy: SessionPolicy,
+
+    /// trusted_proxies are the proxies of which the `X-Forwarded-For` header is honoured.
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// totp_secret_encryptor encrypts the TOTP secrets of users before they are stored.
+    pub totp_secret_encryptor: LocalPasetoV4SecretEncryptor<TotpSecret>,
+
+    pub email_client: Arc<dyn EmailClient>,
+
+    pub password_reset: PasswordResetConfig,
+
+    /// password_policy determines which new passwords users may choose.
+    pub password_policy: PasswordPolicy,
+
+    /// lockout_policy determines when accounts are locked after failed login attempts.
+    pub lockout_policy: LockoutPolicy,
+
+    /// failed_logins keeps track of the failed login attempts per username.
+    pub failed_logins: Arc<dyn FailedLoginStore>,
+
+    /// rate_limiters is only set when rate limiting is enabled.
+    pub rate_limiters: Option<RateLimiters>,
+
+    /// dummy_password_hash is verified against when signing in with an unknown username.
+    pub dummy_password_hash: Secret<String>,
 }
 
 impl<'a> AppState {
-    pub fn new_token_encryptor(&'a self) -> LocalPasetoV4TokenEncryptor {
-        LocalPasetoV4TokenEncryptor {
-            symmetrick_key: self.encryption_key.clone(),
+    pub fn new_token_encryptor(&'a self) -> SessionTokenEncryptor {
+        self.token_encryptor.clone()
+    }
+
+    /// Remembers the sessions which have just ended, after their ending is committed, so that the
+    /// revocation check rejects their access tokens without querying the database.
+    pub fn remember_ended_sessions(&self, session_ids: impl IntoIterator<Item = Uuid>) {
+        if let Some(ended_sessions) = &self.ended_sessions {
+            session_ids.into_iter().for_each(|session_id| ended_sessions.insert(session_id));
         }
     }
 }
@@ -26,10 +94,67 @@ impl TryFrom<Configuration> for AppState {
 
     fn try_from(config: Configuration) -> Result<Self, Self::Error> {
         let pg_pool = PgPoolOptions::new().connect_lazy_with(config.database.with_db());
+        let session_policy = config.sessions.policy().context("Invalid session configuration")?;
+        let db = Database(pg_pool);
 
         return Ok(AppState {
-            db: Database(pg_pool),
-            encryption_key: config.application.encryption_key()?,
+            failed_logins: config.login_lockout.store(&db),
+            lockout_policy: config.login_lockout.policy().context("Invalid login lockout configuration")?,
+            db,
+            token_encryptor: SessionTokenEncryptor::new(&config.application, &session_policy)?,
+            session_concurrency: config.sessions.concurrency,
+            ended_sessions: new_ended_session_cache(config.sessions.revocation_check, &session_policy),
+            active_sessions: new_active_session_cache(config.sessions.revocation_check, config.sessions.active_session_cache_seconds),
+            expired_access_token_grace_period: config.sessions.expired_access_token_grace_period()
+                .context("Invalid session configuration")?,
+            session_policy,
+            totp_secret_encryptor: new_totp_secret_encryptor(&config.application)?,
+            trusted_proxies: config.application.trusted_proxies,
+            email_client: config.email_client.client(),
+            password_reset: config.password_reset,
+            password_policy: config.password_policy.policy()?,
+            rate_limiters: config.rate_limit.rate_limiters().context("Invalid rate limits")?,
+            dummy_password_hash: new_dummy_password_hash()?,
         });
     }
 }
+
+/// Creates the encryptor of TOTP secrets, which uses the encryption keys regardless of the token format.
+pub fn new_totp_secret_encryptor(config: &ApplicationConfig) -> Result<LocalPasetoV4SecretEncryptor<TotpSecret>, anyhow::Error> {
+    let keyring = config.encryption_keyring().context("Failed to parse encryption keys")?;
+    Ok(LocalPasetoV4SecretEncryptor::new(keyring, TOTP_SECRET_PURPOSE))
+}
+
+/// Creates the hash of a random password with the configured Argon2 scheme, so that verifying
+/// the password of an unknown username takes as long as verifying that of an existing user.
+/// Should be called after the password hashing is configured.
+pub fn new_dummy_password_hash() -> Result<Secret<String>, anyhow::Error> {
+    let password = Secret::new(Alphanumeric.sample_string(&mut rand::thread_rng(), 32));
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    let hash = Argon2Scheme::configured().hash(password, &salt)
+        .context("Failed to hash dummy password")?;
+
+    Ok(Secret::new(hash.to_string()))
+}
+
+/// Creates the cache used for the revocation check of access tokens, if enabled.
+pub fn new_ended_session_cache(revocation_check: bool, session_policy: &SessionPolicy) -> Option<Arc<EndedSessionCache>> {
+    if !revocation_check {
+        return None
+    }
+
+    let ttl = session_policy.access_token_lifetime
+        .to_std()
+        .expect("Access token lifetime should not be negative");
+
+    Some(Arc::new(EndedSessionCache::new(ttl)))
+}
+
+/// Creates the cache used by the revocation check to notice sessions ended by other instances, if enabled.
+pub fn new_active_session_cache(revocation_check: bool, ttl_seconds: Option<u64>) -> Option<Arc<ActiveSessionCache>> {
+    if !revocation_check {
+        return None
+    }
+
+    ttl_seconds.map(|seconds| Arc::new(ActiveSessionCache::new(std::time::Duration::from_secs(seconds))))
+}
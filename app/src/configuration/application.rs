@@ -1,9 +1,11 @@
+use chrono::{DateTime, Utc};
 use pasetors::errors::Error;
-use pasetors::keys::SymmetricKey;
+use pasetors::keys::{AsymmetricPublicKey, AsymmetricSecretKey, SymmetricKey};
 use pasetors::version4::V4;
 use secrecy::{ExposeSecret, Secret};
 use serde_aux::field_attributes::deserialize_number_from_string;
-use std::net::TcpListener;
+use std::net::{IpAddr, TcpListener};
+use infrastructure::paseto::symmetric_keyring::{RetiredSymmetricKey, SymmetricKeyring};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct ApplicationConfig {
@@ -11,7 +13,66 @@ pub struct ApplicationConfig {
     pub port: u16,
     pub host: String,
     pub base_url: String,
+    /// encryption_key is the active key used to encrypt tokens in the `paseto_local` format.
     pub encryption_key: Secret<String>,
+
+    /// retired_encryption_keys are previous encryption keys, of which tokens are still accepted
+    /// until their retirement. Allows rotating the encryption key without ending every session.
+    #[serde(default)]
+    pub retired_encryption_keys: Vec<RetiredEncryptionKeyConfig>,
+
+    /// token_format determines in which format the session tokens are issued.
+    #[serde(default)]
+    pub token_format: TokenFormat,
+
+    /// signing_key is the PASERK `k4.secret` key used to sign tokens in the `paseto_public` and `jwt_eddsa` formats.
+    #[serde(default)]
+    pub signing_key: Option<Secret<String>>,
+
+    /// verification_keys are PASERK `k4.public` keys of which signed tokens are accepted
+    /// besides those of the signing key, e.g. the public keys of previous signing keys.
+    #[serde(default)]
+    pub verification_keys: Vec<String>,
+
+    /// jwt_secret is the shared secret used to sign tokens in the `jwt_hs256` format,
+    /// should be at least 32 bytes long.
+    #[serde(default)]
+    pub jwt_secret: Option<Secret<String>>,
+
+    /// trusted_proxies are the addresses of the reverse proxies in front of the application,
+    /// of which the `X-Forwarded-For` header is trusted to contain the IP address of the client.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct RetiredEncryptionKeyConfig {
+    pub key: Secret<String>,
+
+    /// retire_at is the moment after which tokens encrypted with the key are rejected,
+    /// should be at least the refresh token lifetime after the key stopped being active.
+    pub retire_at: DateTime<Utc>,
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenFormat {
+    /// PASETO v4.local tokens, encrypted with the encryption key.
+    /// Only this application can read or verify the tokens.
+    #[default]
+    PasetoLocal,
+
+    /// PASETO v4.public tokens, signed with the signing key.
+    /// Anyone with the published public keys can verify the tokens.
+    PasetoPublic,
+
+    /// JWT signed with the jwt secret using HS256, for systems that only accept JWT.
+    /// Only those who know the secret can verify the tokens.
+    JwtHs256,
+
+    /// JWT signed with the signing key using EdDSA, for systems that only accept JWT.
+    /// Anyone with the published public keys can verify the tokens.
+    JwtEddsa,
 }
 
 impl ApplicationConfig {
@@ -30,4 +91,27 @@ impl ApplicationConfig {
     pub fn encryption_key(&self) -> Result<SymmetricKey<V4>, Error> {
         SymmetricKey::<V4>::from(self.encryption_key.expose_secret().as_bytes())
     }
+
+    pub fn encryption_keyring(&self) -> Result<SymmetricKeyring, Error> {
+        let retired_keys = self.retired_encryption_keys.iter()
+            .map(|retired_key| Ok(RetiredSymmetricKey {
+                key: SymmetricKey::<V4>::from(retired_key.key.expose_secret().as_bytes())?,
+                retire_at: retired_key.retire_at,
+            }))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        SymmetricKeyring::new(self.encryption_key()?, retired_keys)
+    }
+
+    pub fn signing_key(&self) -> Result<Option<AsymmetricSecretKey<V4>>, Error> {
+        self.signing_key.as_ref()
+            .map(|key| AsymmetricSecretKey::<V4>::try_from(key.expose_secret().as_str()))
+            .transpose()
+    }
+
+    pub fn verification_keys(&self) -> Result<Vec<AsymmetricPublicKey<V4>>, Error> {
+        self.verification_keys.iter()
+            .map(|key| AsymmetricPublicKey::<V4>::try_from(key.as_str()))
+            .collect()
+    }
 }
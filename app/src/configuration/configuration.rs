@@ -3,6 +3,13 @@ use serde::Deserialize;
 use crate::configuration::admin::AdminConfig;
 use crate::configuration::application::ApplicationConfig;
 use crate::configuration::database::DatabaseConfig;
+use crate::configuration::email_client::EmailClientConfig;
+use crate::configuration::login_lockout::LoginLockoutConfig;
+use crate::configuration::password_hashing::PasswordHashingConfig;
+use crate::configuration::password_policy::PasswordPolicyConfig;
+use crate::configuration::password_reset::PasswordResetConfig;
+use crate::configuration::rate_limit::RateLimitConfig;
+use crate::configuration::sessions::SessionsConfig;
 use crate::configuration::telemetry::TelemetryConfig;
 
 #[derive(Deserialize, Clone)]
@@ -11,7 +18,20 @@ pub struct Configuration {
     pub database: DatabaseConfig,
     pub admin: AdminConfig,
     // environment: Environment
-    pub telemetry: TelemetryConfig
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+    pub email_client: EmailClientConfig,
+    #[serde(default)]
+    pub password_reset: PasswordResetConfig,
+    #[serde(default)]
+    pub password_policy: PasswordPolicyConfig,
+    #[serde(default)]
+    pub password_hashing: PasswordHashingConfig,
+    #[serde(default)]
+    pub login_lockout: LoginLockoutConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
 }
 
 /// APP_ENVIRONMENT is the name of the environment variable used to determine the running environment.
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::email_client::email_client::EmailClient;
+use crate::email_client::local_email_client::LocalEmailClient;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct EmailClientConfig {
+    /// sender_email is the address emails are sent from.
+    pub sender_email: String,
+
+    /// output determines where sent emails end up.
+    #[serde(default)]
+    pub output: EmailOutput,
+}
+
+/// EmailOutput determines where the emails are written to, as there is no mail provider yet.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailOutput {
+    /// Every email is printed to stdout.
+    #[default]
+    Stdout,
+
+    /// Every email is appended to the file at the given path.
+    File(PathBuf),
+}
+
+impl EmailClientConfig {
+    pub fn client(&self) -> Arc<dyn EmailClient> {
+        Arc::new(LocalEmailClient::new(self.sender_email.clone(), self.output.clone()))
+    }
+}
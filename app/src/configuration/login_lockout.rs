@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use domain::user::login_lockout::LockoutPolicy;
+
+use crate::configuration::seconds::duration_of_seconds;
+use crate::failed_logins::failed_login_store::FailedLoginStore;
+use crate::failed_logins::in_memory_failed_login_store::InMemoryFailedLoginStore;
+use crate::failed_logins::postgres_failed_login_store::PostgresFailedLoginStore;
+use crate::queries::database::Database;
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LoginLockoutConfig {
+    /// max_failed_attempts is the amount of failed login attempts within the window that lock an account.
+    pub max_failed_attempts: u32,
+
+    /// window_seconds is the period in which failed login attempts are counted.
+    pub window_seconds: u64,
+
+    /// lockout_seconds is the duration of the first lockout, which doubles with every consecutive lockout.
+    pub lockout_seconds: u64,
+
+    /// max_lockout_seconds caps the duration of a lockout.
+    pub max_lockout_seconds: u64,
+
+    /// store determines where failed login attempts are kept.
+    pub store: FailedLoginStoreKind,
+}
+
+/// FailedLoginStoreKind determines where the failed login attempts are kept.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailedLoginStoreKind {
+    /// Every instance keeps track of failed login attempts by itself.
+    #[default]
+    InMemory,
+
+    /// Failed login attempts are kept in Postgres, shared by all instances.
+    Postgres,
+}
+
+impl Default for LoginLockoutConfig {
+    fn default() -> Self {
+        let policy = LockoutPolicy::default();
+
+        Self {
+            max_failed_attempts: policy.max_failed_attempts,
+            window_seconds: policy.window.num_seconds() as u64,
+            lockout_seconds: policy.lockout.num_seconds() as u64,
+            max_lockout_seconds: policy.max_lockout.num_seconds() as u64,
+            store: FailedLoginStoreKind::default(),
+        }
+    }
+}
+
+impl LoginLockoutConfig {
+    pub fn policy(&self) -> anyhow::Result<LockoutPolicy> {
+        Ok(LockoutPolicy {
+            max_failed_attempts: self.max_failed_attempts,
+            window: duration_of_seconds("login_lockout.window_seconds", self.window_seconds)?,
+            lockout: duration_of_seconds("login_lockout.lockout_seconds", self.lockout_seconds)?,
+            max_lockout: duration_of_seconds("login_lockout.max_lockout_seconds", self.max_lockout_seconds)?,
+        })
+    }
+
+    pub fn store(&self, db: &Database) -> Arc<dyn FailedLoginStore> {
+        match self.store {
+            FailedLoginStoreKind::InMemory => Arc::new(InMemoryFailedLoginStore::new()),
+            FailedLoginStoreKind::Postgres => Arc::new(PostgresFailedLoginStore::new(db.clone())),
+        }
+    }
+}
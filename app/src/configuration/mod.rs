@@ -3,3 +3,11 @@ pub mod configuration;
 pub mod database;
 pub mod admin;
 pub mod telemetry;
+pub mod sessions;
+pub mod email_client;
+pub mod password_reset;
+pub mod password_policy;
+pub mod password_hashing;
+pub mod login_lockout;
+pub mod rate_limit;
+pub mod seconds;
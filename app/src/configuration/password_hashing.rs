@@ -0,0 +1,29 @@
+use security::hash::argon2::{Argon2Params, Argon2Scheme, Pepper};
+
+#[derive(serde::Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PasswordHashingConfig {
+    /// argon2 are the parameters new password hashes are created with. Passwords of which the hash
+    /// was created with weaker parameters are hashed again when the user signs in.
+    pub argon2: Argon2Params,
+
+    /// pepper is the secret mixed into every new password hash, so that the hashes cannot be
+    /// cracked without it. Passwords of which the hash has another pepper are hashed again
+    /// when the user signs in.
+    pub pepper: Option<Pepper>,
+
+    /// retired_peppers are previous peppers, of which hashes can still be validated until their
+    /// users signed in again. Allows rotating the pepper without locking out users.
+    pub retired_peppers: Vec<Pepper>,
+}
+
+impl PasswordHashingConfig {
+    pub fn scheme(&self) -> Argon2Scheme {
+        let scheme = Argon2Scheme::new(self.argon2);
+
+        match &self.pepper {
+            None => scheme,
+            Some(pepper) => scheme.with_pepper(pepper.clone(), self.retired_peppers.clone()),
+        }
+    }
+}
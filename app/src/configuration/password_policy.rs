@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use domain::user::password::{BreachedPasswords, CharacterClass, PasswordPolicy};
+
+#[derive(serde::Deserialize, Clone)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    /// min_length is the least amount of characters of a new password.
+    pub min_length: usize,
+
+    /// max_length is the most amount of characters of a new password, which bounds the time it takes to hash it.
+    pub max_length: usize,
+
+    /// required_character_classes are the classes of which a new password needs at least one character,
+    /// any of `lowercase`, `uppercase`, `digit` and `symbol`.
+    pub required_character_classes: Vec<CharacterClass>,
+
+    /// reject_username rejects new passwords containing the username of the user.
+    pub reject_username: bool,
+
+    /// breached_passwords_file is a file with one breached password per line, which are rejected as new password.
+    pub breached_passwords_file: Option<PathBuf>,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        let policy = PasswordPolicy::default();
+
+        Self {
+            min_length: policy.min_length,
+            max_length: policy.max_length,
+            required_character_classes: policy.required_character_classes,
+            reject_username: policy.reject_username,
+            breached_passwords_file: None,
+        }
+    }
+}
+
+impl PasswordPolicyConfig {
+
+    /// Returns the password policy, which reads the breached passwords file when configured.
+    pub fn policy(&self) -> anyhow::Result<PasswordPolicy> {
+        let breached_passwords = match &self.breached_passwords_file {
+            None => None,
+            Some(path) => {
+                let passwords = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read breached passwords from {}", path.display()))?;
+                Some(BreachedPasswords::from_lines(passwords.lines()))
+            }
+        };
+
+        Ok(PasswordPolicy {
+            min_length: self.min_length,
+            max_length: self.max_length,
+            required_character_classes: self.required_character_classes.clone(),
+            reject_username: self.reject_username,
+            breached_passwords,
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::Duration;
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PasswordResetConfig {
+    /// url is the page linked in password reset emails, which receives the reset token
+    /// as `token` query parameter and lets the user choose a new password.
+    pub url: String,
+
+    /// token_lifetime_seconds determines how long a password reset token can be used after being issued.
+    pub token_lifetime_seconds: u64,
+}
+
+impl Default for PasswordResetConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://127.0.0.1/reset_password".to_string(),
+            token_lifetime_seconds: 3600,
+        }
+    }
+}
+
+impl PasswordResetConfig {
+    pub fn token_lifetime(&self) -> Duration {
+        Duration::seconds(self.token_lifetime_seconds as i64)
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::middleware::rate_limiter::{RateLimit, RateLimitError, RateLimiter, RateLimiters};
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// enabled determines whether requests are rate limited at all.
+    pub enabled: bool,
+
+    /// auth is the rate limit of the authentication endpoints, e.g. login and refresh.
+    pub auth: RateLimit,
+
+    /// read is the rate limit of the endpoints which only read data.
+    pub read: RateLimit,
+
+    /// write is the rate limit of the endpoints which modify data.
+    pub write: RateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auth: RateLimit { capacity: 10, refill_per_second: 0.2 },
+            read: RateLimit { capacity: 100, refill_per_second: 10.0 },
+            write: RateLimit { capacity: 30, refill_per_second: 1.0 },
+        }
+    }
+}
+
+impl RateLimitConfig {
+
+    /// Returns the rate limiters of the route groups, or None when rate limiting is disabled.
+    pub fn rate_limiters(&self) -> Result<Option<RateLimiters>, RateLimitError> {
+        if !self.enabled {
+            return Ok(None)
+        }
+
+        Ok(Some(RateLimiters {
+            auth: Arc::new(RateLimiter::new(self.auth)?),
+            read: Arc::new(RateLimiter::new(self.read)?),
+            write: Arc::new(RateLimiter::new(self.write)?),
+        }))
+    }
+}
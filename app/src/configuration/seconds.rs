@@ -0,0 +1,25 @@
+use anyhow::Context;
+use chrono::{Duration, Utc};
+
+/// Returns the duration of a setting configured in seconds. Rejects durations which are too long to
+/// be represented or to be added to the current time, which would otherwise panic once used.
+pub fn duration_of_seconds(setting: &str, seconds: u64) -> anyhow::Result<Duration> {
+    i64::try_from(seconds).ok()
+        .and_then(Duration::try_seconds)
+        .filter(|duration| Utc::now().checked_add_signed(*duration).is_some())
+        .with_context(|| format!("{setting} of {seconds} seconds is out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use crate::configuration::seconds::duration_of_seconds;
+
+    #[test]
+    fn test_out_of_range_durations_are_rejected() {
+        assert_eq!(duration_of_seconds("setting", 60).unwrap(), Duration::minutes(1));
+        assert!(duration_of_seconds("setting", u64::MAX).is_err());
+        assert!(duration_of_seconds("setting", i64::MAX as u64).is_err());
+        assert!(duration_of_seconds("setting", 1 << 50).is_err());
+    }
+}
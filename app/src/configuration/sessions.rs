@@ -0,0 +1,116 @@
+use chrono::Duration;
+use domain::sessions::session_concurrency::SessionConcurrency;
+use domain::sessions::session_policy::SessionPolicy;
+
+use crate::configuration::seconds::duration_of_seconds;
+
+#[derive(serde::Deserialize, Clone)]
+#[serde(default)]
+pub struct SessionsConfig {
+    /// concurrency determines how many sessions a user can have active at once.
+    pub concurrency: SessionConcurrency,
+
+    /// revocation_check enables verifying that the session of an access token
+    /// has not ended, instead of only checking the expiration of the access token.
+    /// Only sessions ended by this instance are noticed, which are remembered in memory.
+    pub revocation_check: bool,
+
+    /// active_session_cache_seconds enables noticing sessions ended by other instances in the revocation check,
+    /// by querying the database once per session within the given duration. Sessions ended by another
+    /// instance are accepted for at most this duration.
+    pub active_session_cache_seconds: Option<u64>,
+
+    /// expired_access_token_grace_period_seconds enables the detection of expired access tokens.
+    /// Using an access token longer than the grace period after its expiration ends its session.
+    pub expired_access_token_grace_period_seconds: Option<u64>,
+
+    /// refresh_token_reuse_grace_period_seconds determines how long a refresh token may be used
+    /// again after it got rotated, to receive the same new tokens. Allows clients to send
+    /// concurrent refresh requests without ending their session.
+    pub refresh_token_reuse_grace_period_seconds: u64,
+
+    /// access_token_lifetime_seconds determines how long an access token is valid after being issued.
+    pub access_token_lifetime_seconds: u64,
+
+    /// refresh_token_lifetime_seconds determines how long a refresh token is valid after being issued.
+    pub refresh_token_lifetime_seconds: u64,
+
+    /// max_session_age_seconds determines how long a session can last since it was created,
+    /// no refresh token is issued beyond this age.
+    pub max_session_age_seconds: u64,
+
+    /// idle_timeout_seconds enables ending sessions that have not been refreshed for the given duration.
+    pub idle_timeout_seconds: Option<u64>,
+
+    /// mfa_pending_token_lifetime_seconds determines how long users with two-factor authentication
+    /// have to enter their one-time password after entering their password.
+    pub mfa_pending_token_lifetime_seconds: u64,
+
+    /// issuer is set on every issued token and required on every received token.
+    pub issuer: String,
+
+    /// audience is set on every issued token and required on every received token.
+    pub audience: String,
+
+    /// accept_legacy_audience allows received tokens to have the user id as audience, as the tokens
+    /// issued before the audience became configurable did. Can be disabled once those have expired.
+    pub accept_legacy_audience: bool,
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        let policy = SessionPolicy::default();
+
+        Self {
+            concurrency: SessionConcurrency::default(),
+            revocation_check: false,
+            active_session_cache_seconds: None,
+            expired_access_token_grace_period_seconds: None,
+            refresh_token_reuse_grace_period_seconds: 0,
+            access_token_lifetime_seconds: policy.access_token_lifetime.num_seconds() as u64,
+            refresh_token_lifetime_seconds: policy.refresh_token_lifetime.num_seconds() as u64,
+            max_session_age_seconds: policy.max_session_age.num_seconds() as u64,
+            idle_timeout_seconds: None,
+            mfa_pending_token_lifetime_seconds: policy.mfa_pending_token_lifetime.num_seconds() as u64,
+            issuer: policy.issuer,
+            audience: policy.audience,
+            accept_legacy_audience: policy.accept_legacy_audience,
+        }
+    }
+}
+
+impl SessionsConfig {
+    pub fn expired_access_token_grace_period(&self) -> anyhow::Result<Option<Duration>> {
+        self.expired_access_token_grace_period_seconds
+            .map(|seconds| duration_of_seconds("sessions.expired_access_token_grace_period_seconds", seconds))
+            .transpose()
+    }
+
+    pub fn policy(&self) -> anyhow::Result<SessionPolicy> {
+        Ok(SessionPolicy {
+            access_token_lifetime: duration_of_seconds(
+                "sessions.access_token_lifetime_seconds",
+                self.access_token_lifetime_seconds
+            )?,
+            refresh_token_lifetime: duration_of_seconds(
+                "sessions.refresh_token_lifetime_seconds",
+                self.refresh_token_lifetime_seconds
+            )?,
+            max_session_age: duration_of_seconds("sessions.max_session_age_seconds", self.max_session_age_seconds)?,
+            idle_timeout: self.idle_timeout_seconds
+                .map(|seconds| duration_of_seconds("sessions.idle_timeout_seconds", seconds))
+                .transpose()?,
+            refresh_token_reuse_grace_period: duration_of_seconds(
+                "sessions.refresh_token_reuse_grace_period_seconds",
+                self.refresh_token_reuse_grace_period_seconds
+            )?,
+            mfa_pending_token_lifetime: duration_of_seconds(
+                "sessions.mfa_pending_token_lifetime_seconds",
+                self.mfa_pending_token_lifetime_seconds
+            )?,
+            issuer: self.issuer.clone(),
+            audience: self.audience.clone(),
+            accept_legacy_audience: self.accept_legacy_audience,
+        })
+    }
+}
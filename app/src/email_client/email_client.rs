@@ -0,0 +1,19 @@
+use std::fmt::Debug;
+
+use axum::async_trait;
+use serde::Serialize;
+
+/// Email is a plain text email to a single recipient.
+#[derive(Serialize, Clone, Debug)]
+pub struct Email {
+    pub recipient: String,
+    pub subject: String,
+    pub text_body: String,
+}
+
+/// EmailClient delivers emails to their recipients, allowing the way emails are delivered
+/// to be swapped without changing the handlers that send them.
+#[async_trait]
+pub trait EmailClient: Debug + Send + Sync {
+    async fn send_email(&self, email: &Email) -> anyhow::Result<()>;
+}
@@ -0,0 +1,72 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use anyhow::Context;
+use axum::async_trait;
+use serde::Serialize;
+
+use crate::configuration::email_client::EmailOutput;
+use crate::email_client::email_client::{Email, EmailClient};
+use crate::telemetry::spawn_blocking_with_tracing;
+
+/// LocalEmailClient writes emails to stdout or a file instead of delivering them,
+/// for local runs and tests. Every email is written as a single line of JSON.
+#[derive(Debug)]
+pub struct LocalEmailClient {
+    sender_email: String,
+    output: EmailOutput,
+}
+
+#[derive(Serialize)]
+struct SentEmail<'a> {
+    sender: &'a str,
+    #[serde(flatten)]
+    email: &'a Email,
+}
+
+impl LocalEmailClient {
+    pub fn new(sender_email: String, output: EmailOutput) -> Self {
+        Self {
+            sender_email,
+            output,
+        }
+    }
+}
+
+#[async_trait]
+impl EmailClient for LocalEmailClient {
+    #[tracing::instrument(
+    name = "Writing email to local output",
+    skip(self, email),
+    fields(output = ? self.output)
+    )]
+    async fn send_email(&self, email: &Email) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(&SentEmail {
+            sender: &self.sender_email,
+            email,
+        }).context("Failed to serialize email")?;
+        line.push('\n');
+
+        match &self.output {
+            EmailOutput::Stdout => {
+                print!("{}", line);
+                Ok(())
+            }
+            EmailOutput::File(path) => {
+                let path = path.clone();
+
+                // Moving the file access into a different thread, as it blocks
+                spawn_blocking_with_tracing(move || {
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&path)
+                        .and_then(|mut file| file.write_all(line.as_bytes()))
+                        .with_context(|| format!("Failed to append email to {}", path.display()))
+                })
+                    .await
+                    .context("Failed to spawn blocking tokio task to write email")?
+            }
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod email_client;
+pub mod local_email_client;
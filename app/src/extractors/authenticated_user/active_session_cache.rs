@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+/// ActiveSessionCache keeps track of session ids which were found to be active in the database
+/// moments ago, so that the revocation check only queries the database once per session within
+/// the ttl, to notice sessions which were ended by other instances.
+///
+/// Sessions ended by another instance are therefore accepted for at most the ttl.
+#[derive(Debug)]
+pub struct ActiveSessionCache {
+    ttl: Duration,
+    active_sessions: Mutex<ActiveSessions>,
+}
+
+#[derive(Debug)]
+struct ActiveSessions {
+    sessions: HashMap<Uuid, Instant>,
+    evicted_at: Instant,
+}
+
+impl ActiveSessionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            active_sessions: Mutex::new(ActiveSessions {
+                sessions: HashMap::new(),
+                evicted_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn contains(&self, session_id: &Uuid) -> bool {
+        let active_sessions = self.active_sessions.lock()
+            .expect("ActiveSessionCache mutex should not be poisoned");
+
+        active_sessions.sessions
+            .get(session_id)
+            .is_some_and(|cached_at| cached_at.elapsed() < self.ttl)
+    }
+
+    pub fn insert(&self, session_id: Uuid) {
+        let mut active_sessions = self.active_sessions.lock()
+            .expect("ActiveSessionCache mutex should not be poisoned");
+
+        // every active session is inserted once per ttl, so expired entries are only evicted once per ttl
+        if active_sessions.evicted_at.elapsed() >= self.ttl {
+            active_sessions.sessions.retain(|_, cached_at| cached_at.elapsed() < self.ttl);
+            active_sessions.evicted_at = Instant::now();
+        }
+
+        active_sessions.sessions.insert(session_id, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use uuid::Uuid;
+    use crate::extractors::authenticated_user::active_session_cache::ActiveSessionCache;
+
+    #[test]
+    fn test_contains_active_session() {
+        let cache = ActiveSessionCache::new(Duration::from_secs(60));
+        let session_id = Uuid::new_v4();
+
+        assert!(!cache.contains(&session_id));
+        cache.insert(session_id);
+        assert!(cache.contains(&session_id));
+        assert!(!cache.contains(&Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = ActiveSessionCache::new(Duration::from_millis(10));
+        let session_id = Uuid::new_v4();
+
+        cache.insert(session_id);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!cache.contains(&session_id));
+
+        // inserting after the ttl evicts the expired entries
+        cache.insert(Uuid::new_v4());
+        assert_eq!(cache.active_sessions.lock().unwrap().sessions.len(), 1);
+    }
+}
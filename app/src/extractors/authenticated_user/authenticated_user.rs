@@ -1,30 +1,36 @@
 use std::sync::Arc;
 
+use anyhow::Context;
 use axum::{async_trait, RequestPartsExt};
 use axum::extract::{FromRef, FromRequestParts};
 use axum::http::request::Parts;
+use chrono::{Duration, Utc};
 use axum_extra::headers::Authorization;
 use axum_extra::headers::authorization::Bearer;
 use axum_extra::TypedHeader;
 use secrecy::Secret;
-use uuid::Uuid;
 
-use domain::sessions::tokens::AccessToken;
+use domain::security_event::security_event::{SecurityEvent, SecurityEventKind};
+use domain::sessions::tokens::{AccessToken, ServiceAccountAccessToken};
 use domain::sessions::user_session_token::UserSessionToken;
+use domain::user::personal_access_token::PersonalAccessToken;
 use domain::user::user_id::UserId;
-use security::encryption::decryptor::Decryptor;
 use security::token::token::Token;
 
 use crate::app_state::AppState;
+use crate::extractors::authenticated_user::credential::Credential;
+use crate::extractors::authenticated_user::verified_access_token::VerifiedAccessToken;
 use crate::handlers::v1::auth::authentication_error::AuthenticationError;
 use crate::telemetry::TelemetryRecord;
 
+/// AuthenticatedUser is the user of either a session access token or a personal access token
+/// in the Bearer header. Handlers which require a session should use
+/// [`crate::extractors::authenticated_user::session_user::SessionUser`] instead.
 #[derive(Debug, Clone)]
 pub struct AuthenticatedUser {
     pub state: Arc<AppState>,
     pub user_id: UserId,
-    pub session_id: Uuid,
-    pub refresh_token_id: Uuid
+    pub credential: Credential,
 }
 
 #[async_trait]
@@ -54,9 +60,33 @@ impl<S> FromRequestParts<S> for AuthenticatedUser where
             .map_err(|_| AuthenticationError::AccessTokenHeadersInvalid)?;
 
         let app_state: Arc<AppState> = Arc::from_ref(state);
+        let bearer = Secret::new(bearer.token().to_string());
+        if PersonalAccessToken::is_personal_access_token(&bearer) {
+            return authenticate_personal_access_token(app_state, bearer).await
+        }
+
         let cipher = app_state.new_token_encryptor();
-        let access_token = Secret::new(bearer.token().to_string());
-        let access_token: UserSessionToken<AccessToken> = cipher.decrypt(&access_token)?;
+        // the rate limit middleware already verified the token, unless rate limiting is disabled
+        let verified_access_token = match parts.extensions.get::<VerifiedAccessToken>() {
+            Some(verified_access_token) => Ok(verified_access_token.clone()),
+            None => VerifiedAccessToken::verify(&cipher, &bearer),
+        };
+
+        let access_token = match verified_access_token {
+            Ok(VerifiedAccessToken::Session(access_token)) => access_token,
+            Ok(VerifiedAccessToken::ServiceAccount(service_account_token)) => {
+                return authenticate_service_account(app_state, service_account_token)
+            },
+            Err(e) if e.is_expired() => {
+                if let Some(grace_period) = app_state.expired_access_token_grace_period {
+                    let expired_access_token = cipher.decrypt_ignoring_expiration(&bearer)?;
+                    detect_use_of_expired_access_token(&app_state, expired_access_token, grace_period).await?;
+                }
+
+                return Err(AuthenticationError::TokenInvalid)
+            },
+            Err(e) => return Err(e.into()),
+        };
 
         access_token.get_custom_claims().user_id.record_in_telemetry("user_id");
         access_token.get_custom_claims().session_id.record_in_telemetry("session_id");
@@ -67,11 +97,169 @@ impl<S> FromRequestParts<S> for AuthenticatedUser where
             return Err(AuthenticationError::TokenInvalid)
         }
 
+        if let Some(ended_sessions) = &app_state.ended_sessions {
+            // every session ended by this instance is remembered once its ending is committed
+            let session_id = access_token.get_custom_claims().session_id;
+            if ended_sessions.contains(&session_id) {
+                return Err(AuthenticationError::SessionNotActive)
+            }
+
+            // sessions ended by other instances are only noticed by querying the database,
+            // which is done at most once per session within the ttl of the active session cache
+            if let Some(active_sessions) = &app_state.active_sessions {
+                if !active_sessions.contains(&session_id) {
+                    let session_is_active = app_state.db.is_session_active(&session_id)
+                        .await
+                        .context("Failed to query database to check if session is active")?;
+
+                    if !session_is_active {
+                        ended_sessions.insert(session_id);
+                        return Err(AuthenticationError::SessionNotActive)
+                    }
+
+                    active_sessions.insert(session_id);
+                }
+            }
+        }
+
         Ok(AuthenticatedUser {
             state: app_state,
             user_id: access_token.get_custom_claims().user_id.into(),
-            session_id: access_token.get_custom_claims().session_id,
-            refresh_token_id: access_token.get_custom_claims().refresh_token_id
+            credential: Credential::Session {
+                session_id: access_token.get_custom_claims().session_id,
+                refresh_token_id: access_token.get_custom_claims().refresh_token_id,
+            },
         })
     }
-}
\ No newline at end of file
+}
+
+/// Authenticates the service account of an access token issued by the client credentials grant.
+/// These access tokens are not part of a session, so they are valid until they expire.
+fn authenticate_service_account(
+    state: Arc<AppState>,
+    access_token: UserSessionToken<ServiceAccountAccessToken>
+) -> Result<AuthenticatedUser, AuthenticationError> {
+    let token_is_invalid = access_token.get_subject() != ServiceAccountAccessToken::subject()
+        || access_token.expired()
+        || !access_token.active();
+    if token_is_invalid {
+        return Err(AuthenticationError::TokenInvalid)
+    }
+
+    let claims = access_token.get_custom_claims();
+    claims.user_id.record_in_telemetry("user_id");
+
+    Ok(AuthenticatedUser {
+        state,
+        user_id: claims.user_id.into(),
+        credential: Credential::ServiceAccount {
+            client_id: claims.client_id.clone(),
+            scopes: claims.scopes.clone(),
+        },
+    })
+}
+
+/// Authenticates the user of a personal access token, which is looked up by the id in the token.
+/// Unknown, revoked and expired tokens are all rejected as invalid.
+#[tracing::instrument(
+    name = "Authenticating personal access token",
+    skip(state, token),
+    fields(token_id = tracing::field::Empty, user_id = tracing::field::Empty)
+)]
+async fn authenticate_personal_access_token(
+    state: Arc<AppState>,
+    token: Secret<String>
+) -> Result<AuthenticatedUser, AuthenticationError> {
+    let token_id = PersonalAccessToken::id_of(&token)
+        .ok_or(AuthenticationError::TokenInvalid)?;
+    token_id.record_in_telemetry("token_id");
+
+    let personal_access_token: PersonalAccessToken = state.db
+        .get_personal_access_token(&token_id)
+        .await
+        .context("Failed to query database to get personal access token")?
+        .ok_or(AuthenticationError::TokenInvalid)?
+        .try_into()
+        .context("Failed to parse personal access token record")?;
+
+    if !personal_access_token.matches(&token) {
+        return Err(AuthenticationError::TokenInvalid)
+    }
+
+    personal_access_token.user_id.0.record_in_telemetry("user_id");
+
+    Ok(AuthenticatedUser {
+        state,
+        user_id: personal_access_token.user_id,
+        credential: Credential::PersonalAccessToken {
+            token_id: personal_access_token.id,
+            scopes: personal_access_token.scopes,
+        },
+    })
+}
+
+/// Ends the session of an access token that got used longer than the grace period after its
+/// expiration, as that is very likely to be malicious activity. Legitimate clients refresh their
+/// tokens before they expire, a small grace period is allowed to account for clock skew and
+/// requests that were in flight.
+#[tracing::instrument(
+    name = "Detecting use of expired access token",
+    skip(state, access_token, grace_period),
+    fields(
+        user_id = %access_token.get_custom_claims().user_id,
+        session_id = %access_token.get_custom_claims().session_id,
+    )
+)]
+async fn detect_use_of_expired_access_token(
+    state: &AppState,
+    access_token: UserSessionToken<AccessToken>,
+    grace_period: Duration
+) -> Result<(), AuthenticationError> {
+    if *access_token.get_expiration() + grace_period > Utc::now() {
+        return Ok(())
+    }
+
+    let session = state.db
+        .get_active_session_by_id(&access_token.get_custom_claims().session_id)
+        .await
+        .context("Failed to query database to get active session")?;
+
+    // the session may already have ended, e.g. by logging out, after which
+    // its expired access tokens are no longer a threat.
+    let Some(session) = session else {
+        return Ok(())
+    };
+
+    tracing::warn!(
+        device = %session.device(),
+        "Expired access token was used beyond the grace period, ending its session"
+    );
+
+    let session_id = *session.id();
+    let event = SecurityEvent::new(
+        *session.user_id(),
+        Some(*session.id()),
+        SecurityEventKind::UsedExpiredAccessToken {
+            token_id: *access_token.get_id()
+        }
+    );
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to end session of expired access token")?;
+
+    transaction.save_just_ended_session(&session.end_by_use_of_expired_access_token())
+        .await
+        .context("Failed to save session ended by use of expired access token")?;
+
+    transaction.save_security_event(&event)
+        .await
+        .context("Failed to save security event of expired access token")?;
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing session ended by use of expired access token")?;
+
+    state.remember_ended_sessions([session_id]);
+    Ok(())
+}
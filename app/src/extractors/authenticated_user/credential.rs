@@ -0,0 +1,36 @@
+use uuid::Uuid;
+
+use domain::user::personal_access_token::Scope;
+
+/// Credential is what the user authenticated with.
+#[derive(Debug, Clone)]
+pub enum Credential {
+
+    /// Session is an access token of a session, which carries every scope.
+    Session {
+        session_id: Uuid,
+        refresh_token_id: Uuid,
+    },
+
+    /// PersonalAccessToken is a personal access token, which only carries its own scopes.
+    PersonalAccessToken {
+        token_id: Uuid,
+        scopes: Vec<Scope>,
+    },
+
+    /// ServiceAccount is an access token of a service account, which only carries the scopes it was issued with.
+    ServiceAccount {
+        client_id: String,
+        scopes: Vec<Scope>,
+    },
+}
+
+impl Credential {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        match self {
+            Credential::Session { .. } => true,
+            Credential::PersonalAccessToken { scopes, .. }
+            | Credential::ServiceAccount { scopes, .. } => scopes.contains(&scope),
+        }
+    }
+}
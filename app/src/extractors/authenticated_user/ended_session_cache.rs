@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+/// EndedSessionCache keeps track of session ids which are known to have ended,
+/// so that requests made with access tokens of those sessions can be rejected
+/// without querying the database.
+///
+/// Only ended sessions are cached, as a session can never become active again
+/// after it has ended. Entries are kept for the lifetime of an access token,
+/// after which any access token of the session will have expired anyway.
+#[derive(Debug)]
+pub struct EndedSessionCache {
+    ttl: Duration,
+    ended_sessions: Mutex<HashMap<Uuid, Instant>>
+}
+
+impl EndedSessionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            ended_sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn contains(&self, session_id: &Uuid) -> bool {
+        let ended_sessions = self.ended_sessions.lock()
+            .expect("EndedSessionCache mutex should not be poisoned");
+
+        ended_sessions
+            .get(session_id)
+            .is_some_and(|cached_at| cached_at.elapsed() < self.ttl)
+    }
+
+    pub fn insert(&self, session_id: Uuid) {
+        let mut ended_sessions = self.ended_sessions.lock()
+            .expect("EndedSessionCache mutex should not be poisoned");
+
+        // sessions do not end often, so its fine to evict expired entries on every insert
+        ended_sessions.retain(|_, cached_at| cached_at.elapsed() < self.ttl);
+        ended_sessions.insert(session_id, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use uuid::Uuid;
+    use crate::extractors::authenticated_user::ended_session_cache::EndedSessionCache;
+
+    #[test]
+    fn test_contains_ended_session() {
+        let cache = EndedSessionCache::new(Duration::from_secs(60));
+        let session_id = Uuid::new_v4();
+
+        assert!(!cache.contains(&session_id));
+        cache.insert(session_id);
+        assert!(cache.contains(&session_id));
+        assert!(!cache.contains(&Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = EndedSessionCache::new(Duration::from_millis(10));
+        let session_id = Uuid::new_v4();
+
+        cache.insert(session_id);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!cache.contains(&session_id));
+
+        // inserting evicts the expired entries
+        cache.insert(Uuid::new_v4());
+        assert_eq!(cache.ended_sessions.lock().unwrap().len(), 1);
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::Context;
-use crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser;
+use crate::extractors::authenticated_user::session_user::SessionUser;
 use crate::handlers::v1::auth::authentication_error::AuthenticationError;
 
-impl AuthenticatedUser {
+impl SessionUser {
     pub async fn logout(self) -> Result<(), AuthenticationError> {
         let session = self.state.db
             .get_active_session_by_id(&self.session_id)
@@ -23,6 +23,35 @@ impl AuthenticatedUser {
             .await
             .context("Failed to commit transaction containing updated database")?;
 
+        self.state.remember_ended_sessions([*session.id()]);
+        Ok(())
+    }
+}
+
+impl SessionUser {
+    pub async fn logout_everywhere(self) -> Result<(), AuthenticationError> {
+        let sessions = self.state.db
+            .get_active_sessions_by_user_id(self.user_id)
+            .await
+            .context("Failed to query database to get active sessions")?;
+
+        let mut transaction = self.state.db.new_transaction()
+            .await
+            .context("Failed to begin a transaction to store ended user sessions")?;
+
+        let mut ended_session_ids = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            ended_session_ids.push(*session.id());
+            transaction.save_just_ended_session(&session.end_by_user_logout_everywhere())
+                .await
+                .context("Failed to save ended user session to database")?;
+        }
+
+        transaction.commit()
+            .await
+            .context("Failed to commit transaction containing ended user sessions")?;
+
+        self.state.remember_ended_sessions(ended_session_ids);
         Ok(())
     }
 }
@@ -1,2 +1,7 @@
+pub mod active_session_cache;
 pub mod authenticated_user;
+pub mod credential;
+pub mod ended_session_cache;
 pub mod logout;
+pub mod session_user;
+pub mod verified_access_token;
\ No newline at end of file
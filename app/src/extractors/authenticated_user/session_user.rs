@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use uuid::Uuid;
+
+use domain::user::user_id::UserId;
+
+use crate::app_state::AppState;
+use crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser;
+use crate::extractors::authenticated_user::credential::Credential;
+use crate::handlers::v1::auth::authentication_error::AuthenticationError;
+
+/// SessionUser is the user of a session access token. Personal access tokens are rejected, as they
+/// must not be able to manage the sessions, credentials or personal access tokens of the user.
+#[derive(Debug, Clone)]
+pub struct SessionUser {
+    pub state: Arc<AppState>,
+    pub user_id: UserId,
+    pub session_id: Uuid,
+    pub refresh_token_id: Uuid,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for SessionUser where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthenticationError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+
+        let Credential::Session { session_id, refresh_token_id } = authenticated_user.credential else {
+            return Err(AuthenticationError::SessionRequired)
+        };
+
+        Ok(SessionUser {
+            state: authenticated_user.state,
+            user_id: authenticated_user.user_id,
+            session_id,
+            refresh_token_id,
+        })
+    }
+}
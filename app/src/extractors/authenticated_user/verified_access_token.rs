@@ -0,0 +1,44 @@
+use secrecy::Secret;
+use uuid::Uuid;
+
+use domain::sessions::tokens::{AccessToken, ServiceAccountAccessToken};
+use domain::sessions::user_session_token::UserSessionToken;
+use security::encryption::decryptor::Decryptor;
+use security::token::token::Token;
+
+use crate::session_token_encryptor::{SessionTokenDecryptionError, SessionTokenEncryptor};
+
+/// VerifiedAccessToken is the access token in the Bearer header after it has been decrypted and verified,
+/// either of a session or of a service account. The rate limit middleware stores it in the extensions
+/// of the request, such that [`crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser`]
+/// does not decrypt the same token again.
+#[derive(Debug, Clone)]
+pub enum VerifiedAccessToken {
+    Session(UserSessionToken<AccessToken>),
+    ServiceAccount(UserSessionToken<ServiceAccountAccessToken>),
+}
+
+impl VerifiedAccessToken {
+
+    /// Decrypts the access token as the access token of a session, or else as the access token of a
+    /// service account, which carries other claims. Expired tokens are not decrypted a second time.
+    pub fn verify(
+        cipher: &SessionTokenEncryptor,
+        bearer: &Secret<String>
+    ) -> Result<Self, SessionTokenDecryptionError> {
+        match cipher.decrypt(bearer) {
+            Ok(access_token) => Ok(VerifiedAccessToken::Session(access_token)),
+            Err(e) if e.is_expired() => Err(e),
+            Err(e) => cipher.decrypt(bearer)
+                .map(VerifiedAccessToken::ServiceAccount)
+                .map_err(|_| e),
+        }
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            VerifiedAccessToken::Session(access_token) => access_token.get_custom_claims().user_id,
+            VerifiedAccessToken::ServiceAccount(access_token) => access_token.get_custom_claims().user_id,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRef, FromRequestParts};
+use axum::http::header::USER_AGENT;
+use axum::http::request::Parts;
+
+use domain::sessions::device::Device;
+use domain::sessions::user_agent::UserAgent;
+
+use crate::app_state::AppState;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// ClientDevice is the device of the client that sent the request, as described by its
+/// `User-Agent` header and its IP address. Never rejects the request, unknown values are left empty.
+#[derive(Debug, Clone)]
+pub struct ClientDevice(pub Device);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientDevice where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let app_state: Arc<AppState> = Arc::from_ref(state);
+
+        let user_agent = parts.headers.get(USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .and_then(|user_agent| UserAgent::new(user_agent).ok());
+
+        let ip_address = client_ip_address_of(parts, &app_state.trusted_proxies);
+
+        Ok(ClientDevice(Device::new(user_agent, ip_address)))
+    }
+}
+
+/// Returns the IP address of the client that sent the request,
+/// or None when the address of the peer is unknown.
+pub fn client_ip_address_of(parts: &Parts, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer_address = parts.extensions.get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| address.ip());
+
+    let forwarded_for = parts.headers.get_all(X_FORWARDED_FOR).iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<&str>>()
+        .join(",");
+
+    peer_address
+        .map(|peer_address| client_ip_address(peer_address, &forwarded_for, trusted_proxies))
+}
+
+/// Returns the IP address of the client. The `X-Forwarded-For` header is only honoured when the
+/// request is received from a trusted proxy, as anyone can set it. Each proxy appends the address
+/// it received the request from, therefor the header is read from right to left up until the first
+/// address that is not a trusted proxy.
+fn client_ip_address(peer_address: IpAddr, forwarded_for: &str, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let mut client_address = peer_address;
+    if !trusted_proxies.contains(&client_address) {
+        return client_address
+    }
+
+    for forwarded_address in forwarded_for.rsplit(',').map(str::trim).filter(|address| !address.is_empty()) {
+        let Ok(forwarded_address) = forwarded_address.parse::<IpAddr>() else {
+            break
+        };
+
+        client_address = forwarded_address;
+        if !trusted_proxies.contains(&client_address) {
+            break
+        }
+    }
+
+    client_address
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use crate::extractors::client_device::client_device::client_ip_address;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().expect("Failed to parse ip address")
+    }
+
+    #[test]
+    fn test_forwarded_for_is_ignored_without_trusted_proxy() {
+        let got = client_ip_address(ip("10.0.0.5"), "1.2.3.4", &[]);
+        assert_eq!(got, ip("10.0.0.5"));
+
+        let got = client_ip_address(ip("10.0.0.5"), "1.2.3.4", &[ip("10.0.0.1")]);
+        assert_eq!(got, ip("10.0.0.5"));
+    }
+
+    #[test]
+    fn test_forwarded_for_is_honoured_from_trusted_proxy() {
+        let trusted_proxies = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+        let got = client_ip_address(ip("10.0.0.1"), "1.2.3.4", &trusted_proxies);
+        assert_eq!(got, ip("1.2.3.4"));
+
+        // addresses added by the client itself are not trusted
+        let got = client_ip_address(ip("10.0.0.1"), "6.6.6.6, 1.2.3.4, 10.0.0.2", &trusted_proxies);
+        assert_eq!(got, ip("1.2.3.4"));
+
+        let got = client_ip_address(ip("10.0.0.1"), "", &trusted_proxies);
+        assert_eq!(got, ip("10.0.0.1"));
+
+        let got = client_ip_address(ip("10.0.0.1"), "not an ip, 10.0.0.2", &trusted_proxies);
+        assert_eq!(got, ip("10.0.0.2"));
+
+        let got = client_ip_address(ip("10.0.0.1"), "2001:db8::1", &trusted_proxies);
+        assert_eq!(got, ip("2001:db8::1"));
+    }
+}
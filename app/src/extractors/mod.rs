@@ -1,3 +1,4 @@
 pub mod authenticated_user;
 pub mod admin;
+pub mod client_device;
 pub mod user;
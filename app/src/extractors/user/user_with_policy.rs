@@ -4,20 +4,20 @@ use anyhow::Context;
 use axum::extract::{FromRef, FromRequest, FromRequestParts};
 use axum::http::request::Parts;
 use axum::{async_trait, RequestExt};
-use uuid::Uuid;
 
 use domain::user::user_id::UserId;
 
 use crate::app_state::AppState;
 use crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser;
+use crate::extractors::authenticated_user::credential::Credential;
 use crate::handlers::v1::auth::authentication_error::AuthenticationError;
 use crate::policy::policy::Policy;
+use crate::policy::policy_authorization_error::PolicyRejectionError;
 
 pub struct UserWithPolicy<T: Policy> {
     pub policy: T,
     pub user_id: UserId,
-    pub session_id: Uuid,
-    pub refresh_token_id: Uuid,
+    pub credential: Credential,
 }
 
 #[async_trait]
@@ -33,13 +33,18 @@ impl <S, P> FromRequestParts<S> for UserWithPolicy<P>
         let mut mutable_parts = parts.clone();
         let authenticated_user = AuthenticatedUser::from_request_parts(&mut mutable_parts, state).await?;
 
+        // checked before the policy, so a personal access token never grants more than its
+        // scopes allow, even when its owner could perform the operation
+        if !authenticated_user.credential.has_scope(P::REQUIRED_SCOPE) {
+            return Err(PolicyRejectionError::Forbidden.into())
+        }
+
         let policy = P::new(authenticated_user.state, authenticated_user.user_id).await?;
 
         Ok(Self {
             policy,
             user_id: authenticated_user.user_id,
-            session_id: authenticated_user.session_id,
-            refresh_token_id: authenticated_user.refresh_token_id,
+            credential: authenticated_user.credential,
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use std::fmt::Debug;
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use domain::user::login_lockout::{FailedLogins, LockoutPolicy};
+
+/// FailedLoginStore keeps track of the failed login attempts per username,
+/// from which is determined whether an account is locked.
+#[async_trait]
+pub trait FailedLoginStore: Debug + Send + Sync {
+
+    /// Returns the failed logins of the username, or None when there are none.
+    async fn get(&self, username: &str) -> anyhow::Result<Option<FailedLogins>>;
+
+    /// Records a failed login attempt of the username at the given time according to the policy,
+    /// returns the failed logins including the attempt.
+    async fn record_failure(
+        &self,
+        username: &str,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<FailedLogins>;
+
+    /// Forgets the failed logins of the username, which unlocks the account.
+    async fn clear(&self, username: &str) -> anyhow::Result<()>;
+}
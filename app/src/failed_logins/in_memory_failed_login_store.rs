@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use domain::user::login_lockout::{FailedLogins, LockoutPolicy};
+
+use crate::failed_logins::failed_login_store::FailedLoginStore;
+
+/// InMemoryFailedLoginStore keeps the failed logins in the memory of this instance,
+/// which means every instance locks accounts independently of the others.
+#[derive(Debug, Default)]
+pub struct InMemoryFailedLoginStore {
+    failed_logins: Mutex<HashMap<String, FailedLogins>>,
+}
+
+impl InMemoryFailedLoginStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FailedLoginStore for InMemoryFailedLoginStore {
+    async fn get(&self, username: &str) -> anyhow::Result<Option<FailedLogins>> {
+        let failed_logins = self.failed_logins.lock()
+            .expect("InMemoryFailedLoginStore mutex should not be poisoned");
+
+        Ok(failed_logins.get(username).cloned())
+    }
+
+    async fn record_failure(
+        &self,
+        username: &str,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<FailedLogins> {
+        let mut failed_logins = self.failed_logins.lock()
+            .expect("InMemoryFailedLoginStore mutex should not be poisoned");
+
+        // anyone can fail to login with any username, so entries which no longer
+        // count towards a lockout are evicted on every failure
+        failed_logins.retain(|_, entry| !policy.is_forgotten(entry, now));
+
+        let previous = failed_logins.remove(username);
+        let updated = policy.record_failure(previous, now);
+        failed_logins.insert(username.to_string(), updated.clone());
+
+        Ok(updated)
+    }
+
+    async fn clear(&self, username: &str) -> anyhow::Result<()> {
+        let mut failed_logins = self.failed_logins.lock()
+            .expect("InMemoryFailedLoginStore mutex should not be poisoned");
+
+        failed_logins.remove(username);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use domain::user::login_lockout::LockoutPolicy;
+
+    use crate::failed_logins::failed_login_store::FailedLoginStore;
+    use crate::failed_logins::in_memory_failed_login_store::InMemoryFailedLoginStore;
+
+    #[tokio::test]
+    async fn test_record_failure_and_clear() {
+        let store = InMemoryFailedLoginStore::new();
+        let policy = LockoutPolicy::default();
+        let now = Utc::now();
+
+        for _ in 0..policy.max_failed_attempts {
+            store.record_failure("username", &policy, now).await.unwrap();
+        }
+
+        let failed_logins = store.get("username").await.unwrap().unwrap();
+        assert!(failed_logins.locked_for(now).is_some());
+        assert_eq!(store.get("other_username").await.unwrap(), None);
+
+        store.clear("username").await.unwrap();
+        assert_eq!(store.get("username").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_entries_which_no_longer_count_are_evicted() {
+        let store = InMemoryFailedLoginStore::new();
+        let policy = LockoutPolicy::default();
+        let now = Utc::now();
+
+        store.record_failure("username", &policy, now).await.unwrap();
+        store.record_failure("other_username", &policy, now + policy.window).await.unwrap();
+
+        assert_eq!(store.get("username").await.unwrap(), None);
+        assert_eq!(store.failed_logins.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod failed_login_store;
+pub mod in_memory_failed_login_store;
+pub mod postgres_failed_login_store;
@@ -0,0 +1,79 @@
+use anyhow::Context;
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use domain::user::login_lockout::{FailedLogins, LockoutPolicy};
+
+use crate::failed_logins::failed_login_store::FailedLoginStore;
+use crate::queries::database::Database;
+use crate::queries::records::failed_logins_record::FailedLoginsRecord;
+
+/// PostgresFailedLoginStore keeps the failed logins in Postgres,
+/// so that accounts are locked across all instances of the application.
+#[derive(Debug)]
+pub struct PostgresFailedLoginStore {
+    db: Database,
+}
+
+impl PostgresFailedLoginStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl FailedLoginStore for PostgresFailedLoginStore {
+    async fn get(&self, username: &str) -> anyhow::Result<Option<FailedLogins>> {
+        let record = self.db.get_failed_logins(username)
+            .await
+            .context("Failed to get failed logins from Postgres")?;
+
+        Ok(record.map(FailedLogins::from))
+    }
+
+    async fn record_failure(
+        &self,
+        username: &str,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<FailedLogins> {
+        let mut transaction = self.db.new_transaction()
+            .await
+            .context("Failed to start a Postgres transaction")?;
+
+        // anyone can fail to login with any username, so rows which no longer
+        // count towards a lockout are deleted on every failure
+        transaction.delete_forgotten_failed_logins(policy, now)
+            .await
+            .context("Failed to delete forgotten failed logins from Postgres")?;
+
+        let previous = transaction.get_failed_logins_for_update(username, now)
+            .await
+            .context("Failed to get failed logins from Postgres")?;
+
+        let updated = policy.record_failure(Some(FailedLogins::from(previous)), now);
+        transaction.save_failed_logins(&FailedLoginsRecord::new(username, &updated))
+            .await
+            .context("Failed to save failed logins to Postgres")?;
+
+        transaction.commit()
+            .await
+            .context("Failed to commit transaction")?;
+
+        Ok(updated)
+    }
+
+    async fn clear(&self, username: &str) -> anyhow::Result<()> {
+        let mut transaction = self.db.new_transaction()
+            .await
+            .context("Failed to start a Postgres transaction")?;
+
+        transaction.delete_failed_logins(username)
+            .await
+            .context("Failed to delete failed logins from Postgres")?;
+
+        transaction.commit()
+            .await
+            .context("Failed to commit transaction")
+    }
+}
@@ -1,7 +1,11 @@
 use std::fmt::{Debug, Formatter};
+use axum::http::header::RETRY_AFTER;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
 use sqlx::Error;
+use domain::user::password::PasswordViolation;
 use lib_util::errors::errors::format_error_chain;
 use crate::policy::policy_authorization_error::PolicyRejectionError;
 
@@ -17,7 +21,25 @@ pub enum HandlerError {
     InternalError(#[from] anyhow::Error),
     
     #[error("Not Found")]
-    NotFound
+    NotFound,
+
+    #[error("Forbidden")]
+    Forbidden,
+
+    #[error("Conflict")]
+    Conflict,
+
+    #[error("Unprocessable Entity")]
+    UnprocessableEntity,
+
+    /// PasswordRejected is returned when a new password does not meet the password policy.
+    #[error("Password does not meet the password policy")]
+    PasswordRejected(Vec<PasswordViolation>),
+
+    /// AccountLocked is returned when the password of a user is to be verified
+    /// while their account is locked after too many failed attempts.
+    #[error("Account is locked after too many failed attempts")]
+    AccountLocked { retry_after: chrono::Duration },
 }
 
 impl Debug for HandlerError {
@@ -34,7 +56,19 @@ impl IntoResponse for HandlerError {
                 // todo log internal error prior to returning a response
                 StatusCode::INTERNAL_SERVER_ERROR.into_response()
             },
-            HandlerError::NotFound => StatusCode::NOT_FOUND.into_response()
+            HandlerError::NotFound => StatusCode::NOT_FOUND.into_response(),
+            HandlerError::Forbidden => StatusCode::FORBIDDEN.into_response(),
+            HandlerError::Conflict => StatusCode::CONFLICT.into_response(),
+            HandlerError::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY.into_response(),
+            HandlerError::PasswordRejected(violations) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "violations": violations })),
+            ).into_response(),
+            HandlerError::AccountLocked { retry_after } => {
+                // rounded up, so clients do not retry while the account is still locked
+                let retry_after_seconds = (retry_after.num_milliseconds() + 999) / 1000;
+                (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after_seconds.to_string())]).into_response()
+            },
         }
     }
 }
\ No newline at end of file
@@ -1,3 +1,3 @@
-
 pub mod v1;
+pub mod well_known;
 mod error;
@@ -1,10 +1,12 @@
 use std::fmt::{Debug, Formatter};
 use axum::extract::rejection::JsonRejection;
+use axum::http::header::RETRY_AFTER;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use infrastructure::paseto::paseto_token_encryptor::LocalPasetoV4DecryptionError;
+use chrono::Duration;
 use lib_util::errors::errors::format_error_chain;
 use crate::policy::policy_authorization_error::PolicyRejectionError;
+use crate::session_token_encryptor::SessionTokenDecryptionError;
 use crate::util::handlers::InternalErrorResponse;
 
 pub type AuthenticationResult<T> = Result<T, AuthenticationError>;
@@ -20,11 +22,24 @@ pub enum AuthenticationError {
     #[error("Received token was invalid")]
     TokenInvalid,
 
+    #[error("Received one-time password is incorrect or has already been used")]
+    OneTimePasswordInvalid,
+
+    /// AccountLocked is returned for usernames with too many failed login attempts, regardless
+    /// of whether the user exists, so it does not reveal which usernames exist.
+    #[error("Account is locked after too many failed login attempts")]
+    AccountLocked { retry_after: Duration },
+
     #[error("Session for the token is not active")]
     SessionNotActive,
 
+    /// SessionRequired is returned when a personal access token is used for an
+    /// endpoint which can only be used with the access token of a session.
+    #[error("Endpoint requires the access token of a session")]
+    SessionRequired,
+
     #[error(transparent)]
-    TokenDecryptionError(#[from] LocalPasetoV4DecryptionError),
+    TokenDecryptionError(#[from] SessionTokenDecryptionError),
 
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
@@ -55,13 +70,18 @@ impl IntoResponse for AuthenticationError {
             | AuthenticationError::SessionNotActive
             | AuthenticationError::CredentialsInvalid
             | AuthenticationError::TokenInvalid
+            | AuthenticationError::OneTimePasswordInvalid
             | AuthenticationError::UnAuthorized
             | AuthenticationError::AuthenticatedUserIsNotOfTypeAdmin => StatusCode::UNAUTHORIZED.into_response(),
-            AuthenticationError::TokenDecryptionError(e) => match e {
-                LocalPasetoV4DecryptionError::TokenNotYetActive => {
-                    StatusCode::UNAUTHORIZED.into_response()
-                }
-                _ => InternalErrorResponse::from(tracing::Span::current()).into_response(),
+            AuthenticationError::SessionRequired => StatusCode::FORBIDDEN.into_response(),
+            AuthenticationError::AccountLocked { retry_after } => {
+                // rounded up, so clients do not retry while the account is still locked
+                let retry_after_seconds = (retry_after.num_milliseconds() + 999) / 1000;
+                (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after_seconds.to_string())]).into_response()
+            },
+            AuthenticationError::TokenDecryptionError(e) => match e.is_invalid_token() {
+                true => StatusCode::UNAUTHORIZED.into_response(),
+                false => InternalErrorResponse::from(tracing::Span::current()).into_response(),
             },
             AuthenticationError::UnexpectedError(_) => {
                 // todo verify if this actually works properly
@@ -9,16 +9,21 @@ use password_hash::SaltString;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 use tokio::task::JoinError;
+use uuid::Uuid;
 
+use domain::sessions::device::Device;
 use domain::sessions::state::newly_created::NewlyCreated;
+use domain::sessions::tokens::MfaPendingToken;
 use domain::sessions::user_session::UserSession;
 use domain::user::password::{MatchError, MatchResult, Password};
 use security::encryption::encryptor::Encryptor;
 
 use crate::app_state::AppState;
+use crate::extractors::client_device::client_device::ClientDevice;
 use crate::handlers::v1::auth::authentication_error::{
     AuthenticationError, AuthenticationResult,
 };
+use crate::queries::transaction::_transaction::Transaction;
 use crate::telemetry::spawn_blocking_with_tracing;
 
 #[derive(Deserialize)]
@@ -35,12 +40,21 @@ pub enum LoginResponse {
         refresh_token: String,
         refresh_token_expiration: DateTime<Utc>,
     },
+
+    /// The password of a user with two-factor authentication was correct, the mfa token is
+    /// exchanged for the session tokens at `/v1/auth/login/totp` together with a one-time password,
+    /// or at `/v1/auth/login/recovery_code` together with a recovery code.
+    MfaRequired {
+        mfa_token: String,
+        mfa_token_expiration: DateTime<Utc>,
+    },
 }
 
 impl IntoResponse for LoginResponse {
     fn into_response(self) -> Response {
         match self {
-            LoginResponse::UserLoggedInSuccessfully { .. } => {
+            LoginResponse::UserLoggedInSuccessfully { .. }
+            | LoginResponse::MfaRequired { .. } => {
                 (StatusCode::OK, Json(self)).into_response()
             }
         }
@@ -49,13 +63,14 @@ impl IntoResponse for LoginResponse {
 
 #[tracing::instrument(
     name = "Received user login request",
-    skip(state, credentials),
+    skip(state, device, credentials),
     fields(
         user_id = tracing::field::Empty
     ),
 )]
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    ClientDevice(device): ClientDevice,
     credentials: Json<LoginRequestBody>,
 ) -> AuthenticationResult<LoginResponse> {
     // We Always need to verify the submitted password with a password hash,
@@ -63,9 +78,18 @@ pub async fn login(
     // user enumeration vulnerabilities.
     // See: https://en.wikipedia.org/wiki/Timing_attack
     // and https://owasp.org/www-project-web-security-testing-guide/latest/4-Web_Application_Security_Testing/03-Identity_Management_Testing/04-Testing_for_Account_Enumeration_and_Guessable_User_Account
+    // Locked accounts are rejected before verifying the password, so the password
+    // cannot be guessed while locked.
+    let failed_logins = state.failed_logins.get(&credentials.username)
+        .await
+        .context("Failed to get failed logins of username")?;
+    if let Some(retry_after) = failed_logins.as_ref().and_then(|f| f.locked_for(Utc::now())) {
+        return Err(AuthenticationError::AccountLocked { retry_after })
+    }
+
     let mut user_id = None;
-    let mut expected_user_password =
-        get_dummy_hash().context("Failed to create default password")?;
+    let mut expected_user_password = Password::try_from(state.dummy_password_hash.expose_secret().as_str())
+        .context("Failed to parse dummy password hash")?;
 
     // todo move into queries crate
     let optional_user_credentials = state.db.get_user_credentials(&credentials.username)
@@ -83,35 +107,102 @@ pub async fn login(
         .context("Failed to spawn blocking tokio task to verify password")?
         .context("Failed to submitted password with expected password")?;
 
-    let user_id = user_id.ok_or(AuthenticationError::CredentialsInvalid)?;
+    // failed attempts of unknown usernames are recorded as well, so they are locked alike
+    let password_matches = !matches!(match_result, MatchResult::DoesNotMatch);
+    let Some(user_id) = user_id.filter(|_| password_matches) else {
+        state.failed_logins.record_failure(&credentials.username, &state.lockout_policy, Utc::now())
+            .await
+            .context("Failed to record failed login")?;
+
+        return Err(AuthenticationError::CredentialsInvalid)
+    };
+
+    // the account might have been locked by concurrent attempts while the password was verified,
+    // so the lockout is checked once more before the failed logins are cleared
+    let failed_logins = state.failed_logins.get(&credentials.username)
+        .await
+        .context("Failed to get failed logins of username")?;
+    if let Some(retry_after) = failed_logins.as_ref().and_then(|f| f.locked_for(Utc::now())) {
+        return Err(AuthenticationError::AccountLocked { retry_after })
+    }
+
+    if failed_logins.is_some() {
+        state.failed_logins.clear(&credentials.username)
+            .await
+            .context("Failed to clear failed logins of username")?;
+    }
+
     let mut transaction = state
         .db
         .new_transaction()
         .await
         .context("Failed to start a Postgres transaction")?;
 
-    match match_result {
-        MatchResult::DoesNotMatch => return Err(AuthenticationError::CredentialsInvalid),
-        MatchResult::Matches => {}
-        MatchResult::MatchesButSchemeOutdated => {
-            // TODO: move this code in its own function
-            // TODO: shouldn't fail entire login operation if we cannot update the password, better to just log it
-            let password = credentials.password.clone();
-            let hash_result = spawn_blocking_with_tracing(move || {
-                let salt_string = salt();
-                return Password::new(password, &salt_string);
-            })
+    if let MatchResult::MatchesButSchemeOutdated = match_result {
+        // TODO: move this code in its own function
+        // TODO: shouldn't fail entire login operation if we cannot update the password, better to just log it
+        let password = credentials.password.clone();
+        let hash_result = spawn_blocking_with_tracing(move || {
+            let salt_string = salt();
+            return Password::new(password, &salt_string);
+        })
+        .await
+        .context("Failed to spawn tokio blocking task to rehash outdated password")?
+        .context("Failed hash the password of user")?;
+
+        transaction.update_user_password(user_id.clone(), hash_result)
             .await
-            .context("Failed to spawn tokio blocking task to rehash outdated password")?
-            .context("Failed hash the password of user")?;
+            .context("Failed to password of user in Postgres")?;
+    }
 
-            transaction.update_user_password(user_id.clone(), hash_result)
-                .await
-                .context("Failed to password of user in Postgres")?;
-        }
-    };
+    let totp_credential = state.db.get_totp_credential(&user_id)
+        .await
+        .context("Failed to get TOTP credential of user")?;
+
+    // users with two-factor authentication only receive a session after entering their one-time password
+    if totp_credential.is_some_and(|credential| credential.confirmed_at.is_some()) {
+        let mfa_token = MfaPendingToken { user_id }.issue(&state.session_policy);
+        let cipher = state.new_token_encryptor();
+        let encrypted_mfa_token = spawn_blocking_with_tracing(move || cipher.encrypt(&mfa_token))
+            .await
+            .context("Failed to spawn blocking tokio task to encrypt mfa token")?
+            .context("Failed to encrypt mfa token")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit transaction")?;
 
-    let new_session = UserSession::<NewlyCreated>::new(user_id.into());
+        return Ok(LoginResponse::MfaRequired {
+            mfa_token: encrypted_mfa_token.token.expose_secret().clone(),
+            mfa_token_expiration: encrypted_mfa_token.expires_at,
+        })
+    }
+
+    start_session(&state, transaction, user_id, device).await
+}
+
+/// Starts a new session for the user who just signed in, ending older sessions
+/// when the user has reached the maximum amount of sessions.
+pub async fn start_session(
+    state: &AppState,
+    mut transaction: Transaction,
+    user_id: Uuid,
+    device: Device,
+) -> AuthenticationResult<LoginResponse> {
+    let active_sessions = transaction.get_active_sessions_by_user_id_for_update(user_id.into())
+        .await
+        .context("Failed to get active sessions of user")?;
+
+    let mut ended_session_ids = vec![];
+    for session in state.session_concurrency.sessions_to_end(active_sessions) {
+        ended_session_ids.push(*session.id());
+        transaction.save_just_ended_session(&session.end_by_sign_in_on_other_device())
+            .await
+            .context("Failed to save session ended by signing in on other device")?;
+    }
+
+    let new_session = UserSession::<NewlyCreated>::new(user_id.into(), device, &state.session_policy);
     transaction.save_newly_created_user_session(&new_session)
         .await
         .context("Failed to save new user session to the database")?;
@@ -138,6 +229,7 @@ pub async fn login(
         .await
         .context("Failed to commit transaction")?;
 
+    state.remember_ended_sessions(ended_session_ids);
     Ok(LoginResponse::UserLoggedInSuccessfully {
         // we are removing 30 seconds from the actual expiration time, to increase the
         // likelihood of the token refreshing the tokens on time
@@ -148,10 +240,6 @@ pub async fn login(
     })
 }
 
-fn get_dummy_hash() -> anyhow::Result<Password> {
-    Ok(Password::try_from("$argon2id$v=19$m=15000,t=2,p=1$gZiV/M1gPc22ElAH/Jh1Hw$CWOrkoo7oJBQ/iyh7uJ0LO2aLEfrHwTWllSAxT0zRno")?)
-}
-
 #[tracing::instrument(
     name = "Comparing expected password with submitted password",
     skip(expected_password, submitted_password)
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::State;
+use axum::Json;
+use chrono::Utc;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use domain::user::password::MatchError;
+use domain::user::recovery_code::RecoveryCode;
+
+use crate::app_state::AppState;
+use crate::extractors::client_device::client_device::ClientDevice;
+use crate::handlers::v1::auth::authentication_error::{AuthenticationError, AuthenticationResult};
+use crate::handlers::v1::auth::login::login::{start_session, LoginResponse};
+use crate::handlers::v1::auth::login::login_totp::{
+    clear_failed_logins, record_failed_second_factor, user_id_of_mfa_token, username_of_unlocked_user,
+};
+use crate::telemetry::{spawn_blocking_with_tracing, TelemetryRecord};
+
+#[derive(Deserialize)]
+pub struct LoginRecoveryCodeRequestBody {
+    mfa_token: Secret<String>,
+    recovery_code: Secret<String>,
+}
+
+/// Completes the sign in of a user with two-factor authentication like `login_totp`, with one of
+/// their recovery codes instead of a one-time password. Every recovery code can only be used once.
+#[tracing::instrument(
+    name = "Received user login request with recovery code",
+    skip(state, device, request),
+    fields(
+        user_id = tracing::field::Empty
+    ),
+)]
+pub async fn login_recovery_code(
+    State(state): State<Arc<AppState>>,
+    ClientDevice(device): ClientDevice,
+    Json(request): Json<LoginRecoveryCodeRequestBody>,
+) -> AuthenticationResult<LoginResponse> {
+    let user_id = user_id_of_mfa_token(&state, &request.mfa_token).await?;
+    user_id.record_in_telemetry("user_id");
+    let username = username_of_unlocked_user(&state, user_id).await?;
+
+    let recovery_codes = state.db.get_unused_recovery_codes(&user_id)
+        .await
+        .context("Failed to get recovery codes of user")?
+        .into_iter()
+        .map(|record| record.try_into())
+        .collect::<Result<Vec<RecoveryCode>, _>>()
+        .context("Failed to parse hash of recovery code")?;
+
+    // Moving the comparisons into a different thread, as hashing is considered heavy
+    let submitted_code = request.recovery_code;
+    let recovery_code_id = spawn_blocking_with_tracing(move || {
+        for recovery_code in recovery_codes {
+            if recovery_code.matches(&submitted_code)? {
+                return Ok(Some(recovery_code.id))
+            }
+        }
+
+        Ok::<_, MatchError>(None)
+    })
+        .await
+        .context("Failed to spawn blocking tokio task to verify recovery code")?
+        .context("Failed to verify submitted recovery code")?;
+    let Some(recovery_code_id) = recovery_code_id else {
+        record_failed_second_factor(&state, &username).await?;
+        return Err(AuthenticationError::OneTimePasswordInvalid)
+    };
+
+    let mut transaction = state
+        .db
+        .new_transaction()
+        .await
+        .context("Failed to start a Postgres transaction")?;
+
+    // the recovery code could have been used by a concurrent request since it was queried
+    let code_unused = transaction.mark_recovery_code_as_used(&recovery_code_id, &Utc::now())
+        .await
+        .context("Failed to mark recovery code as used")?;
+    if !code_unused {
+        record_failed_second_factor(&state, &username).await?;
+        return Err(AuthenticationError::OneTimePasswordInvalid)
+    }
+
+    clear_failed_logins(&state, &username).await?;
+    start_session(&state, transaction, user_id, device).await
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::State;
+use axum::Json;
+use chrono::Utc;
+use secrecy::Secret;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use domain::sessions::tokens::MfaPendingToken;
+use domain::sessions::user_session_token::UserSessionToken;
+use security::encryption::decryptor::Decryptor;
+use security::token::token::Token;
+
+use crate::app_state::AppState;
+use crate::extractors::client_device::client_device::ClientDevice;
+use crate::handlers::v1::auth::authentication_error::{AuthenticationError, AuthenticationResult};
+use crate::handlers::v1::auth::login::login::{start_session, LoginResponse};
+use crate::session_token_encryptor::SessionTokenDecryptionError;
+use crate::telemetry::{spawn_blocking_with_tracing, TelemetryRecord};
+
+#[derive(Deserialize)]
+pub struct LoginTotpRequestBody {
+    mfa_token: Secret<String>,
+    code: String,
+}
+
+/// Completes the sign in of a user with two-factor authentication, by exchanging the mfa token
+/// received after entering their password together with a one-time password for a new session.
+#[tracing::instrument(
+    name = "Received user login request with one-time password",
+    skip(state, device, request),
+    fields(
+        user_id = tracing::field::Empty
+    ),
+)]
+pub async fn login_totp(
+    State(state): State<Arc<AppState>>,
+    ClientDevice(device): ClientDevice,
+    Json(request): Json<LoginTotpRequestBody>,
+) -> AuthenticationResult<LoginResponse> {
+    let user_id = user_id_of_mfa_token(&state, &request.mfa_token).await?;
+    user_id.record_in_telemetry("user_id");
+    let username = username_of_unlocked_user(&state, user_id).await?;
+
+    let mut credential = state.db.get_totp_credential(&user_id)
+        .await
+        .context("Failed to get TOTP credential of user")?
+        .ok_or(AuthenticationError::TokenInvalid)?
+        .decrypt(&state.totp_secret_encryptor)
+        .context("Failed to decrypt TOTP secret of user")?;
+
+    if !credential.is_confirmed() {
+        return Err(AuthenticationError::TokenInvalid)
+    }
+
+    if credential.verify(&request.code, Utc::now()).is_err() {
+        record_failed_second_factor(&state, &username).await?;
+        return Err(AuthenticationError::OneTimePasswordInvalid)
+    }
+
+    let mut transaction = state
+        .db
+        .new_transaction()
+        .await
+        .context("Failed to start a Postgres transaction")?;
+
+    // the code could have been used by a concurrent request since the credential was queried
+    let code_unused = transaction.update_totp_credential_usage(&credential)
+        .await
+        .context("Failed to save usage of TOTP credential")?;
+    if !code_unused {
+        record_failed_second_factor(&state, &username).await?;
+        return Err(AuthenticationError::OneTimePasswordInvalid)
+    }
+
+    clear_failed_logins(&state, &username).await?;
+    start_session(&state, transaction, user_id, device).await
+}
+
+/// Returns the id of the user the mfa token was issued to, after entering their password.
+pub async fn user_id_of_mfa_token(state: &AppState, mfa_token: &Secret<String>) -> AuthenticationResult<Uuid> {
+    let token_encryptor = state.new_token_encryptor();
+    let encrypted_mfa_token = mfa_token.clone();
+    let mfa_token = spawn_blocking_with_tracing(move || {
+        let mfa_token: Result<UserSessionToken<MfaPendingToken>, SessionTokenDecryptionError> =
+            token_encryptor.decrypt(&encrypted_mfa_token);
+
+        mfa_token
+    })
+        .await
+        .context("Failed to spawn blocking tokio task to decrypt mfa token")??;
+
+    // other tokens with a user id, such as access tokens, decrypt as mfa token as well
+    if mfa_token.get_subject() != MfaPendingToken::subject() {
+        return Err(AuthenticationError::TokenInvalid)
+    }
+
+    Ok(mfa_token.get_custom_claims().user_id)
+}
+
+/// Returns the username of the user the mfa token was issued to. Failed second factors are
+/// recorded as failed logins of the username, so accounts locked by guessing one-time passwords
+/// or recovery codes are rejected here, like at the sign in with a password.
+pub async fn username_of_unlocked_user(state: &AppState, user_id: Uuid) -> AuthenticationResult<String> {
+    let username = state.db.get_username(user_id.into())
+        .await
+        .context("Failed to get username of user")?
+        .ok_or(AuthenticationError::TokenInvalid)?;
+
+    let failed_logins = state.failed_logins.get(&username)
+        .await
+        .context("Failed to get failed logins of username")?;
+    if let Some(retry_after) = failed_logins.and_then(|f| f.locked_for(Utc::now())) {
+        return Err(AuthenticationError::AccountLocked { retry_after })
+    }
+
+    Ok(username)
+}
+
+/// Records a failed second factor of the user as failed login of their username.
+pub async fn record_failed_second_factor(state: &AppState, username: &str) -> AuthenticationResult<()> {
+    state.failed_logins.record_failure(username, &state.lockout_policy, Utc::now())
+        .await
+        .context("Failed to record failed login")?;
+
+    Ok(())
+}
+
+/// Forgets the failed second factors of the user, after they completed the sign in.
+pub async fn clear_failed_logins(state: &AppState, username: &str) -> AuthenticationResult<()> {
+    state.failed_logins.clear(username)
+        .await
+        .context("Failed to clear failed logins of username")?;
+
+    Ok(())
+}
@@ -1 +1,3 @@
 pub mod login;
+pub mod login_totp;
+pub mod login_recovery_code;
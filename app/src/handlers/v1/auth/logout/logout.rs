@@ -1,6 +1,6 @@
 use axum::http::StatusCode;
 
-use crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser;
+use crate::extractors::authenticated_user::session_user::SessionUser;
 use crate::handlers::v1::auth::authentication_error::AuthenticationResult;
 
 #[tracing::instrument(
@@ -8,7 +8,7 @@ use crate::handlers::v1::auth::authentication_error::AuthenticationResult;
     skip(authenticated_user)
 )]
 pub async fn logout(
-    authenticated_user: AuthenticatedUser
+    authenticated_user: SessionUser
 ) -> AuthenticationResult<StatusCode> {
     authenticated_user.logout().await?;
     Ok(StatusCode::OK)
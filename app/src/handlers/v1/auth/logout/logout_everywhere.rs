@@ -0,0 +1,15 @@
+use axum::http::StatusCode;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::v1::auth::authentication_error::AuthenticationResult;
+
+#[tracing::instrument(
+    name = "Logging out user everywhere by invalidating all user sessions",
+    skip(authenticated_user)
+)]
+pub async fn logout_everywhere(
+    authenticated_user: SessionUser
+) -> AuthenticationResult<StatusCode> {
+    authenticated_user.logout_everywhere().await?;
+    Ok(StatusCode::OK)
+}
@@ -1 +1,2 @@
 pub mod logout;
+pub mod logout_everywhere;
\ No newline at end of file
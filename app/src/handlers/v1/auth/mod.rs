@@ -2,3 +2,4 @@ pub mod authentication_error;
 pub mod login;
 pub mod logout;
 pub mod refresh;
+pub mod password_reset;
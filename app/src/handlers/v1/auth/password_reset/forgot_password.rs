@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+
+use domain::user::password_reset_token::PasswordResetToken;
+
+use crate::app_state::AppState;
+use crate::email_client::email_client::Email;
+use crate::handlers::error::HandlerResponse;
+use crate::telemetry::{spawn_with_tracing, TelemetryRecord};
+
+#[derive(Deserialize)]
+pub struct ForgotPasswordRequestBody {
+    username: String,
+}
+
+/// Emails a password reset token to the user, with which a new password can be set at `reset_password`.
+/// Responds the same whether or not the user exists or has an email, so the endpoint cannot be
+/// used to find out which usernames exist. The token is created and emailed after responding,
+/// so the response time does not reveal it either.
+#[tracing::instrument(
+    name = "Received forgot password request",
+    skip(state, request),
+    fields(
+        user_id = tracing::field::Empty
+    ),
+)]
+pub async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ForgotPasswordRequestBody>,
+) -> HandlerResponse<StatusCode> {
+    spawn_with_tracing(async move {
+        if let Err(e) = send_password_reset_email(&state, &request.username).await {
+            tracing::error!(error = ?e, "Failed to send password reset email");
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn send_password_reset_email(state: &AppState, username: &str) -> anyhow::Result<()> {
+    let Some((user_id, email)) = state.db.get_email_by_username(username)
+        .await
+        .context("Failed to get email of user")? else {
+        return Ok(())
+    };
+    user_id.record_in_telemetry("user_id");
+
+    let lifetime = state.password_reset.token_lifetime();
+    let (token, reset_token) = PasswordResetToken::generate(user_id, lifetime);
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store password reset token")?;
+
+    transaction.save_password_reset_token(&reset_token)
+        .await
+        .context("Failed to save password reset token")?;
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing password reset token")?;
+
+    let reset_link = format!("{}?token={}", state.password_reset.url, token.expose_secret());
+    state.email_client.send_email(&Email {
+        recipient: email,
+        subject: "Reset your password".to_string(),
+        text_body: format!(
+            "A password reset was requested for your account {}.\n\
+            Choose a new password within {} minutes at {}\n\
+            If you did not request this, you can ignore this email.",
+            username,
+            lifetime.num_minutes(),
+            reset_link,
+        ),
+    })
+        .await
+        .context("Failed to send password reset email")
+}
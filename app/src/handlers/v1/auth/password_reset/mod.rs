@@ -0,0 +1,2 @@
+pub mod forgot_password;
+pub mod reset_password;
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::Utc;
+use password_hash::SaltString;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use domain::user::password::Password;
+use domain::user::password_reset_token::PasswordResetToken;
+
+use crate::app_state::AppState;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::telemetry::{spawn_blocking_with_tracing, TelemetryRecord};
+
+#[derive(Deserialize)]
+pub struct ResetPasswordRequestBody {
+    token: Secret<String>,
+    new_password: Secret<String>,
+}
+
+/// Sets a new password for the user of the password reset token, which is emailed by `forgot_password`.
+/// The token can only be used once, and every session of the user is ended as the old password
+/// may be known to someone else.
+#[tracing::instrument(
+    name = "Received reset password request",
+    skip(state, request),
+    fields(
+        user_id = tracing::field::Empty
+    ),
+)]
+pub async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ResetPasswordRequestBody>,
+) -> HandlerResponse<StatusCode> {
+    let token_id = PasswordResetToken::id_of(&request.token)
+        .ok_or(HandlerError::Forbidden)?;
+    let reset_token: PasswordResetToken = state.db.get_password_reset_token(&token_id)
+        .await
+        .context("Failed to get password reset token")?
+        .ok_or(HandlerError::Forbidden)?
+        .into();
+    if !reset_token.matches(&request.token) {
+        return Err(HandlerError::Forbidden)
+    }
+    let user_id = reset_token.user_id;
+    user_id.record_in_telemetry("user_id");
+
+    let username = state.db.get_username(user_id)
+        .await
+        .context("Failed to get username of user")?
+        .ok_or(HandlerError::Forbidden)?;
+    state.password_policy.validate(&request.new_password, &username)
+        .map_err(HandlerError::PasswordRejected)?;
+
+    // Moving hashing into a different thread because it is considered heavy,
+    // which can block tokio runtime.
+    let new_password = request.new_password;
+    let new_password = spawn_blocking_with_tracing(move || {
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        Password::new(new_password, &salt)
+    })
+        .await
+        .context("Failed to spawn blocking tokio task to hash password")?
+        .context("Failed to hash new password")?;
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to reset password")?;
+
+    // the token could have been used by a concurrent request since it was queried
+    let unused_tokens = transaction.mark_password_reset_tokens_as_used(&user_id, &Utc::now())
+        .await
+        .context("Failed to mark password reset tokens as used")?;
+    if !unused_tokens.contains(&token_id) {
+        return Err(HandlerError::Forbidden)
+    }
+
+    transaction.update_user_password(user_id.0, new_password)
+        .await
+        .context("Failed to save new password of user")?;
+
+    let sessions = transaction.get_active_sessions_by_user_id_for_update(user_id)
+        .await
+        .context("Failed to query database to get active sessions")?;
+
+    let mut ended_session_ids = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        ended_session_ids.push(*session.id());
+        transaction.save_just_ended_session(&session.end_by_password_change())
+            .await
+            .context("Failed to save session ended by password reset")?;
+    }
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing new password")?;
+
+    state.remember_ended_sessions(ended_session_ids);
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -6,23 +6,26 @@ use axum::http::StatusCode;
 use axum::Json;
 use chrono::{DateTime, Duration, Utc};
 use domain::sessions::state::just_ended::JustEnded;
+use domain::sessions::refresh_outcome::RefreshOutcome;
 use domain::sessions::state::refreshed::Refreshed;
+use domain::sessions::state::reissued::Reissued;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use domain::sessions::tokens::RefreshToken;
 use domain::sessions::user_session::UserSession;
 use domain::sessions::user_session_token::UserSessionToken;
-use infrastructure::paseto::paseto_token_encryptor::LocalPasetoV4DecryptionError;
-use security::encryption::decryptor::Decryptor;
 use security::encryption::encryptor::Encryptor;
 use security::token::token::Token;
 
 use crate::app_state::AppState;
+use crate::extractors::client_device::client_device::ClientDevice;
 use crate::handlers::v1::auth::authentication_error::{
     AuthenticationError, AuthenticationResult,
 };
+use crate::queries::transaction::_transaction::Transaction;
 use crate::telemetry::spawn_blocking_with_tracing;
+use crate::session_token_encryptor::SessionTokenDecryptionError;
 
 #[derive(Deserialize)]
 pub struct RefreshRequest {
@@ -39,7 +42,7 @@ pub struct RefreshResponse {
 
 #[tracing::instrument(
     name = "Refreshing access and refresh tokens for user",
-    skip(state, refresh_request),
+    skip(state, device, refresh_request),
     fields (
         user_id = tracing::field::Empty,
         session_id = tracing::field::Empty,
@@ -49,17 +52,20 @@ pub struct RefreshResponse {
 )]
 pub async fn refresh(
     State(state): State<Arc<AppState>>,
+    ClientDevice(device): ClientDevice,
     refresh_request: Json<RefreshRequest>,
 ) -> AuthenticationResult<(StatusCode, Json<RefreshResponse>)> {
     let token_encryptor = state.new_token_encryptor();
     let refresh_token = spawn_blocking_with_tracing(move || {
-        let refresh_token: Result<UserSessionToken<RefreshToken>, LocalPasetoV4DecryptionError> =
-            token_encryptor.decrypt(&refresh_request.refresh_token);
+        // expired refresh tokens are accepted here so the session can be ended for the right reason,
+        // refreshing the session never succeeds with an expired token.
+        let refresh_token: Result<UserSessionToken<RefreshToken>, SessionTokenDecryptionError> =
+            token_encryptor.decrypt_ignoring_expiration(&refresh_request.refresh_token);
 
         refresh_token
     })
         .await
-        .context("Failed to spawn blocking tokio task to encrypt refreshed session tokens")??;
+        .context("Failed to spawn blocking tokio task to decrypt refresh token")??;
 
     tracing::Span::current().record(
         "user_id", &tracing::field::display(&refresh_token.get_custom_claims().user_id),
@@ -71,8 +77,13 @@ pub async fn refresh(
         "refresh_token_id", &tracing::field::display(&refresh_token.get_id()),
     );
 
-    let active_session = state.db
-        .get_active_session_by_id(&refresh_token.get_custom_claims().session_id)
+    // the session is locked until the transaction ends, so concurrent refreshes with the same
+    // refresh token are handled one after another, the later ones reissuing the tokens of the first.
+    let mut transaction = state.db.new_transaction().await
+        .context("Failed to start transaction to refresh session")?;
+
+    let active_session = transaction
+        .get_active_session_by_id_for_update(&refresh_token.get_custom_claims().session_id)
         .await
         .context("Failed to active session from Postgres")?
         .ok_or(AuthenticationError::SessionNotActive)?;
@@ -82,21 +93,26 @@ pub async fn refresh(
         &tracing::field::display(&active_session.state().latest_refresh_token.id),
     );
 
-    return match active_session.refresh(refresh_token) {
-        Ok(refreshed_session) => {
-            save_refreshed_session_and_generate_response(state, refreshed_session).await
+    match active_session.refresh(refresh_token, device, &state.session_policy) {
+        RefreshOutcome::Refreshed(refreshed_session) => {
+            save_refreshed_session_and_generate_response(state, transaction, refreshed_session).await
+        }
+        RefreshOutcome::Reissued(reissued_session) => {
+            transaction.rollback().await
+                .context("Failed to release lock on session of reissued tokens")?;
+            generate_reissued_session_response(state, reissued_session).await
         }
-        Err(ended_session) => save_ended_session_and_generate_response(state, ended_session).await,
-    };
+        RefreshOutcome::Ended(ended_session) => {
+            save_ended_session_and_generate_response(state, transaction, ended_session).await
+        }
+    }
 }
 
 async fn save_refreshed_session_and_generate_response(
     state: Arc<AppState>,
+    mut transaction: Transaction,
     refreshed_session: UserSession<Refreshed>,
 ) -> AuthenticationResult<(StatusCode, Json<RefreshResponse>)> {
-    let mut transaction = state.db.new_transaction().await
-        .context("Failed to start transaction to save refreshed session")?;
-
     transaction.save_refreshed_session(&refreshed_session).await
         .context("Failed to save refresh session to Postgres")?;
 
@@ -132,18 +148,51 @@ async fn save_refreshed_session_and_generate_response(
     ))
 }
 
+async fn generate_reissued_session_response(
+    state: Arc<AppState>,
+    reissued_session: UserSession<Reissued>,
+) -> AuthenticationResult<(StatusCode, Json<RefreshResponse>)> {
+    tracing::info!("Refresh token was reused within its grace period, reissuing latest tokens");
+
+    let token_encryptor = state.new_token_encryptor();
+    let (access_token, refresh_token) = spawn_blocking_with_tracing(move || {
+        let encryption_access_result =
+            token_encryptor.encrypt(reissued_session.state().access_token());
+
+        let encryption_refresh_result =
+            token_encryptor.encrypt(reissued_session.state().refresh_token());
+
+        (encryption_access_result, encryption_refresh_result)
+    })
+    .await
+    .context("Failed to spawn blocking tokio task to encrypt reissued session tokens")?;
+
+    let access_token = access_token.context("Failed to encrypt access token")?;
+    let refresh_token = refresh_token.context("Failed to encrypt refresh token")?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RefreshResponse {
+            access_token: access_token.token.expose_secret().clone(),
+            access_token_expiration: access_token.expires_at - Duration::seconds(30),
+            refresh_token: refresh_token.token.expose_secret().clone(),
+            refresh_token_expiration: refresh_token.expires_at - Duration::seconds(30),
+        }),
+    ))
+}
+
 async fn save_ended_session_and_generate_response(
     state: Arc<AppState>,
+    mut transaction: Transaction,
     ended_session: UserSession<JustEnded>,
 ) -> AuthenticationResult<(StatusCode, Json<RefreshResponse>)> {
-    let mut transaction = state.db.new_transaction().await
-        .context("Failed to start transaction to save refreshed session")?;
-
     transaction.save_just_ended_session(&ended_session).await
         .context("Failed to save refresh session to Postgres")?;
 
     transaction.commit().await
         .context("Failed to commit just ended token to Postgres")?;
 
+    state.remember_ended_sessions([*ended_session.id()]);
+
     Err(AuthenticationError::TokenInvalid)
 }
@@ -1,13 +1,13 @@
 use axum::Json;
 use serde::Serialize;
 use uuid::Uuid;
-use crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser;
+use crate::extractors::authenticated_user::session_user::SessionUser;
 
 #[tracing::instrument(
     name = "Checking health of server via protected health check",
     skip(current_user)
 )]
-pub async fn current_user(current_user: AuthenticatedUser) -> Json<CurrentUser> {
+pub async fn current_user(current_user: SessionUser) -> Json<CurrentUser> {
     return Json(current_user.into())
 }
 
@@ -18,8 +18,8 @@ pub struct CurrentUser {
     pub refresh_token_id: Uuid
 }
 
-impl From<AuthenticatedUser> for CurrentUser {
-    fn from(value: AuthenticatedUser) -> Self {
+impl From<SessionUser> for CurrentUser {
+    fn from(value: SessionUser) -> Self {
         Self {
             user_id: value.user_id.0,
             session_id: value.session_id,
@@ -1,5 +1,8 @@
 pub mod auth;
 pub mod current_user;
 pub mod users;
+pub mod sessions;
 pub mod teams;
 pub mod health_check;
+pub mod oauth;
+pub mod service_accounts;
@@ -0,0 +1,2 @@
+pub mod oauth_error;
+pub mod token;
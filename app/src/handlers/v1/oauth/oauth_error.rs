@@ -0,0 +1,63 @@
+use std::fmt::{Debug, Formatter};
+
+use axum::http::header::WWW_AUTHENTICATE;
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+
+use lib_util::errors::errors::format_error_chain;
+
+use crate::util::handlers::InternalErrorResponse;
+
+/// OAuthError is the error response of the token endpoint, as specified in section 5.2 of RFC 6749.
+#[derive(thiserror::Error)]
+pub enum OAuthError {
+    #[error("Token request is missing a parameter or is malformed")]
+    InvalidRequest,
+
+    #[error("Client authentication failed")]
+    InvalidClient,
+
+    #[error("Grant type is not supported")]
+    UnsupportedGrantType,
+
+    #[error("Requested scope is invalid")]
+    InvalidScope,
+
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl OAuthError {
+    fn code(&self) -> &'static str {
+        match self {
+            OAuthError::InvalidRequest | OAuthError::UnexpectedError(_) => "invalid_request",
+            OAuthError::InvalidClient => "invalid_client",
+            OAuthError::UnsupportedGrantType => "unsupported_grant_type",
+            OAuthError::InvalidScope => "invalid_scope",
+        }
+    }
+}
+
+impl Debug for OAuthError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        let body = Json(json!({ "error": self.code() }));
+
+        match self {
+            OAuthError::UnexpectedError(_) => InternalErrorResponse::from(tracing::Span::current()).into_response(),
+            OAuthError::InvalidClient => {
+                (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, "Basic")], body).into_response()
+            },
+            OAuthError::InvalidRequest
+            | OAuthError::UnsupportedGrantType
+            | OAuthError::InvalidScope => (StatusCode::BAD_REQUEST, body).into_response(),
+        }
+    }
+}
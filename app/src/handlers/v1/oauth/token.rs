@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::rejection::FormRejection;
+use axum::extract::State;
+use axum::http::header::{CACHE_CONTROL, PRAGMA};
+use axum::response::IntoResponse;
+use axum::{Form, Json};
+use axum_extra::headers::authorization::Basic;
+use axum_extra::headers::Authorization;
+use axum_extra::TypedHeader;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use domain::sessions::tokens::ServiceAccountAccessToken;
+use domain::user::personal_access_token::Scope;
+use domain::user::service_account::ServiceAccount;
+use security::encryption::encryptor::Encryptor;
+
+use crate::app_state::AppState;
+use crate::handlers::v1::oauth::oauth_error::OAuthError;
+use crate::telemetry::{spawn_blocking_with_tracing, TelemetryRecord};
+
+const GRANT_TYPE_CLIENT_CREDENTIALS: &str = "client_credentials";
+const TOKEN_TYPE_BEARER: &str = "Bearer";
+
+/// The token request of section 4.4.2 of RFC 6749. Every parameter is optional here,
+/// so missing parameters are reported as OAuth errors instead of being rejected by the extractor.
+#[derive(Deserialize)]
+pub struct TokenRequestBody {
+    grant_type: Option<String>,
+
+    /// scope is the space-delimited list of requested scopes, every scope is granted when omitted.
+    scope: Option<String>,
+
+    /// client_id and client_secret can be sent in the body by clients
+    /// which are unable to use the Authorization header.
+    client_id: Option<String>,
+    client_secret: Option<Secret<String>>,
+}
+
+#[derive(Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+
+    /// expires_in is the lifetime of the access token in seconds.
+    pub expires_in: i64,
+    pub scope: String,
+}
+
+/// Issues access tokens to service accounts with the client credentials grant of RFC 6749.
+/// Service accounts authenticate with HTTP Basic authentication, or with their client
+/// credentials in the body. There is no refresh token, a new access token is requested instead.
+#[tracing::instrument(
+    name = "Issuing access token to service account",
+    skip_all,
+    fields(
+        client_id = tracing::field::Empty,
+    )
+)]
+pub async fn token(
+    State(state): State<Arc<AppState>>,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    request: Result<Form<TokenRequestBody>, FormRejection>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let Form(request) = request.map_err(|_| OAuthError::InvalidRequest)?;
+
+    match request.grant_type.as_deref() {
+        None => return Err(OAuthError::InvalidRequest),
+        Some(GRANT_TYPE_CLIENT_CREDENTIALS) => {},
+        Some(_) => return Err(OAuthError::UnsupportedGrantType),
+    }
+
+    let (client_id, client_secret) = match (basic, request.client_id, request.client_secret) {
+        (Some(TypedHeader(Authorization(basic))), None, None) => {
+            (basic.username().to_string(), Secret::new(basic.password().to_string()))
+        },
+        (None, Some(client_id), Some(client_secret)) => (client_id, client_secret),
+        // clients must not use more than one authentication method
+        (Some(_), _, _) => return Err(OAuthError::InvalidRequest),
+        (None, _, _) => return Err(OAuthError::InvalidClient),
+    };
+    client_id.record_in_telemetry("client_id");
+
+    let scopes = parse_scopes(request.scope.as_deref())?;
+
+    let service_account: ServiceAccount = state.db
+        .get_service_account_by_client_id(&client_id)
+        .await
+        .context("Failed to query database to get service account")?
+        .ok_or(OAuthError::InvalidClient)?
+        .into();
+
+    if !service_account.secret_matches(&client_secret) {
+        return Err(OAuthError::InvalidClient)
+    }
+
+    let scope = scopes.iter().map(Scope::name).collect::<Vec<_>>().join(" ");
+    let access_token = ServiceAccountAccessToken {
+        user_id: service_account.user_id.0,
+        client_id: service_account.client_id,
+        scopes,
+    }.issue(&state.session_policy);
+
+    let cipher = state.new_token_encryptor();
+    let encrypted_access_token = spawn_blocking_with_tracing(move || cipher.encrypt(&access_token))
+        .await
+        .context("Failed to spawn blocking tokio task to encrypt access token")?
+        .context("Failed to encrypt access token of service account")?;
+
+    // responses containing tokens must not be cached, as required by section 5.1 of RFC 6749
+    let headers = [(CACHE_CONTROL, "no-store"), (PRAGMA, "no-cache")];
+
+    Ok((headers, Json(TokenResponse {
+        access_token: encrypted_access_token.token.expose_secret().clone(),
+        token_type: TOKEN_TYPE_BEARER,
+        expires_in: state.session_policy.access_token_lifetime.num_seconds(),
+        scope,
+    })))
+}
+
+/// Parses the space-delimited scopes of the request, every scope is granted when none are requested.
+fn parse_scopes(scope: Option<&str>) -> Result<Vec<Scope>, OAuthError> {
+    let Some(scope) = scope else {
+        return Ok(vec![Scope::Read, Scope::Write])
+    };
+
+    let mut scopes = scope.split(' ')
+        .map(|name| Scope::from_name(name).ok_or(OAuthError::InvalidScope))
+        .collect::<Result<Vec<_>, _>>()?;
+    scopes.sort_by_key(Scope::name);
+    scopes.dedup();
+
+    Ok(scopes)
+}
@@ -0,0 +1,74 @@
+use anyhow::Context;
+use axum::http::StatusCode;
+use axum::Json;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use domain::role::role::SystemRole;
+use domain::team::team_id::TeamId;
+use domain::user::service_account::ServiceAccount;
+use domain::user::user_id::UserId;
+
+use crate::extractors::user::user_with_policy::UserWithPolicy;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::policy::policies::create_user_policy::{CreateUserDetails, CreateUserPolicy};
+use crate::policy::policy::Policy;
+
+#[derive(Deserialize)]
+pub struct CreateServiceAccountRequestBody {
+    id: UserId,
+
+    /// name is the username of the service account, e.g. `billing-service`.
+    name: String,
+    role: Option<SystemRole>,
+
+    /// team_id is the team the service account becomes a member of.
+    #[serde(default)]
+    team_id: Option<TeamId>,
+}
+
+#[derive(Serialize)]
+pub struct CreateServiceAccountResponse {
+    pub user_id: Uuid,
+    pub client_id: String,
+
+    /// client_secret is only shown once, only its hash is stored.
+    pub client_secret: String,
+}
+
+/// Creates a service account, which obtains access tokens at `/v1/oauth/token` with the client
+/// credentials in the response. Creating a service account requires the same permissions as
+/// creating a user with the same role and team.
+#[tracing::instrument(
+    name = "Creating service account",
+    skip(user, request),
+    fields(
+        service_account_id = %request.id,
+    )
+)]
+pub async fn create_service_account(
+    user: UserWithPolicy<CreateUserPolicy>,
+    Json(request): Json<CreateServiceAccountRequestBody>,
+) -> HandlerResponse<(StatusCode, Json<CreateServiceAccountResponse>)> {
+    let contract = user.policy.authorize(CreateUserDetails {
+        role: request.role,
+        team_to_part_of: request.team_id,
+    }).await?;
+
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(HandlerError::UnprocessableEntity)
+    }
+
+    let (client_secret, service_account) = ServiceAccount::generate(request.id);
+    contract.create_service_account(&service_account, name)
+        .await
+        .context("Failed to create service account")?;
+
+    Ok((StatusCode::CREATED, Json(CreateServiceAccountResponse {
+        user_id: service_account.user_id.0,
+        client_id: service_account.client_id,
+        client_secret: client_secret.expose_secret().clone(),
+    })))
+}
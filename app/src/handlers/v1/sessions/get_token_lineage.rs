@@ -0,0 +1,85 @@
+use anyhow::Context;
+use axum::extract::Path;
+use axum::Json;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::extractors::user::user_with_policy::UserWithPolicy;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::policy::policies::read_session_token_lineage_policy::ReadSessionTokenLineagePolicy;
+use crate::policy::policy::Policy;
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
+use crate::telemetry::TelemetryRecord;
+
+#[derive(Deserialize)]
+pub struct TokenLineageParams {
+    session_id: Uuid
+}
+
+#[derive(Serialize)]
+pub struct TokenLineageResponse {
+    session_id: Uuid,
+    user_id: Uuid,
+    created_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    ending_reason: Option<String>,
+    /// ending_token_id is the refresh token that caused the session to end,
+    /// e.g. the token that got replayed when the session ended by AttemptedToReuseRefreshToken.
+    ending_token_id: Option<Uuid>,
+    refresh_tokens: Vec<RefreshTokenResponse>
+}
+
+#[derive(Serialize)]
+pub struct RefreshTokenResponse {
+    id: Uuid,
+    parent_id: Option<Uuid>,
+    issued_at: DateTime<Utc>,
+    expiration: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+impl From<RefreshTokenRecord> for RefreshTokenResponse {
+    fn from(token: RefreshTokenRecord) -> Self {
+        Self {
+            id: token.id,
+            parent_id: token.parent_id,
+            issued_at: token.issued_at.and_utc(),
+            expiration: token.expiration.and_utc(),
+            used_at: token.used_at.map(|t: NaiveDateTime| t.and_utc()),
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting refresh token lineage of session",
+    skip(user, params),
+    fields(
+        session_id = tracing::field::Empty,
+    )
+)]
+pub async fn get_token_lineage(
+    user: UserWithPolicy<ReadSessionTokenLineagePolicy>,
+    Path(params): Path<TokenLineageParams>
+) -> HandlerResponse<Json<TokenLineageResponse>> {
+    params.session_id.record_in_telemetry("session_id");
+
+    let contract = user.policy.authorize(params.session_id).await?;
+    let lineage = contract.get_token_lineage()
+        .await
+        .context("Failed to get refresh token lineage of session")?;
+
+    let Some((session, refresh_tokens)) = lineage else {
+        return Err(HandlerError::NotFound)
+    };
+
+    Ok(Json(TokenLineageResponse {
+        session_id: session.id,
+        user_id: session.user_id,
+        created_at: session.created_at.and_utc(),
+        ended_at: session.ended_at.map(|t| t.and_utc()),
+        ending_reason: session.ending_reason,
+        ending_token_id: session.ending_token_id,
+        refresh_tokens: refresh_tokens.into_iter().map(RefreshTokenResponse::from).collect(),
+    }))
+}
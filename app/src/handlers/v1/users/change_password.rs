@@ -0,0 +1,136 @@
+use anyhow::Context;
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::Utc;
+use password_hash::SaltString;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+
+use domain::user::password::{MatchResult, Password};
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::telemetry::spawn_blocking_with_tracing;
+
+#[derive(Deserialize)]
+pub struct ChangePasswordRequestBody {
+    current_password: Secret<String>,
+    new_password: Secret<String>,
+
+    /// Ends every session of the user except the session making the request,
+    /// e.g. when the current password may be known to someone else.
+    #[serde(default)]
+    end_other_sessions: bool,
+}
+
+/// Changes the password of the authenticated user, which requires the current password
+/// so that a stolen access token alone is not enough to take over the account. Wrong current
+/// passwords count as failed logins, so the password cannot be guessed here either.
+#[tracing::instrument(
+    name = "Changing password of authenticated user",
+    skip(authenticated_user, request),
+    fields(
+        end_other_sessions = request.end_other_sessions
+    )
+)]
+pub async fn change_password(
+    authenticated_user: SessionUser,
+    Json(request): Json<ChangePasswordRequestBody>,
+) -> HandlerResponse<StatusCode> {
+    let state = &authenticated_user.state;
+
+    if request.new_password.expose_secret() == request.current_password.expose_secret() {
+        return Err(HandlerError::UnprocessableEntity)
+    }
+
+    let username = state.db.get_username(authenticated_user.user_id)
+        .await
+        .context("Failed to get username of authenticated user")?
+        .ok_or(HandlerError::NotFound)?;
+    state.password_policy.validate(&request.new_password, &username)
+        .map_err(HandlerError::PasswordRejected)?;
+
+    // Locked accounts are rejected before verifying the password, like when logging in.
+    let failed_logins = state.failed_logins.get(&username)
+        .await
+        .context("Failed to get failed logins of username")?;
+    if let Some(retry_after) = failed_logins.as_ref().and_then(|f| f.locked_for(Utc::now())) {
+        return Err(HandlerError::AccountLocked { retry_after })
+    }
+
+    let password_hash = state.db.get_password_hash(authenticated_user.user_id)
+        .await
+        .context("Failed to get password hash of authenticated user")?
+        .ok_or(HandlerError::NotFound)?;
+    let current_password = Password::try_from(password_hash.expose_secret().clone())
+        .context("Failed to parse password hash")?;
+
+    // Moving matching and hashing into a different thread because these operations are
+    // considered heavy, which can block tokio runtime.
+    let submitted_password = request.current_password;
+    let new_password = request.new_password;
+    let new_password = spawn_blocking_with_tracing(move || -> anyhow::Result<Option<Password>> {
+        match current_password.matches(&submitted_password)? {
+            MatchResult::DoesNotMatch => Ok(None),
+            MatchResult::Matches | MatchResult::MatchesButSchemeOutdated => {
+                let salt = SaltString::generate(&mut rand::thread_rng());
+                Ok(Some(Password::new(new_password, &salt)?))
+            }
+        }
+    })
+        .await
+        .context("Failed to spawn blocking tokio task to verify and hash password")?
+        .context("Failed to verify current password and hash new password")?;
+
+    let Some(new_password) = new_password else {
+        state.failed_logins.record_failure(&username, &state.lockout_policy, Utc::now())
+            .await
+            .context("Failed to record failed password verification")?;
+
+        return Err(HandlerError::Forbidden)
+    };
+
+    // the account might have been locked by concurrent attempts while the password was verified
+    let failed_logins = state.failed_logins.get(&username)
+        .await
+        .context("Failed to get failed logins of username")?;
+    if let Some(retry_after) = failed_logins.as_ref().and_then(|f| f.locked_for(Utc::now())) {
+        return Err(HandlerError::AccountLocked { retry_after })
+    }
+
+    if failed_logins.is_some() {
+        state.failed_logins.clear(&username)
+            .await
+            .context("Failed to clear failed logins of username")?;
+    }
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store new password")?;
+
+    transaction.update_user_password(authenticated_user.user_id.0, new_password)
+        .await
+        .context("Failed to save new password of user")?;
+
+    let mut ended_session_ids = vec![];
+    if request.end_other_sessions {
+        let sessions = transaction.get_active_sessions_by_user_id_for_update(authenticated_user.user_id)
+            .await
+            .context("Failed to query database to get active sessions")?;
+
+        for session in sessions.into_iter().filter(|session| *session.id() != authenticated_user.session_id) {
+            ended_session_ids.push(*session.id());
+            transaction.save_just_ended_session(&session.end_by_password_change())
+                .await
+                .context("Failed to save session ended by password change")?;
+        }
+    }
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing new password")?;
+
+    state.remember_ended_sessions(ended_session_ids);
+
+    Ok(StatusCode::NO_CONTENT)
+}
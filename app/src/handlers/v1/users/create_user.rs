@@ -1,9 +1,12 @@
+use crate::app_state::AppState;
 use crate::extractors::user::user_with_policy::UserWithPolicy;
-use crate::handlers::error::HandlerResponse;
+use crate::handlers::error::{HandlerError, HandlerResponse};
 use crate::policy::policies::create_user_policy::{CreateUserDetails, CreateUserPolicy};
 use crate::policy::policy::Policy;
 use crate::telemetry::spawn_blocking_with_tracing;
+use std::sync::Arc;
 use anyhow::Context;
+use axum::extract::State;
 use axum::http::StatusCode;
 use axum::Json;
 use domain::role::role::{SystemRole};
@@ -19,16 +22,27 @@ pub struct CreateUserRequestBody {
     id: UserId,
     username: String,
     password: Secret<String>,
-    role: Option<SystemRole>
+    role: Option<SystemRole>,
+
+    /// email is where password reset emails of the user are sent to.
+    #[serde(default)]
+    email: Option<String>,
 }
 
-pub async fn create_user(user: UserWithPolicy<CreateUserPolicy>, Json(new_user): Json<CreateUserRequestBody>) -> HandlerResponse<StatusCode> {
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    user: UserWithPolicy<CreateUserPolicy>,
+    Json(new_user): Json<CreateUserRequestBody>,
+) -> HandlerResponse<StatusCode> {
     // authorize logged in user to see if it can create the user with the given roles
     let new_user_contract = user.policy.authorize(CreateUserDetails {
         role: new_user.role,
         team_to_part_of: None,
     }).await?;
-    
+
+    state.password_policy.validate(&new_user.password, &new_user.username)
+        .map_err(HandlerError::PasswordRejected)?;
+
     // hash password of new user
     let password = new_user.password;
     let hashed_pw = spawn_blocking_with_tracing(move || {
@@ -47,7 +61,7 @@ pub async fn create_user(user: UserWithPolicy<CreateUserPolicy>, Json(new_user):
     };
 
     new_user_contract
-        .create_user(user)
+        .create_user(user, new_user.email)
         .await
         .context("Failed to create new user")?;
     
@@ -1,4 +1,8 @@
 pub mod me;
 pub mod create_user;
 pub mod get_user_details;
-
+pub mod sessions;
+pub mod totp;
+pub mod change_password;
+pub mod unlock_user;
+pub mod tokens;
@@ -0,0 +1,55 @@
+use anyhow::Context;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::telemetry::TelemetryRecord;
+
+#[derive(Deserialize)]
+pub struct EndSessionParams {
+    session_id: Uuid
+}
+
+#[tracing::instrument(
+    name = "Ending session of authenticated user",
+    skip(authenticated_user, params),
+    fields(
+        session_to_end = tracing::field::Empty,
+    )
+)]
+pub async fn end_session(
+    authenticated_user: SessionUser,
+    Path(params): Path<EndSessionParams>
+) -> HandlerResponse<StatusCode> {
+    params.session_id.record_in_telemetry("session_to_end");
+
+    let session = authenticated_user.state.db
+        .get_active_session_by_id(&params.session_id)
+        .await
+        .context("Failed to query database to get active session")?;
+
+    // Sessions of other users are reported as not found, to not leak their existence.
+    let session = match session {
+        Some(session) if *session.user_id() == authenticated_user.user_id => session,
+        _ => return Err(HandlerError::NotFound)
+    };
+
+    let mut transaction = authenticated_user.state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store ended user session")?;
+
+    transaction.save_just_ended_session(&session.end_by_user_revocation())
+        .await
+        .context("Failed to save ended user session to database")?;
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing ended user session")?;
+
+    authenticated_user.state.remember_ended_sessions([params.session_id]);
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,38 @@
+use anyhow::Context;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+use domain::user::user_id::UserId;
+
+use crate::extractors::user::user_with_policy::UserWithPolicy;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::policy::policies::end_user_sessions_policy::EndUserSessionsPolicy;
+use crate::policy::policy::Policy;
+
+#[derive(Deserialize)]
+pub struct EndUserSessionsParams {
+    user_id: UserId
+}
+
+#[tracing::instrument(
+    name = "Ending all sessions of user",
+    skip(user, params),
+    fields(
+        user_id = %params.user_id,
+        sessions_ended = tracing::field::Empty,
+    )
+)]
+pub async fn end_user_sessions(
+    user: UserWithPolicy<EndUserSessionsPolicy>,
+    Path(params): Path<EndUserSessionsParams>
+) -> HandlerResponse<StatusCode> {
+    let contract = user.policy.authorize(params.user_id).await?;
+    let sessions_ended = contract.end_sessions()
+        .await
+        .context("Failed to end sessions of user")?
+        .ok_or(HandlerError::NotFound)?;
+
+    tracing::Span::current().record("sessions_ended", sessions_ended);
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,69 @@
+use std::net::IpAddr;
+
+use anyhow::Context;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use domain::sessions::state::active::Active;
+use domain::sessions::user_agent::{DeviceClass, UserAgent};
+use domain::sessions::user_session::UserSession;
+use security::token::token::Token;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::HandlerResponse;
+
+#[derive(Serialize)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    pub current: bool,
+
+    /// device describes the device the session was last used on, e.g. `Chrome on Linux from 10.0.0.5`.
+    pub device: String,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device_class: DeviceClass,
+    pub ip_address: Option<IpAddr>,
+}
+
+impl SessionResponse {
+    fn from(session: &UserSession<Active>, current_session_id: &Uuid) -> Self {
+        let user_agent = session.device().user_agent.as_ref();
+        Self {
+            id: *session.id(),
+            created_at: *session.created_at(),
+            last_refreshed_at: *session.state().latest_refresh_token.get_issued_at(),
+            current: session.id() == current_session_id,
+            device: session.device().to_string(),
+            browser: user_agent.and_then(UserAgent::browser).map(str::to_string),
+            os: user_agent.and_then(UserAgent::os).map(str::to_string),
+            device_class: user_agent.map_or(DeviceClass::Unknown, UserAgent::device_class),
+            ip_address: session.device().ip_address,
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Get active sessions of authenticated user",
+    skip_all
+)]
+pub async fn get_sessions(
+    authenticated_user: SessionUser
+) -> HandlerResponse<Json<Vec<SessionResponse>>> {
+    let sessions = authenticated_user.state.db
+        .get_active_sessions_by_user_id(authenticated_user.user_id)
+        .await
+        .context("Failed to query active sessions of user from Postgres")?;
+
+    // Sessions are only marked as ended once they are used after expiring,
+    // therefor sessions which can no longer be refreshed are filtered out.
+    let sessions = sessions.iter()
+        .filter(|session| !session.state().latest_refresh_token.expired())
+        .map(|session| SessionResponse::from(session, &authenticated_user.session_id))
+        .collect();
+
+    Ok(Json(sessions))
+}
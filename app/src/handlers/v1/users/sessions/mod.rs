@@ -0,0 +1,3 @@
+pub mod get_sessions;
+pub mod end_session;
+pub mod end_user_sessions;
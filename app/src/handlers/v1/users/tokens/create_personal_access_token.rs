@@ -0,0 +1,106 @@
+use anyhow::Context;
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use domain::user::personal_access_token::{PersonalAccessToken, Scope};
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+
+/// The longest name of a personal access token.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// The longest lifetime of a personal access token in days, tokens which should
+/// last longer are created without an expiration.
+const MAX_EXPIRES_IN_DAYS: u32 = 3650;
+
+#[derive(Deserialize)]
+pub struct CreatePersonalAccessTokenRequestBody {
+    /// name describes what the token is used for, e.g. `deploy pipeline`.
+    name: String,
+    scopes: Vec<Scope>,
+
+    /// expires_in_days is the lifetime of the token, tokens without it never expire.
+    expires_in_days: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct CreatePersonalAccessTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+
+    /// prefix is the start of the token, by which the token can be recognized later on.
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+
+    /// token is only shown once, only its hash is stored.
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Creates a personal access token for the authenticated user, which can be used instead of an
+/// access token for the endpoints allowed by its scopes.
+#[tracing::instrument(
+    name = "Creating personal access token for authenticated user",
+    skip(authenticated_user, request),
+    fields(
+        scopes = ?request.scopes,
+        expires_in_days = ?request.expires_in_days,
+    )
+)]
+pub async fn create_personal_access_token(
+    authenticated_user: SessionUser,
+    Json(request): Json<CreatePersonalAccessTokenRequestBody>,
+) -> HandlerResponse<(StatusCode, Json<CreatePersonalAccessTokenResponse>)> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(HandlerError::UnprocessableEntity)
+    }
+
+    let mut scopes = request.scopes;
+    scopes.sort_by_key(Scope::name);
+    scopes.dedup();
+    if scopes.is_empty() {
+        return Err(HandlerError::UnprocessableEntity)
+    }
+
+    let lifetime = match request.expires_in_days {
+        Some(days) if days == 0 || days > MAX_EXPIRES_IN_DAYS => return Err(HandlerError::UnprocessableEntity),
+        Some(days) => Some(Duration::days(days.into())),
+        None => None,
+    };
+
+    let (token, personal_access_token) = PersonalAccessToken::generate(
+        authenticated_user.user_id,
+        name,
+        scopes,
+        lifetime,
+    ).ok_or(HandlerError::UnprocessableEntity)?;
+
+    let mut transaction = authenticated_user.state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store personal access token")?;
+
+    transaction.save_personal_access_token(&personal_access_token)
+        .await
+        .context("Failed to save personal access token to database")?;
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing personal access token")?;
+
+    Ok((StatusCode::CREATED, Json(CreatePersonalAccessTokenResponse {
+        id: personal_access_token.id,
+        prefix: personal_access_token.prefix(),
+        name: personal_access_token.name,
+        scopes: personal_access_token.scopes,
+        token: token.expose_secret().clone(),
+        created_at: personal_access_token.created_at,
+        expires_at: personal_access_token.expiration.map(|expiration| expiration.0),
+    })))
+}
@@ -0,0 +1,57 @@
+use anyhow::Context;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use domain::user::personal_access_token::{PersonalAccessToken, Scope};
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::HandlerResponse;
+
+#[derive(Serialize)]
+pub struct PersonalAccessTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<PersonalAccessToken> for PersonalAccessTokenResponse {
+    fn from(token: PersonalAccessToken) -> Self {
+        Self {
+            id: token.id,
+            prefix: token.prefix(),
+            name: token.name,
+            scopes: token.scopes,
+            created_at: token.created_at,
+            expires_at: token.expiration.map(|expiration| expiration.0),
+        }
+    }
+}
+
+/// Returns the personal access tokens of the authenticated user which are neither revoked nor expired.
+#[tracing::instrument(
+    name = "Get personal access tokens of authenticated user",
+    skip_all
+)]
+pub async fn get_personal_access_tokens(
+    authenticated_user: SessionUser
+) -> HandlerResponse<Json<Vec<PersonalAccessTokenResponse>>> {
+    let records = authenticated_user.state.db
+        .get_active_personal_access_tokens_by_user_id(authenticated_user.user_id, Utc::now())
+        .await
+        .context("Failed to query personal access tokens of user from Postgres")?;
+
+    let tokens = records.into_iter()
+        .map(|record| {
+            let token: PersonalAccessToken = record.try_into()?;
+            Ok(PersonalAccessTokenResponse::from(token))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context("Failed to parse personal access token records")?;
+
+    Ok(Json(tokens))
+}
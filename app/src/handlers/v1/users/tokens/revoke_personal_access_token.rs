@@ -0,0 +1,48 @@
+use anyhow::Context;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::telemetry::TelemetryRecord;
+
+#[derive(Deserialize)]
+pub struct RevokePersonalAccessTokenParams {
+    token_id: Uuid
+}
+
+#[tracing::instrument(
+    name = "Revoking personal access token of authenticated user",
+    skip(authenticated_user, params),
+    fields(
+        token_id = tracing::field::Empty,
+    )
+)]
+pub async fn revoke_personal_access_token(
+    authenticated_user: SessionUser,
+    Path(params): Path<RevokePersonalAccessTokenParams>
+) -> HandlerResponse<StatusCode> {
+    params.token_id.record_in_telemetry("token_id");
+
+    let mut transaction = authenticated_user.state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to revoke personal access token")?;
+
+    // Tokens of other users are reported as not found, to not leak their existence.
+    let revoked = transaction.revoke_personal_access_token(&params.token_id, &authenticated_user.user_id, &Utc::now())
+        .await
+        .context("Failed to revoke personal access token in database")?;
+
+    if !revoked {
+        return Err(HandlerError::NotFound)
+    }
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing revoked personal access token")?;
+
+    Ok(StatusCode::OK)
+}
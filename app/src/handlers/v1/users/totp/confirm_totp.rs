@@ -0,0 +1,60 @@
+use anyhow::Context;
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::Utc;
+use serde::Deserialize;
+
+use domain::user::totp_credential::Error as TotpCredentialError;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+
+#[derive(Deserialize)]
+pub struct ConfirmTotpRequest {
+    code: String,
+}
+
+/// Confirms the TOTP credential of the authenticated user with the first code of their
+/// authenticator app, after which signing in requires a one-time password.
+#[tracing::instrument(
+    name = "Confirming two-factor authentication of authenticated user",
+    skip(authenticated_user, request)
+)]
+pub async fn confirm_totp(
+    authenticated_user: SessionUser,
+    Json(request): Json<ConfirmTotpRequest>,
+) -> HandlerResponse<StatusCode> {
+    let state = &authenticated_user.state;
+
+    let mut credential = state.db.get_totp_credential(&authenticated_user.user_id.0)
+        .await
+        .context("Failed to get TOTP credential of authenticated user")?
+        .ok_or(HandlerError::NotFound)?
+        .decrypt(&state.totp_secret_encryptor)
+        .context("Failed to decrypt TOTP secret of authenticated user")?;
+
+    match credential.confirm(&request.code, Utc::now()) {
+        Ok(()) => {},
+        Err(TotpCredentialError::AlreadyConfirmed) => return Err(HandlerError::Conflict),
+        Err(TotpCredentialError::InvalidCode | TotpCredentialError::CodeAlreadyUsed) => {
+            return Err(HandlerError::UnprocessableEntity)
+        },
+    }
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store confirmed TOTP credential")?;
+
+    let code_unused = transaction.update_totp_credential_usage(&credential)
+        .await
+        .context("Failed to save confirmed TOTP credential to database")?;
+    if !code_unused {
+        return Err(HandlerError::UnprocessableEntity)
+    }
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing confirmed TOTP credential")?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
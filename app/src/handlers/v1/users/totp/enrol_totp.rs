@@ -0,0 +1,81 @@
+use anyhow::Context;
+use axum::http::StatusCode;
+use axum::Json;
+use secrecy::ExposeSecret;
+use serde::Serialize;
+
+use domain::user::recovery_code::RecoveryCode;
+use domain::user::totp_credential::TotpCredential;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::queries::records::totp_credential_record::TotpCredentialRecord;
+use crate::telemetry::spawn_blocking_with_tracing;
+
+#[derive(Serialize)]
+pub struct EnrolTotpResponse {
+    /// The secret encoded as base32, for users who cannot scan the otpauth URI.
+    pub secret: String,
+    pub otpauth_uri: String,
+
+    /// Single-use codes to sign in with when the authenticator app is unavailable,
+    /// which are only shown once.
+    pub recovery_codes: Vec<String>,
+}
+
+/// Generates a new TOTP secret and recovery codes for the authenticated user, which only protect the
+/// account after the secret has been confirmed with a first code. Enrolling again replaces an
+/// unconfirmed secret together with its recovery codes.
+#[tracing::instrument(
+    name = "Enrolling authenticated user in two-factor authentication",
+    skip(authenticated_user)
+)]
+pub async fn enrol_totp(
+    authenticated_user: SessionUser,
+) -> HandlerResponse<(StatusCode, Json<EnrolTotpResponse>)> {
+    let state = &authenticated_user.state;
+
+    let username = state.db.get_username(authenticated_user.user_id)
+        .await
+        .context("Failed to get username of authenticated user")?
+        .ok_or(HandlerError::NotFound)?;
+
+    let credential = TotpCredential::new(authenticated_user.user_id);
+    let otpauth_uri = credential.secret.otpauth_uri(&state.session_policy.issuer, &username)
+        .context("Failed to create otpauth uri of TOTP secret")?;
+    let record = TotpCredentialRecord::encrypt(&credential, &state.totp_secret_encryptor)
+        .context("Failed to encrypt TOTP secret")?;
+
+    let user_id = authenticated_user.user_id;
+    let (codes, recovery_codes) = spawn_blocking_with_tracing(move || RecoveryCode::generate(user_id))
+        .await
+        .context("Failed to spawn blocking tokio task to generate recovery codes")?
+        .context("Failed to hash recovery codes")?;
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store TOTP credential")?;
+
+    let saved = transaction.save_totp_credential(&record)
+        .await
+        .context("Failed to save TOTP credential to database")?;
+
+    // a confirmed credential has to be kept, enrolling would otherwise disable two-factor authentication
+    if !saved {
+        return Err(HandlerError::Conflict)
+    }
+
+    transaction.replace_recovery_codes(&user_id.0, &recovery_codes)
+        .await
+        .context("Failed to save recovery codes to database")?;
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing TOTP credential")?;
+
+    Ok((StatusCode::CREATED, Json(EnrolTotpResponse {
+        secret: credential.secret.to_base32().expose_secret().clone(),
+        otpauth_uri: otpauth_uri.expose_secret().clone(),
+        recovery_codes: codes.iter().map(|code| code.expose_secret().clone()).collect(),
+    })))
+}
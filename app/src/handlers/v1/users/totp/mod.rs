@@ -0,0 +1,3 @@
+pub mod enrol_totp;
+pub mod confirm_totp;
+pub mod regenerate_recovery_codes;
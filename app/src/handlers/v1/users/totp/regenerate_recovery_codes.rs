@@ -0,0 +1,59 @@
+use anyhow::Context;
+use axum::http::StatusCode;
+use axum::Json;
+use secrecy::ExposeSecret;
+use serde::Serialize;
+
+use domain::user::recovery_code::RecoveryCode;
+
+use crate::extractors::authenticated_user::session_user::SessionUser;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::telemetry::spawn_blocking_with_tracing;
+
+#[derive(Serialize)]
+pub struct RegenerateRecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Replaces the recovery codes of the authenticated user with a new set, e.g. after running out
+/// of codes or when the codes may have been seen by someone else.
+#[tracing::instrument(
+    name = "Regenerating recovery codes of authenticated user",
+    skip(authenticated_user)
+)]
+pub async fn regenerate_recovery_codes(
+    authenticated_user: SessionUser,
+) -> HandlerResponse<(StatusCode, Json<RegenerateRecoveryCodesResponse>)> {
+    let state = &authenticated_user.state;
+    let user_id = authenticated_user.user_id;
+
+    // recovery codes are only of use for users who have two-factor authentication
+    let credential = state.db.get_totp_credential(&user_id.0)
+        .await
+        .context("Failed to get TOTP credential of authenticated user")?;
+    let has_two_factor_authentication = credential.is_some_and(|credential| credential.confirmed_at.is_some());
+    if !has_two_factor_authentication {
+        return Err(HandlerError::NotFound)
+    }
+
+    let (codes, recovery_codes) = spawn_blocking_with_tracing(move || RecoveryCode::generate(user_id))
+        .await
+        .context("Failed to spawn blocking tokio task to generate recovery codes")?
+        .context("Failed to hash recovery codes")?;
+
+    let mut transaction = state.db.new_transaction()
+        .await
+        .context("Failed to begin a transaction to store recovery codes")?;
+
+    transaction.replace_recovery_codes(&user_id.0, &recovery_codes)
+        .await
+        .context("Failed to save recovery codes to database")?;
+
+    transaction.commit()
+        .await
+        .context("Failed to commit transaction containing recovery codes")?;
+
+    Ok((StatusCode::CREATED, Json(RegenerateRecoveryCodesResponse {
+        recovery_codes: codes.iter().map(|code| code.expose_secret().clone()).collect(),
+    })))
+}
@@ -0,0 +1,38 @@
+use anyhow::Context;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+use domain::user::user_id::UserId;
+
+use crate::extractors::user::user_with_policy::UserWithPolicy;
+use crate::handlers::error::{HandlerError, HandlerResponse};
+use crate::policy::policies::unlock_user_policy::UnlockUserPolicy;
+use crate::policy::policy::Policy;
+
+#[derive(Deserialize)]
+pub struct UnlockUserParams {
+    user_id: UserId
+}
+
+#[tracing::instrument(
+    name = "Unlocking account of user",
+    skip(user, params),
+    fields(
+        user_id = %params.user_id,
+    )
+)]
+pub async fn unlock_user(
+    user: UserWithPolicy<UnlockUserPolicy>,
+    Path(params): Path<UnlockUserParams>
+) -> HandlerResponse<StatusCode> {
+    let contract = user.policy.authorize(params.user_id).await?;
+    let user_exists = contract.unlock()
+        .await
+        .context("Failed to unlock account of user")?;
+
+    match user_exists {
+        true => Ok(StatusCode::OK),
+        false => Err(HandlerError::NotFound),
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use serde::Serialize;
+
+use crate::app_state::AppState;
+use crate::handlers::error::HandlerResponse;
+
+#[derive(Serialize)]
+pub struct PaserkResponse {
+    pub keys: Vec<PaserkKeyResponse>,
+}
+
+#[derive(Serialize)]
+pub struct PaserkKeyResponse {
+    /// kid is the PASERK id of the key, as found in the footer of the tokens signed with it.
+    pub kid: String,
+
+    /// key is the public key formatted as PASERK.
+    pub key: String,
+}
+
+/// Publishes the public keys with which the issued tokens can be verified, such that other
+/// services can verify the tokens without contacting this service. There are no public keys
+/// when the tokens are encrypted symmetrically.
+#[tracing::instrument(
+    name = "Get public keys of token signer",
+    skip_all
+)]
+pub async fn paserk(State(state): State<Arc<AppState>>) -> HandlerResponse<Json<PaserkResponse>> {
+    let keys = state.token_encryptor.public_keys()
+        .into_iter()
+        .map(|key| PaserkKeyResponse {
+            kid: key.kid,
+            key: key.key,
+        })
+        .collect();
+
+    Ok(Json(PaserkResponse { keys }))
+}
@@ -1,10 +1,13 @@
 pub mod app_state;
 pub mod configuration;
 pub mod database;
+pub mod email_client;
+pub mod failed_logins;
 pub mod extractors;
 pub mod handlers;
 pub mod queries;
 pub mod routes;
+pub mod session_token_encryptor;
 pub mod startup;
 pub mod telemetry;
 pub mod util;
@@ -5,7 +5,7 @@ use app::app_state::AppState;
 use app::configuration::configuration::get_configuration;
 use app::database::get_connection_pool;
 use app::routes::router;
-use app::startup::{create_root_user, migrate, run};
+use app::startup::{configure_password_hashing, create_root_user, migrate, run};
 use app::telemetry::{get_subscriber, init_subscriber, init_tracer};
 
 #[tokio::main]
@@ -31,6 +31,9 @@ async fn main() -> anyhow::Result<()> {
     );
     init_subscriber(subscriber);
 
+    configure_password_hashing(&configuration.password_hashing)
+        .context("Failed to configure password hashing")?;
+
     // Setup database
     let db_pool = get_connection_pool(&configuration.database);
 
@@ -1 +1,3 @@
-pub mod capture_trace_data;
\ No newline at end of file
+pub mod capture_trace_data;
+pub mod rate_limit;
+pub mod rate_limiter;
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::extract::{FromRef, Request, State};
+use axum::http::header::RETRY_AFTER;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum_extra::headers::authorization::Bearer;
+use axum_extra::headers::{Authorization, HeaderMapExt};
+use secrecy::Secret;
+
+use domain::user::personal_access_token::PersonalAccessToken;
+
+use crate::app_state::AppState;
+use crate::extractors::authenticated_user::verified_access_token::VerifiedAccessToken;
+use crate::extractors::client_device::client_device::client_ip_address_of;
+use crate::middleware::rate_limiter::{RateLimitKey, RateLimiter};
+
+/// RateLimitState is the state of the rate limit middleware of a route group.
+#[derive(Clone)]
+pub struct RateLimitState {
+    pub app_state: Arc<AppState>,
+    pub limiter: Arc<RateLimiter>,
+}
+
+impl FromRef<RateLimitState> for Arc<AppState> {
+    fn from_ref(state: &RateLimitState) -> Self {
+        state.app_state.clone()
+    }
+}
+
+/// Rejects requests with `429 Too Many Requests` once the client has used up its requests, together
+/// with a `Retry-After` header telling when to try again. Requests with a valid access token are
+/// limited per user, other requests per IP address, or per /64 network for IPv6. Requests of which
+/// the client is unknown are not limited.
+#[tracing::instrument(
+    name = "Checking rate limit of request",
+    skip_all,
+    fields(key = tracing::field::Empty)
+)]
+pub async fn rate_limit(
+    State(state): State<RateLimitState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let key = rate_limit_key(&mut parts, &state.app_state);
+    let request = Request::from_parts(parts, body);
+
+    let Some(key) = key else {
+        return next.run(request).await
+    };
+    tracing::Span::current().record("key", tracing::field::debug(&key));
+
+    match state.limiter.check(key) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            // rounded up, so clients do not retry before a request can be made again
+            let retry_after_seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after_seconds.to_string())]).into_response()
+        }
+    }
+}
+
+/// Only the access token itself is verified to find the user, which may be a service account.
+/// Whether its session is still active is left to the
+/// [`crate::extractors::authenticated_user::authenticated_user::AuthenticatedUser`] extractor, to which
+/// the verified token is handed over in the extensions of the request. Requests with a token which cannot
+/// be verified are limited by IP address instead, which includes personal access tokens as they can only
+/// be verified with a database lookup.
+fn rate_limit_key(parts: &mut Parts, state: &AppState) -> Option<RateLimitKey> {
+    let verified_access_token = parts.headers.typed_get::<Authorization<Bearer>>()
+        .map(|Authorization(bearer)| Secret::new(bearer.token().to_string()))
+        .filter(|bearer| !PersonalAccessToken::is_personal_access_token(bearer))
+        .and_then(|bearer| VerifiedAccessToken::verify(&state.new_token_encryptor(), &bearer).ok());
+
+    match verified_access_token {
+        Some(verified_access_token) => {
+            let key = RateLimitKey::User(verified_access_token.user_id().into());
+            parts.extensions.insert(verified_access_token);
+            Some(key)
+        },
+        None => client_ip_address_of(parts, &state.trusted_proxies).map(RateLimitKey::ip),
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use domain::user::user_id::UserId;
+
+/// RateLimit is the token bucket of a rate limiter. Every request takes a token out of the bucket,
+/// which is refilled at a steady rate. Allows short bursts of up to `capacity` requests, while
+/// limiting the sustained rate to `refill_per_second` requests per second.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// capacity is the amount of requests that can be made at once.
+    pub capacity: u32,
+
+    /// refill_per_second is the amount of requests per second that can be made after the bucket is empty.
+    pub refill_per_second: f64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RateLimitError {
+    #[error("Capacity of rate limit must be at least 1")]
+    CapacityTooLow,
+
+    #[error("Refill rate of rate limit must be positive")]
+    RefillRateNotPositive,
+
+    #[error("Refill rate of rate limit is too low to refill the bucket in a representable duration")]
+    RefillRateTooLow,
+}
+
+impl RateLimit {
+    pub fn validate(&self) -> Result<(), RateLimitError> {
+        if self.capacity == 0 {
+            return Err(RateLimitError::CapacityTooLow)
+        }
+
+        // also rejects NaN, which is neither positive nor finite
+        if !(self.refill_per_second > 0.0 && self.refill_per_second.is_finite()) {
+            return Err(RateLimitError::RefillRateNotPositive)
+        }
+
+        // the limiter computes how long an empty bucket takes to refill, which must not overflow
+        Duration::try_from_secs_f64(self.capacity as f64 / self.refill_per_second)
+            .map_err(|_| RateLimitError::RefillRateTooLow)?;
+
+        Ok(())
+    }
+}
+
+/// RateLimitKey identifies whose requests are counted together. Requests of authenticated users
+/// are counted per user, regardless of their IP address. Anonymous requests are counted per IP address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitKey {
+    User(UserId),
+    Ip(IpAddr),
+}
+
+impl RateLimitKey {
+    /// Returns the key of the IP address of a client. IPv6 addresses are counted per /64 network,
+    /// as a single client is usually assigned a whole /64 and could otherwise rotate through its addresses.
+    pub fn ip(address: IpAddr) -> Self {
+        match address.to_canonical() {
+            IpAddr::V6(address) => {
+                let network = u128::from(address) & !(u128::MAX >> 64);
+                RateLimitKey::Ip(IpAddr::V6(Ipv6Addr::from(network)))
+            },
+            address => RateLimitKey::Ip(address),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<RateLimitKey, Bucket>,
+    evicted_at: Instant,
+}
+
+/// RateLimiters are the rate limiters of the route groups, each group has its own limits.
+#[derive(Debug, Clone)]
+pub struct RateLimiters {
+    /// auth limits the authentication endpoints, which are stricter as they are targeted by brute-force attacks.
+    pub auth: Arc<RateLimiter>,
+
+    /// read limits the endpoints which only read data.
+    pub read: Arc<RateLimiter>,
+
+    /// write limits the endpoints which modify data.
+    pub write: Arc<RateLimiter>,
+}
+
+/// RateLimiter keeps a token bucket per key in memory.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Result<Self, RateLimitError> {
+        limit.validate()?;
+
+        Ok(Self {
+            limit,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                evicted_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Takes a token out of the bucket of the key, returns how long to wait
+    /// before the next request can be made when the bucket is empty.
+    pub fn check(&self, key: RateLimitKey) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: RateLimitKey, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock()
+            .expect("RateLimiter mutex should not be poisoned");
+
+        let capacity = self.limit.capacity as f64;
+        if now >= buckets.evicted_at + self.refill_duration() {
+            // full buckets are the same as no bucket at all, which are evicted once every time an empty
+            // bucket takes to refill, such that the buckets of clients gone since the last eviction are gone
+            buckets.buckets.retain(|_, bucket| self.refill(bucket, now) < capacity);
+            buckets.evicted_at = now;
+        }
+
+        let bucket = buckets.buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+
+        bucket.tokens = self.refill(bucket, now);
+        bucket.refilled_at = now;
+
+        if bucket.tokens < 1.0 {
+            let retry_after = (1.0 - bucket.tokens) / self.limit.refill_per_second;
+            return Err(Duration::from_secs_f64(retry_after))
+        }
+
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Returns how long it takes for an empty bucket to be completely refilled.
+    fn refill_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.limit.capacity as f64 / self.limit.refill_per_second)
+    }
+
+    /// Returns the tokens of the bucket refilled up until the given time.
+    fn refill(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+        (bucket.tokens + elapsed * self.limit.refill_per_second).min(self.limit.capacity as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::time::{Duration, Instant};
+
+    use uuid::Uuid;
+
+    use crate::middleware::rate_limiter::{RateLimit, RateLimitKey, RateLimiter};
+
+    fn ip_key(address: &str) -> RateLimitKey {
+        RateLimitKey::Ip(address.parse::<IpAddr>().expect("Failed to parse ip address"))
+    }
+
+    #[test]
+    fn test_allows_bursts_up_to_capacity() {
+        let limiter = RateLimiter::new(RateLimit { capacity: 3, refill_per_second: 1.0 }).unwrap();
+        let now = Instant::now();
+        let key = ip_key("10.0.0.1");
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check_at(key, now), Ok(()));
+        }
+        assert_eq!(limiter.check_at(key, now), Err(Duration::from_secs(1)));
+
+        // other keys have their own bucket
+        assert_eq!(limiter.check_at(ip_key("10.0.0.2"), now), Ok(()));
+        assert_eq!(limiter.check_at(RateLimitKey::User(Uuid::new_v4().into()), now), Ok(()));
+    }
+
+    #[test]
+    fn test_bucket_is_refilled_over_time() {
+        let limiter = RateLimiter::new(RateLimit { capacity: 2, refill_per_second: 2.0 }).unwrap();
+        let now = Instant::now();
+        let key = ip_key("10.0.0.1");
+
+        assert_eq!(limiter.check_at(key, now), Ok(()));
+        assert_eq!(limiter.check_at(key, now), Ok(()));
+        assert_eq!(limiter.check_at(key, now + Duration::from_millis(250)), Err(Duration::from_millis(250)));
+        assert_eq!(limiter.check_at(key, now + Duration::from_millis(500)), Ok(()));
+
+        // the bucket never holds more than its capacity
+        let later = now + Duration::from_secs(60);
+        assert_eq!(limiter.check_at(key, later), Ok(()));
+        assert_eq!(limiter.check_at(key, later), Ok(()));
+        assert!(limiter.check_at(key, later).is_err());
+    }
+
+    #[test]
+    fn test_full_buckets_are_evicted() {
+        let limiter = RateLimiter::new(RateLimit { capacity: 1, refill_per_second: 1.0 }).unwrap();
+        let now = Instant::now();
+
+        limiter.check_at(ip_key("10.0.0.1"), now).unwrap();
+        limiter.check_at(ip_key("10.0.0.2"), now + Duration::from_secs(1)).unwrap();
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 1);
+    }
+
+    #[test]
+    fn test_buckets_are_not_evicted_before_they_could_be_refilled() {
+        let limiter = RateLimiter::new(RateLimit { capacity: 2, refill_per_second: 1.0 }).unwrap();
+        let now = Instant::now();
+
+        // the first bucket is full again, but it takes two seconds to refill an empty bucket
+        limiter.check_at(ip_key("10.0.0.1"), now).unwrap();
+        limiter.check_at(ip_key("10.0.0.2"), now + Duration::from_millis(1750)).unwrap();
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 2);
+
+        limiter.check_at(ip_key("10.0.0.3"), now + Duration::from_millis(2500)).unwrap();
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 2);
+    }
+
+    #[test]
+    fn test_ipv6_addresses_are_limited_per_network() {
+        let key = RateLimitKey::ip("2001:db8:1:2:3:4:5:6".parse().unwrap());
+        assert_eq!(key, RateLimitKey::ip("2001:db8:1:2:ffff:ffff:ffff:ffff".parse().unwrap()));
+        assert_eq!(key, ip_key("2001:db8:1:2::"));
+        assert_ne!(key, RateLimitKey::ip("2001:db8:1:3::1".parse().unwrap()));
+
+        // IPv4 addresses are limited per address, also when mapped to IPv6
+        assert_eq!(RateLimitKey::ip("::ffff:10.0.0.1".parse().unwrap()), ip_key("10.0.0.1"));
+        assert_ne!(RateLimitKey::ip("10.0.0.1".parse().unwrap()), ip_key("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_invalid_rate_limits_are_rejected() {
+        assert!(RateLimiter::new(RateLimit { capacity: 0, refill_per_second: 1.0 }).is_err());
+        assert!(RateLimiter::new(RateLimit { capacity: 1, refill_per_second: 0.0 }).is_err());
+        assert!(RateLimiter::new(RateLimit { capacity: 1, refill_per_second: f64::NAN }).is_err());
+        assert!(RateLimiter::new(RateLimit { capacity: 1, refill_per_second: f64::INFINITY }).is_err());
+        assert!(RateLimiter::new(RateLimit { capacity: 1, refill_per_second: 1e-20 }).is_err());
+        assert!(RateLimiter::new(RateLimit { capacity: 1, refill_per_second: f64::MIN_POSITIVE }).is_err());
+    }
+}
@@ -7,6 +7,7 @@ use axum::async_trait;
 use domain::role::role::SystemRole;
 use domain::team::member::Member;
 use domain::team::team_id::TeamId;
+use domain::user::personal_access_token::Scope;
 use domain::user::user_details::UserDetails;
 use domain::user::user_id::UserId;
 use std::sync::Arc;
@@ -37,6 +38,7 @@ impl Policy for AddTeamMemberPolicy {
         }
     }
 
+    const REQUIRED_SCOPE: Scope = Scope::Write;
     type Details = TeamId;
     type Contract = AddMemberContract;
 
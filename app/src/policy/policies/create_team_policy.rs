@@ -6,6 +6,7 @@ use axum::async_trait;
 use domain::role::role::SystemRole;
 use domain::team::team::Team;
 use domain::team::team_id::TeamId;
+use domain::user::personal_access_token::Scope;
 use domain::user::user_details::UserDetails;
 use domain::user::user_id::UserId;
 use std::sync::Arc;
@@ -31,6 +32,7 @@ impl Policy for CreateTeamPolicy {
         }
     }
 
+    const REQUIRED_SCOPE: Scope = Scope::Write;
     type Details = ();
     type Contract = CreateTeamContract;
 
@@ -5,6 +5,8 @@ use domain::user::new_user::NewUser;
 use anyhow::Context;
 use axum::async_trait;
 
+use domain::user::personal_access_token::Scope;
+use domain::user::service_account::ServiceAccount;
 use domain::user::user_details::UserDetails;
 use domain::role::role::SystemRole;
 use domain::team::member::Member;
@@ -35,6 +37,7 @@ impl Policy for CreateUserPolicy {
         }
     }
 
+    const REQUIRED_SCOPE: Scope = Scope::Write;
     type Details = CreateUserDetails;
     type Contract = CreateUserContract;
 
@@ -89,13 +92,14 @@ pub struct CreateUserContract {
 
 impl CreateUserContract {
     
-    pub async fn create_user(&self, new_user: UserCredentials) -> sqlx::Result<()> {
+    pub async fn create_user(&self, new_user: UserCredentials, email: Option<String>) -> sqlx::Result<()> {
         let mut transaction = self.state.db.new_transaction().await?;
 
         transaction.save_new_user(&NewUser {
             id: new_user.id,
             username: new_user.username,
             password: new_user.password,
+            email,
             system_role: self.details.role,
         }).await?;
 
@@ -110,4 +114,22 @@ impl CreateUserContract {
         transaction.commit().await?;
         Ok(())
     }
+
+    /// Creates the user of a service account, which is subject to the same rules as any other user.
+    pub async fn create_service_account(&self, service_account: &ServiceAccount, name: &str) -> sqlx::Result<()> {
+        let mut transaction = self.state.db.new_transaction().await?;
+
+        transaction.save_service_account(service_account, name, self.details.role).await?;
+
+        if let Some(team_id) = self.details.team_to_part_of {
+            transaction.save_team_member(Member {
+                user_id: service_account.user_id,
+                team_id,
+                manager: false,
+            }).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
 }
\ No newline at end of file
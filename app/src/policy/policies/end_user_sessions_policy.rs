@@ -0,0 +1,93 @@
+use crate::app_state::AppState;
+use crate::policy::policy::Policy;
+use crate::policy::policy_authorization_error::PolicyRejectionError;
+use anyhow::Context;
+use axum::async_trait;
+use domain::role::role::SystemRole;
+use domain::user::personal_access_token::Scope;
+use domain::user::user_details::UserDetails;
+use domain::user::user_id::UserId;
+use std::sync::Arc;
+
+pub struct EndUserSessionsPolicy {
+    state: Arc<AppState>,
+    principle: UserDetails
+}
+
+#[async_trait]
+impl Policy for EndUserSessionsPolicy {
+
+    async fn new(state: Arc<AppState>, principle_id: UserId) -> Result<Self, PolicyRejectionError> {
+        let principle = state.db.get_user_details(principle_id).await
+            .context("Failed to user details for principle")?;
+
+        match principle {
+            None => Err(PolicyRejectionError::Forbidden),
+            Some(principle) => Ok(Self {
+                state,
+                principle
+            })
+        }
+    }
+
+    const REQUIRED_SCOPE: Scope = Scope::Write;
+    type Details = UserId;
+    type Contract = EndUserSessionsContract;
+
+    async fn authorize(&self, user_id: Self::Details) -> Result<Self::Contract, PolicyRejectionError> {
+        let principle_role = match self.principle.system_role {
+            Some(role) => role,
+            None => return Err(PolicyRejectionError::Forbidden)
+        };
+
+        let user_details = self.state.db.get_user_details(user_id)
+            .await
+            .with_context(|| format!("Failed to get UserDetails for user: {}", user_id))?;
+
+        let user_exists = user_details.is_some();
+        let role_of_user = user_details.and_then(|details| details.system_role);
+
+        // Admins may end the sessions of anyone but root, root may end the sessions of anyone.
+        match (principle_role, role_of_user) {
+            (SystemRole::Root, _) |
+            (SystemRole::Admin, None) |
+            (SystemRole::Admin, Some(SystemRole::Admin)) => Ok(EndUserSessionsContract {
+                state: self.state.clone(),
+                user_id,
+                user_exists,
+            }),
+            (_, _) => Err(PolicyRejectionError::Forbidden)
+        }
+    }
+}
+
+pub struct EndUserSessionsContract {
+    state: Arc<AppState>,
+    user_id: UserId,
+    user_exists: bool,
+}
+
+impl EndUserSessionsContract {
+
+    /// Ends all active sessions of the user, returns the number of sessions that were ended.
+    /// Returns None when the user does not exist.
+    pub async fn end_sessions(&self) -> Result<Option<usize>, sqlx::Error> {
+        if !self.user_exists {
+            return Ok(None)
+        }
+
+        let sessions = self.state.db.get_active_sessions_by_user_id(self.user_id).await?;
+        let number_of_sessions = sessions.len();
+
+        let mut ended_session_ids = Vec::with_capacity(number_of_sessions);
+        let mut transaction = self.state.db.new_transaction().await?;
+        for session in sessions {
+            ended_session_ids.push(*session.id());
+            transaction.save_just_ended_session(&session.end_by_admin_revocation()).await?;
+        }
+
+        transaction.commit().await?;
+        self.state.remember_ended_sessions(ended_session_ids);
+        Ok(Some(number_of_sessions))
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Context;
 use axum::async_trait;
 use domain::role::role::{SystemRole};
 use domain::team::team_id::TeamId;
+use domain::user::personal_access_token::Scope;
 use domain::user::user_id::UserId;
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -30,6 +31,7 @@ impl Policy for GetTeamsPolicy {
         }
     }
 
+    const REQUIRED_SCOPE: Scope = Scope::Read;
     type Details = ();
     type Contract = ViewTeamsContract;
 
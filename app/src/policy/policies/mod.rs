@@ -3,4 +3,7 @@ pub mod create_team_policy;
 pub mod get_teams_policy;
 pub mod get_team_members_policy;
 pub mod create_user_policy;
-pub mod read_user_details_policy;
\ No newline at end of file
+pub mod read_user_details_policy;
+pub mod end_user_sessions_policy;
+pub mod read_session_token_lineage_policy;
+pub mod unlock_user_policy;
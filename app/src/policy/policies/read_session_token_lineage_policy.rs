@@ -0,0 +1,69 @@
+use crate::app_state::AppState;
+use crate::policy::policy::Policy;
+use crate::policy::policy_authorization_error::PolicyRejectionError;
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
+use crate::queries::records::user_session_record::UserSessionRecord;
+use anyhow::Context;
+use axum::async_trait;
+use domain::user::personal_access_token::Scope;
+use domain::user::user_details::UserDetails;
+use domain::user::user_id::UserId;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ReadSessionTokenLineagePolicy {
+    state: Arc<AppState>,
+    principle: UserDetails
+}
+
+#[async_trait]
+impl Policy for ReadSessionTokenLineagePolicy {
+
+    async fn new(state: Arc<AppState>, principle_id: UserId) -> Result<Self, PolicyRejectionError> {
+        let principle = state.db.get_user_details(principle_id).await
+            .context("Failed to user details for principle")?;
+
+        match principle {
+            None => Err(PolicyRejectionError::Forbidden),
+            Some(principle) => Ok(Self {
+                state,
+                principle
+            })
+        }
+    }
+
+    const REQUIRED_SCOPE: Scope = Scope::Read;
+    type Details = Uuid;
+    type Contract = ReadSessionTokenLineageContract;
+
+    async fn authorize(&self, session_id: Self::Details) -> Result<Self::Contract, PolicyRejectionError> {
+        // The token lineage is meant for investigating suspicious sessions,
+        // therefore only admins and root users are allowed to read it.
+        match self.principle.system_role {
+            Some(_) => Ok(ReadSessionTokenLineageContract {
+                state: self.state.clone(),
+                session_id,
+            }),
+            None => Err(PolicyRejectionError::Forbidden)
+        }
+    }
+}
+
+pub struct ReadSessionTokenLineageContract {
+    state: Arc<AppState>,
+    session_id: Uuid
+}
+
+impl ReadSessionTokenLineageContract {
+
+    /// Returns the session together with all of its refresh tokens, ordered from first to last issued.
+    pub async fn get_token_lineage(&self) -> Result<Option<(UserSessionRecord, Vec<RefreshTokenRecord>)>, sqlx::Error> {
+        let session = match self.state.db.get_session_by_id(&self.session_id).await? {
+            None => return Ok(None),
+            Some(session) => session
+        };
+
+        let refresh_tokens = self.state.db.get_refresh_tokens_by_session_id(&self.session_id).await?;
+        Ok(Some((session, refresh_tokens)))
+    }
+}
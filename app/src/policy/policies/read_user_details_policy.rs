@@ -4,6 +4,7 @@ use crate::policy::policy_authorization_error::PolicyRejectionError;
 use anyhow::Context;
 use axum::async_trait;
 use domain::role::role::SystemRole;
+use domain::user::personal_access_token::Scope;
 use domain::user::user_details::UserDetails;
 use domain::user::user_id::UserId;
 use std::sync::Arc;
@@ -29,6 +30,7 @@ impl Policy for ReadUserDetailsPolicy {
         }
     }
 
+    const REQUIRED_SCOPE: Scope = Scope::Read;
     type Details = UserId;
     type Contract = ReadUserDetailsContract;
 
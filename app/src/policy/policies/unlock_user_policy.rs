@@ -0,0 +1,83 @@
+use crate::app_state::AppState;
+use crate::policy::policy::Policy;
+use crate::policy::policy_authorization_error::PolicyRejectionError;
+use anyhow::Context;
+use axum::async_trait;
+use domain::role::role::SystemRole;
+use domain::user::personal_access_token::Scope;
+use domain::user::user_details::UserDetails;
+use domain::user::user_id::UserId;
+use std::sync::Arc;
+
+pub struct UnlockUserPolicy {
+    state: Arc<AppState>,
+    principle: UserDetails
+}
+
+#[async_trait]
+impl Policy for UnlockUserPolicy {
+
+    async fn new(state: Arc<AppState>, principle_id: UserId) -> Result<Self, PolicyRejectionError> {
+        let principle = state.db.get_user_details(principle_id).await
+            .context("Failed to user details for principle")?;
+
+        match principle {
+            None => Err(PolicyRejectionError::Forbidden),
+            Some(principle) => Ok(Self {
+                state,
+                principle
+            })
+        }
+    }
+
+    const REQUIRED_SCOPE: Scope = Scope::Write;
+    type Details = UserId;
+    type Contract = UnlockUserContract;
+
+    async fn authorize(&self, user_id: Self::Details) -> Result<Self::Contract, PolicyRejectionError> {
+        let principle_role = match self.principle.system_role {
+            Some(role) => role,
+            None => return Err(PolicyRejectionError::Forbidden)
+        };
+
+        let user_details = self.state.db.get_user_details(user_id)
+            .await
+            .with_context(|| format!("Failed to get UserDetails for user: {}", user_id))?;
+
+        let role_of_user = user_details.and_then(|details| details.system_role);
+
+        // Admins may unlock anyone but root, root may unlock anyone.
+        match (principle_role, role_of_user) {
+            (SystemRole::Root, _) |
+            (SystemRole::Admin, None) |
+            (SystemRole::Admin, Some(SystemRole::Admin)) => Ok(UnlockUserContract {
+                state: self.state.clone(),
+                user_id,
+            }),
+            (_, _) => Err(PolicyRejectionError::Forbidden)
+        }
+    }
+}
+
+pub struct UnlockUserContract {
+    state: Arc<AppState>,
+    user_id: UserId
+}
+
+impl UnlockUserContract {
+
+    /// Forgets the failed login attempts of the user, which lifts any lockout of the account.
+    /// Returns false when the user does not exist.
+    pub async fn unlock(&self) -> anyhow::Result<bool> {
+        let username = self.state.db.get_username(self.user_id)
+            .await
+            .context("Failed to get username of user")?;
+
+        let Some(username) = username else {
+            return Ok(false)
+        };
+
+        self.state.failed_logins.clear(&username).await?;
+        Ok(true)
+    }
+}
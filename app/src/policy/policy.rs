@@ -2,6 +2,7 @@ use std::error::Error;
 use std::sync::Arc;
 use axum::async_trait;
 use axum::response::IntoResponse;
+use domain::user::personal_access_token::Scope;
 use domain::user::user_id::UserId;
 use crate::app_state::AppState;
 use crate::policy::policy_authorization_error::PolicyRejectionError;
@@ -16,6 +17,10 @@ pub trait Policy: Sized {
     /// new is a factory method for creating a new instance of the Policy for the given user.
     async fn new(state: Arc<AppState>, user: UserId) -> Result<Self, PolicyRejectionError>;
 
+    /// REQUIRED_SCOPE is the scope a personal access token must carry for the user to be subject
+    /// to the Policy at all, regardless of the permissions of the user.
+    const REQUIRED_SCOPE: Scope;
+
     /// Details contains the necessary information for the Policy to understand the resource for 
     /// which the Policy is to dictate if the user is authorized or not perform an action on that 
     /// resource.
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use sqlx::query_file_as;
+
+use domain::user::user_id::UserId;
+
+use crate::queries::database::Database;
+use crate::queries::records::personal_access_token_record::PersonalAccessTokenRecord;
+
+impl Database {
+
+    /// Returns the personal access tokens of the user which are neither revoked nor expired at the given time.
+    #[tracing::instrument(
+    name = "Querying Postgres for active personal access tokens of user",
+    skip(self, user_id, now),
+    fields(user_id = % user_id.0)
+    )]
+    pub async fn get_active_personal_access_tokens_by_user_id(
+        &self,
+        user_id: UserId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PersonalAccessTokenRecord>, sqlx::Error> {
+        query_file_as!(
+            PersonalAccessTokenRecord,
+            "src/queries/get_active_personal_access_tokens_by_user_id.sql",
+            user_id.0,
+            now.naive_utc(),
+        ).fetch_all(self.db()).await
+    }
+}
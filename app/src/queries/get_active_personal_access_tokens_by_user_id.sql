@@ -0,0 +1,5 @@
+SELECT * FROM personal_access_tokens
+WHERE user_id = $1
+  AND revoked_at IS NULL
+  AND (expires_at IS NULL OR expires_at > $2)
+ORDER BY created_at;
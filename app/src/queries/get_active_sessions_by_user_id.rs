@@ -0,0 +1,93 @@
+use sqlx::query_file_as;
+use tracing::warn;
+
+use domain::sessions::state::active::Active;
+use domain::sessions::user_session::UserSession;
+use domain::user::user_id::UserId;
+
+use crate::queries::database::Database;
+use crate::queries::records::user_session_record::UserSessionRecord;
+
+impl Database {
+    #[tracing::instrument(
+    name = "Querying Postgres for active sessions of user",
+    skip(self, user_id),
+    fields(user_id = % user_id)
+    )]
+    pub async fn get_active_sessions_by_user_id(
+        &self,
+        user_id: UserId
+    ) -> Result<Vec<UserSession<Active>>, sqlx::Error> {
+        let records = query_file_as!(
+            UserSessionRecord,
+            "src/queries/get_active_sessions_by_user_id.sql",
+            user_id.0
+        ).fetch_all(self.db()).await?;
+
+        let mut sessions = Vec::with_capacity(records.len());
+        for record in records {
+            match self.get_latest_token_for_session(&record.id).await? {
+                None => {
+                    warn!("Expected to find a refresh token for the active session of id: {}, but did not find one", record.id);
+                }
+                Some(token) => sessions.push(record.to_active_session(token))
+            }
+        }
+
+        Ok(sessions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+
+    use test_utility::random::_common::{random_salt, random_secret};
+    use test_utility::random::user::random_new_user;
+    use test_utility::random::user_session::random_newly_created_user_session;
+
+    use crate::queries::database::Database;
+
+    #[sqlx::test]
+    async fn test_get_active_sessions_by_user_id(db: PgPool) {
+        let db = Database(db);
+        let mut transaction = db.new_transaction().await.expect("Failed to create transaction");
+
+        let salt = random_salt();
+        let user = random_new_user(random_secret(), &salt);
+        transaction.save_new_user(&user)
+            .await
+            .expect("Failed to create user");
+
+        let first_session = random_newly_created_user_session(user.id);
+        let second_session = random_newly_created_user_session(user.id);
+        transaction.save_newly_created_user_session(&first_session)
+            .await
+            .expect("Failed to save first user session");
+        transaction.save_newly_created_user_session(&second_session)
+            .await
+            .expect("Failed to save second user session");
+
+        transaction.commit().await.expect("Failed to commit transaction");
+
+        // end the first session, leaving only the second session active
+        let ended_session = db.get_active_session_by_id(first_session.id())
+            .await
+            .expect("Failed to get active session by id")
+            .expect("Failed to find active session by id")
+            .end_by_user_revocation();
+
+        let mut transaction = db.new_transaction().await.expect("Failed to create transaction");
+        transaction.save_just_ended_session(&ended_session)
+            .await
+            .expect("Failed to save ended session");
+        transaction.commit().await.expect("Failed to commit transaction");
+
+        let sessions = db.get_active_sessions_by_user_id(user.id)
+            .await
+            .expect("Failed to get active sessions of user");
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id(), second_session.id());
+    }
+}
@@ -0,0 +1,4 @@
+SELECT * FROM user_sessions
+WHERE user_sessions.user_id = $1
+  AND user_sessions.ended_at IS NULL
+ORDER BY user_sessions.created_at;
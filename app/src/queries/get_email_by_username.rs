@@ -0,0 +1,20 @@
+use sqlx::query_file;
+
+use domain::user::user_id::UserId;
+
+use crate::queries::database::Database;
+
+impl Database {
+
+    /// Returns the id and email of the user with the given username,
+    /// or None when there is no such user or the user has no email.
+    #[tracing::instrument(name = "Fetching email for username", skip(self, username))]
+    pub async fn get_email_by_username(&self, username: &str) -> sqlx::Result<Option<(UserId, String)>> {
+        let row = query_file!(
+            "src/queries/get_email_by_username.sql",
+            username
+        ).fetch_optional(self.db()).await?;
+
+        Ok(row.map(|row| (row.user_id.into(), row.email)))
+    }
+}
@@ -0,0 +1,3 @@
+SELECT user_id, email AS "email!" FROM users
+WHERE username = $1
+  AND email IS NOT NULL;
@@ -0,0 +1,15 @@
+use sqlx::query_file_as;
+
+use crate::queries::database::Database;
+use crate::queries::records::failed_logins_record::FailedLoginsRecord;
+
+impl Database {
+    #[tracing::instrument(name = "Querying Postgres for failed logins of username", skip(self))]
+    pub async fn get_failed_logins(&self, username: &str) -> sqlx::Result<Option<FailedLoginsRecord>> {
+        query_file_as!(
+            FailedLoginsRecord,
+            "src/queries/get_failed_logins.sql",
+            username
+        ).fetch_optional(self.db()).await
+    }
+}
@@ -0,0 +1,2 @@
+SELECT * FROM failed_logins
+WHERE username = $1;
@@ -1,9 +1,6 @@
 use sqlx::query_file_as;
 use uuid::Uuid;
 
-use domain::sessions::tokens::RefreshToken;
-use domain::sessions::user_session_token::UserSessionToken;
-
 use crate::queries::database::Database;
 use crate::queries::records::refresh_token_record::RefreshTokenRecord;
 
@@ -16,20 +13,14 @@ impl Database {
     pub async fn get_latest_token_for_session(
         &self,
         session_id: &Uuid,
-    ) -> Result<Option<UserSessionToken<RefreshToken>>, sqlx::Error> {
-        let query_result = query_file_as!(
+    ) -> Result<Option<RefreshTokenRecord>, sqlx::Error> {
+        query_file_as!(
         RefreshTokenRecord,
         "src/queries/get_latest_token_for_session.sql",
         session_id
     )
             .fetch_optional(self.db())
-            .await?;
-
-        if let Some(token) = query_result {
-            return Ok(Some(token.into()))
-        }
-
-        Ok(None)
+            .await
     }
 
 }
@@ -85,13 +76,12 @@ mod tests {
             transaction.commit().await.expect("Failed to commit new refresh token");
 
             // get latest refresh token
-            let token = db.get_latest_token_for_session(session.id())
+            let got = db.get_latest_token_for_session(session.id())
                 .await
                 .expect("Failed to query database to get latest session token")
                 .expect("Failed to find latest session token");
 
             let expected = RefreshTokenRecord::from(&latest_refresh_token);
-            let got = RefreshTokenRecord::from(&token);
 
             println!("expected: {}", expected.id);
             println!("got: {}", got.id);
@@ -0,0 +1,18 @@
+use secrecy::Secret;
+use sqlx::query_file;
+
+use domain::user::user_id::UserId;
+
+use crate::queries::database::Database;
+
+impl Database {
+    #[tracing::instrument(name = "Fetching password hash for user id", skip(self))]
+    pub async fn get_password_hash(&self, user_id: UserId) -> sqlx::Result<Option<Secret<String>>> {
+        let row = query_file!(
+            "src/queries/get_password_hash.sql",
+            user_id.0
+        ).fetch_optional(self.db()).await?;
+
+        Ok(row.map(|row| Secret::new(row.password_hash)))
+    }
+}
@@ -0,0 +1,3 @@
+SELECT password_hash AS "password_hash!" FROM users
+WHERE user_id = $1
+  AND password_hash IS NOT NULL;
@@ -0,0 +1,23 @@
+use sqlx::query_file_as;
+use uuid::Uuid;
+
+use crate::queries::database::Database;
+use crate::queries::records::password_reset_token_record::PasswordResetTokenRecord;
+
+impl Database {
+    #[tracing::instrument(
+    name = "Querying Postgres for password reset token",
+    skip(self, token_id),
+    fields(token_id = % token_id)
+    )]
+    pub async fn get_password_reset_token(
+        &self,
+        token_id: &Uuid,
+    ) -> Result<Option<PasswordResetTokenRecord>, sqlx::Error> {
+        query_file_as!(
+            PasswordResetTokenRecord,
+            "src/queries/get_password_reset_token.sql",
+            token_id
+        ).fetch_optional(self.db()).await
+    }
+}
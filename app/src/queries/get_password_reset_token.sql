@@ -0,0 +1,2 @@
+SELECT * FROM password_reset_tokens
+WHERE id = $1;
@@ -0,0 +1,23 @@
+use sqlx::query_file_as;
+use uuid::Uuid;
+
+use crate::queries::database::Database;
+use crate::queries::records::personal_access_token_record::PersonalAccessTokenRecord;
+
+impl Database {
+    #[tracing::instrument(
+    name = "Querying Postgres for personal access token",
+    skip(self, token_id),
+    fields(token_id = % token_id)
+    )]
+    pub async fn get_personal_access_token(
+        &self,
+        token_id: &Uuid,
+    ) -> Result<Option<PersonalAccessTokenRecord>, sqlx::Error> {
+        query_file_as!(
+            PersonalAccessTokenRecord,
+            "src/queries/get_personal_access_token.sql",
+            token_id
+        ).fetch_optional(self.db()).await
+    }
+}
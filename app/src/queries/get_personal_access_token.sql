@@ -0,0 +1,2 @@
+SELECT * FROM personal_access_tokens
+WHERE id = $1;
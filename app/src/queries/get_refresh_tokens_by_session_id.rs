@@ -0,0 +1,22 @@
+use sqlx::query_file_as;
+use uuid::Uuid;
+use crate::queries::database::Database;
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
+
+impl Database {
+    #[tracing::instrument(
+    name = "Querying Postgres for all refresh tokens of session",
+    skip(self, session_id),
+    fields(session_id = % session_id)
+    )]
+    pub async fn get_refresh_tokens_by_session_id(
+        &self,
+        session_id: &Uuid,
+    ) -> Result<Vec<RefreshTokenRecord>, sqlx::Error> {
+        query_file_as!(
+            RefreshTokenRecord,
+            "src/queries/get_refresh_tokens_by_session_id.sql",
+            session_id
+        ).fetch_all(self.db()).await
+    }
+}
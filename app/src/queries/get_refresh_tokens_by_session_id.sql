@@ -0,0 +1,3 @@
+SELECT * FROM refresh_tokens
+WHERE refresh_tokens.session_id = $1
+ORDER BY refresh_tokens.issued_at;
@@ -0,0 +1,18 @@
+use sqlx::query_file_as;
+
+use crate::queries::database::Database;
+use crate::queries::records::service_account_record::ServiceAccountRecord;
+
+impl Database {
+    #[tracing::instrument(name = "Querying Postgres for service account by client id", skip(self))]
+    pub async fn get_service_account_by_client_id(
+        &self,
+        client_id: &str,
+    ) -> Result<Option<ServiceAccountRecord>, sqlx::Error> {
+        query_file_as!(
+            ServiceAccountRecord,
+            "src/queries/get_service_account_by_client_id.sql",
+            client_id
+        ).fetch_optional(self.db()).await
+    }
+}
@@ -0,0 +1,2 @@
+SELECT * FROM service_accounts
+WHERE client_id = $1;
@@ -0,0 +1,24 @@
+use sqlx::query_file_as;
+use uuid::Uuid;
+use crate::queries::database::Database;
+use crate::queries::records::user_session_record::UserSessionRecord;
+
+impl Database {
+
+    /// Returns the session for the given id, regardless of whether it has ended.
+    #[tracing::instrument(
+    name = "Querying Postgres for session by id",
+    skip(self, session_id),
+    fields(session_id = % session_id)
+    )]
+    pub async fn get_session_by_id(
+        &self,
+        session_id: &Uuid,
+    ) -> Result<Option<UserSessionRecord>, sqlx::Error> {
+        query_file_as!(
+            UserSessionRecord,
+            "src/queries/get_session_by_id.sql",
+            session_id
+        ).fetch_optional(self.db()).await
+    }
+}
@@ -0,0 +1,2 @@
+SELECT * FROM user_sessions
+WHERE user_sessions.id = $1;
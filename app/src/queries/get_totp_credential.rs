@@ -0,0 +1,25 @@
+use sqlx::query_file_as;
+use uuid::Uuid;
+
+use crate::queries::database::Database;
+use crate::queries::records::totp_credential_record::TotpCredentialRecord;
+
+impl Database {
+
+    /// Returns the TOTP credential of the user, regardless of whether it has been confirmed.
+    #[tracing::instrument(
+    name = "Querying Postgres for TOTP credential of user",
+    skip(self, user_id),
+    fields(user_id = % user_id)
+    )]
+    pub async fn get_totp_credential(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Option<TotpCredentialRecord>, sqlx::Error> {
+        query_file_as!(
+            TotpCredentialRecord,
+            "src/queries/get_totp_credential.sql",
+            user_id
+        ).fetch_optional(self.db()).await
+    }
+}
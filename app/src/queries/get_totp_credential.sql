@@ -0,0 +1,2 @@
+SELECT * FROM totp_credentials
+WHERE user_id = $1;
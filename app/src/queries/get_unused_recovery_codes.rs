@@ -0,0 +1,23 @@
+use sqlx::query_file_as;
+use uuid::Uuid;
+
+use crate::queries::database::Database;
+use crate::queries::records::recovery_code_record::RecoveryCodeRecord;
+
+impl Database {
+    #[tracing::instrument(
+    name = "Querying Postgres for unused recovery codes of user",
+    skip(self, user_id),
+    fields(user_id = % user_id)
+    )]
+    pub async fn get_unused_recovery_codes(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<RecoveryCodeRecord>, sqlx::Error> {
+        query_file_as!(
+            RecoveryCodeRecord,
+            "src/queries/get_unused_recovery_codes.sql",
+            user_id
+        ).fetch_all(self.db()).await
+    }
+}
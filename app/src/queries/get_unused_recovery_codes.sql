@@ -0,0 +1,3 @@
+SELECT * FROM recovery_codes
+WHERE user_id = $1
+  AND used_at IS NULL;
@@ -12,8 +12,9 @@ impl Database {
     ) -> sqlx::Result<Option<UserCredentials>> {
         let row = query!(
             r#"
-               SELECT user_id, password_hash FROM users
+               SELECT user_id, password_hash AS "password_hash!" FROM users
                WHERE username = $1
+                 AND password_hash IS NOT NULL
             "#,
             username
         )
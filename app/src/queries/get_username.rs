@@ -0,0 +1,17 @@
+use sqlx::query_file;
+
+use domain::user::user_id::UserId;
+
+use crate::queries::database::Database;
+
+impl Database {
+    #[tracing::instrument(name = "Fetching username for user id", skip(self))]
+    pub async fn get_username(&self, user_id: UserId) -> sqlx::Result<Option<String>> {
+        let row = query_file!(
+            "src/queries/get_username.sql",
+            user_id.0
+        ).fetch_optional(self.db()).await?;
+
+        Ok(row.map(|row| row.username))
+    }
+}
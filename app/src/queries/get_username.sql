@@ -0,0 +1,2 @@
+SELECT username FROM users
+WHERE user_id = $1;
@@ -0,0 +1,62 @@
+use sqlx::query_file;
+use uuid::Uuid;
+use crate::queries::database::Database;
+
+impl Database {
+
+    /// Returns whether a session exists for the given id, which has not yet ended.
+    pub async fn is_session_active(&self, session_id: &Uuid) -> sqlx::Result<bool> {
+        let result = query_file!(
+            "src/queries/is_session_active.sql",
+            session_id,
+        ).fetch_one(self.db()).await?;
+
+        Ok(result.active.unwrap_or(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use test_utility::random::_common::{random_salt, random_secret};
+    use test_utility::random::user::random_new_user;
+    use test_utility::random::user_session::random_newly_created_user_session;
+
+    use crate::queries::database::Database;
+
+    #[sqlx::test]
+    async fn test_is_session_active(db: PgPool) {
+        let db = Database(db);
+        let mut transaction = db.new_transaction().await.expect("Failed to create transaction");
+
+        let user = random_new_user(random_secret(), &random_salt());
+        transaction.save_new_user(&user)
+            .await
+            .expect("Failed to create user");
+
+        let session = random_newly_created_user_session(user.id);
+        transaction.save_newly_created_user_session(&session)
+            .await
+            .expect("Failed to save user session");
+        transaction.commit().await.expect("Failed to commit transaction");
+
+        assert!(db.is_session_active(session.id()).await.expect("Failed to query session"));
+        assert!(!db.is_session_active(&Uuid::new_v4()).await.expect("Failed to query session"));
+
+        let ended_session = db.get_active_session_by_id(session.id())
+            .await
+            .expect("Failed to get active session by id")
+            .expect("Failed to find active session by id")
+            .end_by_user_logout();
+
+        let mut transaction = db.new_transaction().await.expect("Failed to create transaction");
+        transaction.save_just_ended_session(&ended_session)
+            .await
+            .expect("Failed to save ended session");
+        transaction.commit().await.expect("Failed to commit transaction");
+
+        assert!(!db.is_session_active(session.id()).await.expect("Failed to query session"));
+    }
+}
@@ -0,0 +1 @@
+SELECT EXISTS(SELECT 1 FROM user_sessions WHERE id = $1 AND ended_at IS NULL) AS "active";
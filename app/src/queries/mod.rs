@@ -1,8 +1,9 @@
 pub mod database;
 pub mod get_active_session_by_id;
+pub mod get_active_sessions_by_user_id;
 pub mod get_latest_token_for_session;
 pub mod get_refresh_token_by_id;
-mod records;
+pub mod records;
 pub mod transaction;
 pub mod is_admin;
 pub mod get_user_credentials;
@@ -12,3 +13,16 @@ pub mod get_teams;
 pub mod get_members_by_team_id;
 pub mod exist_user_of;
 mod get_system_role_of_user;
+pub mod is_session_active;
+pub mod get_refresh_tokens_by_session_id;
+pub mod get_session_by_id;
+pub mod get_totp_credential;
+pub mod get_username;
+pub mod get_unused_recovery_codes;
+pub mod get_password_hash;
+pub mod get_password_reset_token;
+pub mod get_email_by_username;
+pub mod get_failed_logins;
+pub mod get_personal_access_token;
+pub mod get_active_personal_access_tokens_by_user_id;
+pub mod get_service_account_by_client_id;
@@ -0,0 +1,35 @@
+use chrono::NaiveDateTime;
+
+use domain::user::login_lockout::FailedLogins;
+
+#[derive(Debug)]
+pub struct FailedLoginsRecord {
+    pub username: String,
+    pub failed_attempts: i32,
+    pub window_started_at: NaiveDateTime,
+    pub lockouts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+impl FailedLoginsRecord {
+    pub fn new(username: &str, failed_logins: &FailedLogins) -> Self {
+        FailedLoginsRecord {
+            username: username.to_string(),
+            failed_attempts: failed_logins.failed_attempts.try_into().unwrap_or(i32::MAX),
+            window_started_at: failed_logins.window_started_at.naive_utc(),
+            lockouts: failed_logins.lockouts.try_into().unwrap_or(i32::MAX),
+            locked_until: failed_logins.locked_until.map(|locked_until| locked_until.naive_utc()),
+        }
+    }
+}
+
+impl From<FailedLoginsRecord> for FailedLogins {
+    fn from(record: FailedLoginsRecord) -> Self {
+        FailedLogins {
+            failed_attempts: record.failed_attempts.try_into().unwrap_or_default(),
+            window_started_at: record.window_started_at.and_utc(),
+            lockouts: record.lockouts.try_into().unwrap_or_default(),
+            locked_until: record.locked_until.map(|locked_until| locked_until.and_utc()),
+        }
+    }
+}
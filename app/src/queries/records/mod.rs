@@ -2,4 +2,10 @@ pub mod refresh_token_record;
 pub mod user_record;
 pub mod user_session_record;
 pub mod role_record;
-pub mod user_role_record;
\ No newline at end of file
+pub mod user_role_record;
+pub mod totp_credential_record;
+pub mod recovery_code_record;
+
+pub mod password_reset_token_record;pub mod failed_logins_record;
+pub mod personal_access_token_record;
+pub mod service_account_record;
@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use domain::shared::expiration::Expiration;
+use domain::user::password_reset_token::PasswordResetToken;
+
+#[derive(Debug)]
+pub struct PasswordResetTokenRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+}
+
+impl From<&PasswordResetToken> for PasswordResetTokenRecord {
+    fn from(reset_token: &PasswordResetToken) -> Self {
+        PasswordResetTokenRecord {
+            id: reset_token.id,
+            user_id: reset_token.user_id.0,
+            token_hash: reset_token.hash_string().expose_secret().clone(),
+            created_at: reset_token.created_at.naive_utc(),
+            expires_at: reset_token.expiration.0.naive_utc(),
+            used_at: reset_token.used_at.map(|used_at| used_at.naive_utc()),
+        }
+    }
+}
+
+impl From<PasswordResetTokenRecord> for PasswordResetToken {
+    fn from(record: PasswordResetTokenRecord) -> Self {
+        PasswordResetToken::from_hash(
+            record.id,
+            record.user_id.into(),
+            Secret::new(record.token_hash),
+            record.created_at.and_utc(),
+            Expiration(record.expires_at.and_utc()),
+            record.used_at.map(|used_at| used_at.and_utc()),
+        )
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::anyhow;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use domain::shared::expiration::Expiration;
+use domain::user::personal_access_token::{PersonalAccessToken, Scope};
+
+#[derive(Debug)]
+pub struct PersonalAccessTokenRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub token_hash: String,
+    pub created_at: NaiveDateTime,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl From<&PersonalAccessToken> for PersonalAccessTokenRecord {
+    fn from(token: &PersonalAccessToken) -> Self {
+        PersonalAccessTokenRecord {
+            id: token.id,
+            user_id: token.user_id.0,
+            name: token.name.clone(),
+            scopes: token.scopes.iter().map(|scope| scope.name().to_string()).collect(),
+            token_hash: token.hash_string().to_string(),
+            created_at: token.created_at.naive_utc(),
+            expires_at: token.expiration.map(|expiration| expiration.0.naive_utc()),
+            revoked_at: token.revoked_at.map(|revoked_at| revoked_at.naive_utc()),
+        }
+    }
+}
+
+impl TryInto<PersonalAccessToken> for PersonalAccessTokenRecord {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> Result<PersonalAccessToken, Self::Error> {
+        let scopes = self.scopes.iter()
+            .map(|name| Scope::from_name(name).ok_or_else(|| anyhow!("Unknown scope: {}", name)))
+            .collect::<Result<Vec<Scope>, _>>()?;
+
+        let mut token = PersonalAccessToken::from_hash(
+            self.id,
+            self.user_id.into(),
+            self.name,
+            scopes,
+            self.token_hash,
+            self.created_at.and_utc(),
+            self.expires_at.map(|expires_at| Expiration(expires_at.and_utc())),
+        );
+        token.revoked_at = self.revoked_at.map(|revoked_at| revoked_at.and_utc());
+
+        Ok(token)
+    }
+}
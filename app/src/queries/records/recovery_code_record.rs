@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use domain::user::recovery_code::RecoveryCode;
+
+#[derive(Debug)]
+pub struct RecoveryCodeRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub created_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+}
+
+impl From<&RecoveryCode> for RecoveryCodeRecord {
+    fn from(recovery_code: &RecoveryCode) -> Self {
+        RecoveryCodeRecord {
+            id: recovery_code.id,
+            user_id: recovery_code.user_id.0,
+            code_hash: recovery_code.hash_string().expose_secret().clone(),
+            created_at: recovery_code.created_at.naive_utc(),
+            used_at: recovery_code.used_at.map(|used_at| used_at.naive_utc()),
+        }
+    }
+}
+
+impl TryInto<RecoveryCode> for RecoveryCodeRecord {
+    type Error = password_hash::Error;
+
+    fn try_into(self) -> Result<RecoveryCode, Self::Error> {
+        RecoveryCode::from_hash(
+            self.id,
+            self.user_id.into(),
+            Secret::new(self.code_hash),
+            self.created_at.and_utc(),
+            self.used_at.map(|used_at| used_at.and_utc()),
+        )
+    }
+}
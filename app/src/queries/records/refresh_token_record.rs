@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use security::token::token::Token;
-use domain::sessions::tokens::RefreshToken;
+use domain::sessions::tokens::{AccessToken, RefreshToken};
 use domain::sessions::user_session_token::UserSessionToken;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -15,7 +15,56 @@ pub struct RefreshTokenRecord {
     pub issued_at: NaiveDateTime,
     pub not_before: NaiveDateTime,
     pub expiration: NaiveDateTime,
-    pub used_at: Option<NaiveDateTime>
+    pub used_at: Option<NaiveDateTime>,
+    /// issuer and audience are the claims the token was issued with, shared by its access token.
+    pub issuer: String,
+    pub audience: String,
+    /// parent_reusable_until is the end of the reuse grace period of the parent token,
+    /// during which the parent can be used again to receive this token and its access token.
+    pub parent_reusable_until: Option<NaiveDateTime>,
+    /// The access_token columns describe the access token issued together with this refresh token.
+    pub access_token_id: Option<Uuid>,
+    pub access_token_issued_at: Option<NaiveDateTime>,
+    pub access_token_expiration: Option<NaiveDateTime>,
+}
+
+impl RefreshTokenRecord {
+
+    /// Returns the access token that was issued together with this refresh token, if it was saved.
+    pub fn access_token(&self) -> Option<UserSessionToken<AccessToken>> {
+        let (id, issued_at, expiration) = match (
+            self.access_token_id,
+            self.access_token_issued_at,
+            self.access_token_expiration
+        ) {
+            (Some(id), Some(issued_at), Some(expiration)) => (id, issued_at, expiration),
+            _ => return None
+        };
+
+        let issued_at = DateTime::<Utc>::from_naive_utc_and_offset(issued_at, Utc);
+        Some(UserSessionToken {
+            id,
+            subject: AccessToken::subject().to_string(),
+            audience: self.audience.clone(),
+            issuer: self.issuer.clone(),
+            expiration: DateTime::<Utc>::from_naive_utc_and_offset(expiration, Utc).into(),
+            not_before: issued_at.into(),
+            issued_at,
+            custom_claims: AccessToken {
+                user_id: self.user_id,
+                session_id: self.session_id,
+                refresh_token_id: self.id,
+            },
+        })
+    }
+
+    /// Includes the access token that was issued together with this refresh token.
+    pub fn with_access_token(mut self, access_token: &UserSessionToken<AccessToken>) -> Self {
+        self.access_token_id = Some(*access_token.get_id());
+        self.access_token_issued_at = Some(access_token.get_issued_at().naive_utc());
+        self.access_token_expiration = Some(access_token.get_expiration().naive_utc());
+        self
+    }
 }
 
 impl Into<UserSessionToken<RefreshToken>> for RefreshTokenRecord {
@@ -23,8 +72,8 @@ impl Into<UserSessionToken<RefreshToken>> for RefreshTokenRecord {
         UserSessionToken {
             id: self.id,
             subject: RefreshToken::subject().to_string(),
-            audience: self.user_id.to_string(),
-            issuer: RefreshToken::issuer().to_string(),
+            audience: self.audience,
+            issuer: self.issuer,
             expiration: DateTime::<Utc>::from_naive_utc_and_offset(self.expiration, Utc).into(),
             not_before: DateTime::<Utc>::from_naive_utc_and_offset(self.not_before, Utc).into(),
             issued_at: DateTime::<Utc>::from_naive_utc_and_offset(self.issued_at, Utc),
@@ -47,6 +96,12 @@ impl From<&UserSessionToken<RefreshToken>> for RefreshTokenRecord {
             not_before: token.get_not_before().naive_utc(),
             expiration: token.get_expiration().naive_utc(),
             used_at: None,
+            issuer: token.get_issuer().to_string(),
+            audience: token.get_audience().to_string(),
+            parent_reusable_until: None,
+            access_token_id: None,
+            access_token_issued_at: None,
+            access_token_expiration: None,
         }
     }
 }
@@ -78,6 +133,12 @@ mod tests {
             not_before: token.not_before.0.0.naive_utc(),
             expiration: token.expiration.0.naive_utc(),
             used_at: None,
+            issuer: token.get_issuer().to_string(),
+            audience: token.get_audience().to_string(),
+            parent_reusable_until: None,
+            access_token_id: None,
+            access_token_issued_at: None,
+            access_token_expiration: None,
         };
 
         let got = RefreshTokenRecord::from(&token);
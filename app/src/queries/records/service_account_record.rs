@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use domain::user::service_account::ServiceAccount;
+
+#[derive(Debug)]
+pub struct ServiceAccountRecord {
+    pub user_id: Uuid,
+    pub client_id: String,
+    pub client_secret_hash: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<&ServiceAccount> for ServiceAccountRecord {
+    fn from(service_account: &ServiceAccount) -> Self {
+        ServiceAccountRecord {
+            user_id: service_account.user_id.0,
+            client_id: service_account.client_id.clone(),
+            client_secret_hash: service_account.secret_hash_string().to_string(),
+            created_at: service_account.created_at.naive_utc(),
+        }
+    }
+}
+
+impl From<ServiceAccountRecord> for ServiceAccount {
+    fn from(record: ServiceAccountRecord) -> Self {
+        ServiceAccount::from_hash(
+            record.user_id.into(),
+            record.client_id,
+            record.client_secret_hash,
+            record.created_at.and_utc(),
+        )
+    }
+}
@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use domain::user::totp_credential::TotpCredential;
+use security::encryption::decryptor::Decryptor;
+use security::encryption::encryptor::Encryptor;
+use security::otp::totp::TotpSecret;
+
+#[derive(Debug)]
+pub struct TotpCredentialRecord {
+    pub user_id: Uuid,
+    pub encrypted_secret: String,
+    pub created_at: NaiveDateTime,
+    pub confirmed_at: Option<NaiveDateTime>,
+    pub last_used_step: Option<i64>,
+}
+
+impl TotpCredentialRecord {
+
+    /// Creates the record of the credential, of which the secret is encrypted with the given encryptor.
+    pub fn encrypt<'a, E>(credential: &'a TotpCredential, encryptor: &E) -> Result<Self, E::EncryptionError>
+    where
+        E: Encryptor<'a, TotpSecret, Secret<String>>
+    {
+        Ok(Self {
+            user_id: credential.user_id.0,
+            encrypted_secret: encryptor.encrypt(&credential.secret)?.expose_secret().clone(),
+            created_at: credential.created_at.naive_utc(),
+            confirmed_at: credential.confirmed_at.map(|confirmed_at| confirmed_at.naive_utc()),
+            last_used_step: credential.last_used_step,
+        })
+    }
+
+    /// Returns the credential, of which the secret is decrypted with the given decryptor.
+    pub fn decrypt<D: Decryptor<TotpSecret>>(self, decryptor: &D) -> Result<TotpCredential, D::DecryptionError> {
+        Ok(TotpCredential {
+            user_id: self.user_id.into(),
+            secret: decryptor.decrypt(&Secret::new(self.encrypted_secret))?,
+            created_at: self.created_at.and_utc(),
+            confirmed_at: self.confirmed_at.map(|confirmed_at| confirmed_at.and_utc()),
+            last_used_step: self.last_used_step,
+        })
+    }
+}
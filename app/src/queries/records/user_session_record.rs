@@ -2,15 +2,17 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use domain::sessions::device::Device;
 use domain::sessions::state::active::Active;
 use domain::sessions::state::just_ended::JustEnded;
 use domain::sessions::state::newly_created::NewlyCreated;
 use domain::sessions::state::refreshed::Refreshed;
 use domain::sessions::state::state::SessionEndReason;
-use domain::sessions::user_session_token::UserSessionToken;
-use domain::sessions::tokens::RefreshToken;
+use domain::sessions::user_agent::UserAgent;
 use domain::sessions::user_session::UserSession;
 
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct UserSessionRecord {
     pub id: Uuid,
@@ -19,23 +21,45 @@ pub struct UserSessionRecord {
     pub ended_at: Option<NaiveDateTime>,
     pub ending_reason: Option<String>,
     pub ending_token_id: Option<Uuid>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
 }
 
 impl UserSessionRecord {
-    pub fn to_active_session(self, latest_token: UserSessionToken<RefreshToken>) -> UserSession<Active> {
+    pub fn to_active_session(self, latest_token: RefreshTokenRecord) -> UserSession<Active> {
         UserSession::<Active>::new(
             self.id,
             self.user_id.into(),
             self.created_at.and_utc(),
+            self.device(),
             Active {
-                latest_refresh_token: latest_token,
+                latest_access_token: latest_token.access_token(),
+                parent_reusable_until: latest_token.parent_reusable_until.map(|until| until.and_utc()),
+                latest_refresh_token: latest_token.into(),
             }
         )
     }
+
+    /// Returns the device of the session, ignoring values which can no longer be parsed.
+    pub fn device(&self) -> Device {
+        Device::new(
+            self.user_agent.as_deref().and_then(|user_agent| UserAgent::new(user_agent).ok()),
+            self.ip_address.as_deref().and_then(|ip_address| ip_address.parse().ok()),
+        )
+    }
+}
+
+/// Returns the device as the values of the `user_agent` and `ip_address` columns.
+pub fn device_columns(device: &Device) -> (Option<String>, Option<String>) {
+    (
+        device.user_agent.as_ref().map(|user_agent| user_agent.user_agent().to_string()),
+        device.ip_address.map(|ip_address| ip_address.to_string()),
+    )
 }
 
 impl From<&UserSession<NewlyCreated>> for UserSessionRecord {
     fn from(session: &UserSession<NewlyCreated>) -> Self {
+        let (user_agent, ip_address) = device_columns(session.device());
         UserSessionRecord {
             id: session.id().clone(),
             user_id: session.user_id().0,
@@ -43,12 +67,15 @@ impl From<&UserSession<NewlyCreated>> for UserSessionRecord {
             ended_at: None,
             ending_reason: None,
             ending_token_id: None,
+            user_agent,
+            ip_address,
         }
     }
 }
 
 impl From<&UserSession<Refreshed>> for UserSessionRecord {
     fn from(session: &UserSession<Refreshed>) -> Self {
+        let (user_agent, ip_address) = device_columns(session.device());
         UserSessionRecord {
             id: session.id().clone(),
             user_id: session.user_id().0,
@@ -56,6 +83,8 @@ impl From<&UserSession<Refreshed>> for UserSessionRecord {
             ended_at: None,
             ending_reason: None,
             ending_token_id: None,
+            user_agent,
+            ip_address,
         }
     }
 }
@@ -69,6 +98,7 @@ impl From<&UserSession<JustEnded>> for UserSessionRecord {
             }
         };
 
+        let (user_agent, ip_address) = device_columns(session.device());
         UserSessionRecord {
             id: session.id().clone(),
             user_id: session.user_id().0,
@@ -76,6 +106,8 @@ impl From<&UserSession<JustEnded>> for UserSessionRecord {
             ended_at: Some(session.state().session_end_time().naive_utc()),
             ending_reason: Some(session.state().reason_for_ending().to_string().to_string()),
             ending_token_id,
+            user_agent,
+            ip_address,
         }
     }
 }
@@ -99,10 +131,13 @@ mod tests {
             ended_at: None,
             ending_reason: None,
             ending_token_id: None,
+            user_agent: Some(session.device().user_agent.as_ref().unwrap().user_agent().to_string()),
+            ip_address: Some(session.device().ip_address.unwrap().to_string()),
         };
 
         let got = UserSessionRecord::from(&session);
-        assert_eq!(expected, got)
+        assert_eq!(expected, got);
+        assert_eq!(got.device(), *session.device());
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use sqlx::{query_file, Executor};
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+    #[tracing::instrument(name = "Deleting failed logins of username from Postgres", skip(self))]
+    pub async fn delete_failed_logins(&mut self, username: &str) -> sqlx::Result<()> {
+        self.0.execute(query_file!(
+            "src/queries/transaction/delete_failed_logins.sql",
+            username,
+        )).await?;
+
+        Ok(())
+    }
+}
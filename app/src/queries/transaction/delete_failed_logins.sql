@@ -0,0 +1,2 @@
+DELETE FROM failed_logins
+WHERE username = $1;
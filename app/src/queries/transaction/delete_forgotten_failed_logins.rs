@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, Executor};
+
+use domain::user::login_lockout::LockoutPolicy;
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Deletes the failed logins which the policy has forgotten at the given time,
+    /// see [LockoutPolicy::is_forgotten].
+    #[tracing::instrument(name = "Deleting forgotten failed logins from Postgres", skip(self, policy))]
+    pub async fn delete_forgotten_failed_logins(
+        &mut self,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        self.0.execute(query_file!(
+            "src/queries/transaction/delete_forgotten_failed_logins.sql",
+            (now - policy.window).naive_utc(),
+            (now - policy.max_lockout).naive_utc(),
+        )).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+DELETE FROM failed_logins
+WHERE window_started_at <= $1
+  AND (locked_until IS NULL OR locked_until <= $2);
@@ -0,0 +1,52 @@
+use sqlx::query_file_as;
+use tracing::warn;
+use uuid::Uuid;
+
+use domain::sessions::state::active::Active;
+use domain::sessions::user_session::UserSession;
+
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
+use crate::queries::records::user_session_record::UserSessionRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Gets the active session and locks it until the transaction ends, so concurrent
+    /// refreshes of the same session see the refresh token issued by each other.
+    #[tracing::instrument(
+    name = "Querying Postgres for active session by id to update",
+    skip(self, session_id),
+    fields(session_id = % session_id)
+    )]
+    pub async fn get_active_session_by_id_for_update(
+        &mut self,
+        session_id: &Uuid
+    ) -> Result<Option<UserSession<Active>>, sqlx::Error> {
+        let Some(session) = query_file_as!(
+            UserSessionRecord,
+            "src/queries/transaction/get_active_session_by_id_for_update.sql",
+            session_id
+        ).fetch_optional(&mut *self.0).await? else {
+            return Ok(None)
+        };
+
+        if let Some(time) = session.ended_at {
+            warn!("Asked for an active session but found session (id: {}) that has ended already (at: {})", session_id, time);
+            return Ok(None)
+        }
+
+        let latest_token = query_file_as!(
+            RefreshTokenRecord,
+            "src/queries/get_latest_token_for_session.sql",
+            session_id
+        ).fetch_optional(&mut *self.0).await?;
+
+        match latest_token {
+            None => {
+                warn!("Expected to find a refresh token for the active session of id: {}, but did not find one", session_id);
+                Ok(None)
+            }
+            Some(token) => Ok(Some(session.to_active_session(token)))
+        }
+    }
+}
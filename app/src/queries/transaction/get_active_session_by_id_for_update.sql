@@ -0,0 +1,3 @@
+SELECT * FROM user_sessions
+WHERE user_sessions.id = $1
+FOR UPDATE;
@@ -0,0 +1,54 @@
+use sqlx::{query_file, query_file_as};
+use tracing::warn;
+
+use domain::sessions::state::active::Active;
+use domain::sessions::user_session::UserSession;
+use domain::user::user_id::UserId;
+
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
+use crate::queries::records::user_session_record::UserSessionRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Gets the active sessions of the user, after locking the user until the transaction ends,
+    /// so that concurrent sign ins and password changes of the user see the sessions of each other.
+    #[tracing::instrument(
+    name = "Querying Postgres for active sessions of user to update",
+    skip(self, user_id),
+    fields(user_id = % user_id)
+    )]
+    pub async fn get_active_sessions_by_user_id_for_update(
+        &mut self,
+        user_id: UserId
+    ) -> Result<Vec<UserSession<Active>>, sqlx::Error> {
+        query_file!(
+            "src/queries/transaction/lock_user.sql",
+            user_id.0
+        ).fetch_optional(&mut *self.0).await?;
+
+        let records = query_file_as!(
+            UserSessionRecord,
+            "src/queries/get_active_sessions_by_user_id.sql",
+            user_id.0
+        ).fetch_all(&mut *self.0).await?;
+
+        let mut sessions = Vec::with_capacity(records.len());
+        for record in records {
+            let latest_token = query_file_as!(
+                RefreshTokenRecord,
+                "src/queries/get_latest_token_for_session.sql",
+                record.id
+            ).fetch_optional(&mut *self.0).await?;
+
+            match latest_token {
+                None => {
+                    warn!("Expected to find a refresh token for the active session of id: {}, but did not find one", record.id);
+                }
+                Some(token) => sessions.push(record.to_active_session(token))
+            }
+        }
+
+        Ok(sessions)
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, query_file_as, Executor};
+
+use crate::queries::records::failed_logins_record::FailedLoginsRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Gets the failed logins of the username and locks them until the transaction ends,
+    /// so concurrent failed attempts of other instances are all counted. Usernames without failed
+    /// logins get a row without failed attempts first, as there would be no row to lock otherwise.
+    #[tracing::instrument(name = "Querying Postgres for failed logins of username to update", skip(self))]
+    pub async fn get_failed_logins_for_update(
+        &mut self,
+        username: &str,
+        now: DateTime<Utc>
+    ) -> sqlx::Result<FailedLoginsRecord> {
+        self.0.execute(query_file!(
+            "src/queries/transaction/insert_failed_logins_if_missing.sql",
+            username,
+            now.naive_utc(),
+        )).await?;
+
+        query_file_as!(
+            FailedLoginsRecord,
+            "src/queries/transaction/get_failed_logins_for_update.sql",
+            username
+        ).fetch_one(&mut *self.0).await
+    }
+}
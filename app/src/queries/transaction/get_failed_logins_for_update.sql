@@ -0,0 +1,3 @@
+SELECT * FROM failed_logins
+WHERE username = $1
+FOR UPDATE;
@@ -0,0 +1,3 @@
+INSERT INTO failed_logins (username, failed_attempts, window_started_at, lockouts, locked_until)
+VALUES ($1, 0, $2, 0, NULL)
+ON CONFLICT (username) DO NOTHING;
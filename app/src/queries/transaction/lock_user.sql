@@ -0,0 +1,3 @@
+SELECT users.user_id FROM users
+WHERE users.user_id = $1
+FOR UPDATE;
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use sqlx::query_file;
+use uuid::Uuid;
+
+use domain::user::user_id::UserId;
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Marks every unused password reset token of the user as used, so none of the other reset
+    /// emails can be used anymore once the password has been reset.
+    /// Returns the ids of the tokens which were still unused.
+    #[tracing::instrument(
+    name = "Marking password reset tokens of user as used in Postgres",
+    skip(self, user_id, used_at),
+    fields(user_id = % user_id.0)
+    )]
+    pub async fn mark_password_reset_tokens_as_used(
+        &mut self,
+        user_id: &UserId,
+        used_at: &DateTime<Utc>,
+    ) -> sqlx::Result<Vec<Uuid>> {
+        let rows = query_file!(
+            "src/queries/transaction/mark_password_reset_tokens_as_used.sql",
+            user_id.0,
+            used_at.naive_utc(),
+        ).fetch_all(&mut *self.0).await?;
+
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+}
@@ -0,0 +1,5 @@
+UPDATE password_reset_tokens
+SET used_at = $2
+WHERE password_reset_tokens.user_id = $1
+  AND password_reset_tokens.used_at IS NULL
+RETURNING id;
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, Executor};
+use uuid::Uuid;
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Marks the recovery code as used. Returns false when the recovery code
+    /// was used by another request in the meantime.
+    #[tracing::instrument(
+    name = "Marking recovery code as used in Postgres",
+    skip(self, recovery_code_id, used_at),
+    fields(recovery_code_id = % recovery_code_id)
+    )]
+    pub async fn mark_recovery_code_as_used(
+        &mut self,
+        recovery_code_id: &Uuid,
+        used_at: &DateTime<Utc>,
+    ) -> sqlx::Result<bool> {
+        let result = self.0.execute(query_file!(
+            "src/queries/transaction/mark_recovery_code_as_used.sql",
+            recovery_code_id,
+            used_at.naive_utc(),
+        )).await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
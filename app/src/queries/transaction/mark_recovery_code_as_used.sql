@@ -0,0 +1,4 @@
+UPDATE recovery_codes
+SET used_at = $2
+WHERE recovery_codes.id = $1
+  AND recovery_codes.used_at IS NULL;
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, Executor};
+use uuid::Uuid;
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+    #[tracing::instrument(
+    name = "Marking refresh token as used in Postgres",
+    skip(self, refresh_token_id, used_at),
+    fields(refresh_token_id = % refresh_token_id)
+    )]
+    pub async fn mark_refresh_token_as_used(
+        &mut self,
+        refresh_token_id: &Uuid,
+        used_at: &DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        self.0.execute(query_file!(
+            "src/queries/transaction/mark_refresh_token_as_used.sql",
+            refresh_token_id,
+            used_at.naive_utc(),
+        )).await?;
+
+        Ok(())
+    }
+}
@@ -7,3 +7,21 @@ pub mod update_user_password;
 pub mod save_new_user;
 pub mod save_team;
 mod save_team_member;
+pub mod save_security_event;
+pub mod mark_refresh_token_as_used;
+pub mod update_user_session_device;
+pub mod save_totp_credential;
+pub mod update_totp_credential_usage;
+pub mod replace_recovery_codes;
+pub mod mark_recovery_code_as_used;
+pub mod save_password_reset_token;
+pub mod mark_password_reset_tokens_as_used;
+pub mod get_failed_logins_for_update;
+pub mod save_failed_logins;
+pub mod delete_failed_logins;
+pub mod delete_forgotten_failed_logins;
+pub mod save_personal_access_token;
+pub mod revoke_personal_access_token;
+pub mod save_service_account;
+pub mod get_active_session_by_id_for_update;
+pub mod get_active_sessions_by_user_id_for_update;
@@ -0,0 +1,39 @@
+use sqlx::{query_file, Executor};
+use uuid::Uuid;
+
+use domain::user::recovery_code::RecoveryCode;
+
+use crate::queries::records::recovery_code_record::RecoveryCodeRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Replaces the recovery codes of the user with the given recovery codes,
+    /// after which previously issued recovery codes can no longer be used.
+    #[tracing::instrument(
+    name = "Replacing recovery codes of user in Postgres",
+    skip(self, user_id, recovery_codes),
+    fields(user_id = % user_id)
+    )]
+    pub async fn replace_recovery_codes(
+        &mut self,
+        user_id: &Uuid,
+        recovery_codes: &[RecoveryCode],
+    ) -> Result<(), sqlx::Error> {
+        self.0.execute(query_file!("src/queries/transaction/delete_recovery_codes.sql", user_id)).await?;
+
+        let records = recovery_codes.iter()
+            .map(RecoveryCodeRecord::from)
+            .collect::<Vec<_>>();
+
+        self.0.execute(query_file!("src/queries/transaction/save_recovery_codes.sql",
+            &records.iter().map(|record| record.id).collect::<Vec<_>>(),
+            &records.iter().map(|record| record.user_id).collect::<Vec<_>>(),
+            &records.iter().map(|record| record.code_hash.clone()).collect::<Vec<_>>(),
+            &records.iter().map(|record| record.created_at).collect::<Vec<_>>(),
+            &records.iter().map(|record| record.used_at).collect::<Vec<_>>() as &[Option<_>],
+        )).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, Executor};
+use uuid::Uuid;
+
+use domain::user::user_id::UserId;
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Revokes the personal access token of the user, returns false when the user
+    /// has no such token or it was already revoked.
+    #[tracing::instrument(
+    name = "Revoking personal access token in Postgres",
+    skip(self, user_id, revoked_at),
+    fields(user_id = % user_id.0)
+    )]
+    pub async fn revoke_personal_access_token(
+        &mut self,
+        token_id: &Uuid,
+        user_id: &UserId,
+        revoked_at: &DateTime<Utc>,
+    ) -> sqlx::Result<bool> {
+        let result = self.0.execute(query_file!(
+            "src/queries/transaction/revoke_personal_access_token.sql",
+            token_id,
+            user_id.0,
+            revoked_at.naive_utc(),
+        )).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
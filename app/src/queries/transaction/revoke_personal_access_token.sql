@@ -0,0 +1,5 @@
+UPDATE personal_access_tokens
+SET revoked_at = $3
+WHERE id = $1
+  AND user_id = $2
+  AND revoked_at IS NULL;
@@ -0,0 +1,24 @@
+use sqlx::{query_file, Executor};
+
+use crate::queries::records::failed_logins_record::FailedLoginsRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+    #[tracing::instrument(
+    name = "Saving failed logins to Postgres",
+    skip(self, record),
+    fields(username = % record.username)
+    )]
+    pub async fn save_failed_logins(&mut self, record: &FailedLoginsRecord) -> sqlx::Result<()> {
+        self.0.execute(query_file!(
+            "src/queries/transaction/save_failed_logins.sql",
+            record.username,
+            record.failed_attempts,
+            record.window_started_at,
+            record.lockouts,
+            record.locked_until,
+        )).await?;
+
+        Ok(())
+    }
+}
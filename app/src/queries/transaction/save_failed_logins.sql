@@ -0,0 +1,7 @@
+INSERT INTO failed_logins (username, failed_attempts, window_started_at, lockouts, locked_until)
+VALUES ($1, $2, $3, $4, $5)
+ON CONFLICT (username) DO UPDATE
+SET failed_attempts   = EXCLUDED.failed_attempts,
+    window_started_at = EXCLUDED.window_started_at,
+    lockouts          = EXCLUDED.lockouts,
+    locked_until      = EXCLUDED.locked_until;
@@ -21,7 +21,8 @@ impl Transaction {
             user.id.0,
             user.username,
             user.password.hash().expose_secret(),
-            role as Option<SystemRoleType>
+            role as Option<SystemRoleType>,
+            user.email,
         )).await?;
 
         Ok(())
@@ -55,7 +56,7 @@ mod tests {
         let user_record = query_as!(
             UserRecord,
             r#"
-                SELECT user_id, username, password_hash, system_role AS "system_role!: Option<SystemRoleType>" FROM users
+                SELECT user_id, username, password_hash AS "password_hash!", system_role AS "system_role!: Option<SystemRoleType>" FROM users
                 WHERE user_id = $1
             "#,
             user.id.0
@@ -1,2 +1,2 @@
-INSERT INTO users (user_id, username, password_hash, system_role)
-VALUES ($1, $2, $3, $4);
\ No newline at end of file
+INSERT INTO users (user_id, username, password_hash, system_role, email)
+VALUES ($1, $2, $3, $4, $5);
\ No newline at end of file
@@ -20,7 +20,9 @@ impl Transaction {
         self.0.execute(query_file!("src/queries/transaction/save_newly_created_user_session.sql",
             session_record.id,
             session_record.user_id,
-            session_record.created_at
+            session_record.created_at,
+            session_record.user_agent,
+            session_record.ip_address
         )).await?;
 
         self.save_refresh_token(session.state().refresh_token()).await?;
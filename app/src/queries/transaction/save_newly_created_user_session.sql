@@ -1,2 +1,2 @@
-INSERT INTO user_sessions (id, user_id, created_at)
-VALUES ($1, $2, $3);
\ No newline at end of file
+INSERT INTO user_sessions (id, user_id, created_at, user_agent, ip_address)
+VALUES ($1, $2, $3, $4, $5);
\ No newline at end of file
@@ -0,0 +1,29 @@
+use sqlx::{query_file, Executor};
+
+use domain::user::password_reset_token::PasswordResetToken;
+
+use crate::queries::records::password_reset_token_record::PasswordResetTokenRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+    #[tracing::instrument(
+    name = "Saving password reset token to Postgres",
+    skip(self, reset_token),
+    fields(user_id = % reset_token.user_id.0)
+    )]
+    pub async fn save_password_reset_token(&mut self, reset_token: &PasswordResetToken) -> sqlx::Result<()> {
+        let record = PasswordResetTokenRecord::from(reset_token);
+
+        self.0.execute(query_file!(
+            "src/queries/transaction/save_password_reset_token.sql",
+            record.id,
+            record.user_id,
+            record.token_hash,
+            record.created_at,
+            record.expires_at,
+            record.used_at,
+        )).await?;
+
+        Ok(())
+    }
+}
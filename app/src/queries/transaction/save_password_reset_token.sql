@@ -0,0 +1,2 @@
+INSERT INTO password_reset_tokens (id, user_id, token_hash, created_at, expires_at, used_at)
+VALUES ($1, $2, $3, $4, $5, $6);
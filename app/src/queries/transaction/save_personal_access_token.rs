@@ -0,0 +1,31 @@
+use sqlx::{query_file, Executor};
+
+use domain::user::personal_access_token::PersonalAccessToken;
+
+use crate::queries::records::personal_access_token_record::PersonalAccessTokenRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+    #[tracing::instrument(
+    name = "Saving personal access token to Postgres",
+    skip(self, token),
+    fields(user_id = % token.user_id.0, token_id = % token.id)
+    )]
+    pub async fn save_personal_access_token(&mut self, token: &PersonalAccessToken) -> sqlx::Result<()> {
+        let record = PersonalAccessTokenRecord::from(token);
+
+        self.0.execute(query_file!(
+            "src/queries/transaction/save_personal_access_token.sql",
+            record.id,
+            record.user_id,
+            record.name,
+            &record.scopes,
+            record.token_hash,
+            record.created_at,
+            record.expires_at,
+            record.revoked_at,
+        )).await?;
+
+        Ok(())
+    }
+}
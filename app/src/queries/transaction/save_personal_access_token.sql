@@ -0,0 +1,2 @@
+INSERT INTO personal_access_tokens (id, user_id, name, scopes, token_hash, created_at, expires_at, revoked_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8);
@@ -0,0 +1,2 @@
+INSERT INTO recovery_codes (id, user_id, code_hash, created_at, used_at)
+SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::varchar[], $4::timestamp[], $5::timestamp[]);
@@ -13,7 +13,13 @@ impl Transaction {
         &mut self,
         refresh_token: &UserSessionToken<RefreshToken>,
     ) -> sqlx::Result<()> {
-        let refresh_token_record = RefreshTokenRecord::from(refresh_token);
+        self.save_refresh_token_record(&RefreshTokenRecord::from(refresh_token)).await
+    }
+
+    pub(in crate::queries) async fn save_refresh_token_record(
+        &mut self,
+        refresh_token_record: &RefreshTokenRecord,
+    ) -> sqlx::Result<()> {
         self.0.execute(sqlx::query_file!(
             "src/queries/transaction/save_refresh_token.sql",
             refresh_token_record.id,
@@ -23,6 +29,12 @@ impl Transaction {
             refresh_token_record.issued_at,
             refresh_token_record.not_before,
             refresh_token_record.expiration,
+            refresh_token_record.issuer,
+            refresh_token_record.audience,
+            refresh_token_record.parent_reusable_until,
+            refresh_token_record.access_token_id,
+            refresh_token_record.access_token_issued_at,
+            refresh_token_record.access_token_expiration,
         )).await?;
 
         Ok(())
@@ -1,2 +1,3 @@
-INSERT INTO refresh_tokens (id, session_id, user_id, parent_id, issued_at, not_before, expiration)
-VALUES ($1, $2, $3, $4, $5, $6, $7);
\ No newline at end of file
+INSERT INTO refresh_tokens (id, session_id, user_id, parent_id, issued_at, not_before, expiration, issuer, audience,
+                            parent_reusable_until, access_token_id, access_token_issued_at, access_token_expiration)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13);
\ No newline at end of file
@@ -1,6 +1,8 @@
 use domain::sessions::state::refreshed::Refreshed;
 use domain::sessions::user_session::UserSession;
+use security::token::token::Token;
 
+use crate::queries::records::refresh_token_record::RefreshTokenRecord;
 use crate::queries::transaction::_transaction::Transaction;
 
 impl Transaction {
@@ -12,19 +14,34 @@ impl Transaction {
         &mut self,
         session: &UserSession<Refreshed>,
     ) -> Result<(), sqlx::Error> {
-        self.save_refresh_token(session.state().new_refresh_token()).await?;
+        self.mark_refresh_token_as_used(
+            session.state().old_refresh_token().get_id(),
+            session.state().refreshed_at(),
+        ).await?;
+
+        let mut new_refresh_token = RefreshTokenRecord::from(session.state().new_refresh_token())
+            .with_access_token(session.state().new_access_token());
+        new_refresh_token.parent_reusable_until = session.state()
+            .old_refresh_token_reusable_until()
+            .map(|until| until.naive_utc());
+
+        self.save_refresh_token_record(&new_refresh_token).await?;
+        self.update_user_session_device(session.id(), session.device()).await?;
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::Duration;
     use sqlx::PgPool;
 
+    use domain::sessions::refresh_outcome::RefreshOutcome;
+    use domain::sessions::session_policy::SessionPolicy;
     use security::token::token::Token;
     use test_utility::random::_common::{random_salt, random_secret};
     use test_utility::random::user::random_new_user;
-    use test_utility::random::user_session::random_newly_created_user_session;
+    use test_utility::random::user_session::{random_device, random_newly_created_user_session};
 
     use crate::queries::database::Database;
     use crate::queries::records::refresh_token_record::RefreshTokenRecord;
@@ -64,9 +81,15 @@ mod tests {
         assert_eq!(*active_session.id(), *session.id());
 
         // refresh the session to get a refreshed session
-        let refreshed_session = active_session
-            .refresh(session.state().refresh_token().clone())
-            .expect("Failed to get refreshed session from refresh token");
+        let policy = SessionPolicy {
+            refresh_token_reuse_grace_period: Duration::seconds(10),
+            ..SessionPolicy::default()
+        };
+        let device = random_device();
+        let refreshed_session = match active_session.refresh(session.state().refresh_token().clone(), device.clone(), &policy) {
+            RefreshOutcome::Refreshed(refreshed_session) => refreshed_session,
+            _ => panic!("Failed to get refreshed session from refresh token")
+        };
 
         transaction.save_refreshed_session(&refreshed_session)
             .await
@@ -87,6 +110,41 @@ mod tests {
         let expected = RefreshTokenRecord::from(refreshed_session.state().new_refresh_token());
         let got = RefreshTokenRecord::from(&token);
 
-        assert_eq!(expected, got)
+        assert_eq!(expected, got);
+
+        // check if the old refresh token is marked as used
+        let tokens = database
+            .get_refresh_tokens_by_session_id(session.id())
+            .await
+            .expect("Failed to get refresh tokens of session");
+
+        let old_token = tokens.iter()
+            .find(|t| t.id == *session.state().refresh_token().get_id())
+            .expect("Failed to find old refresh token");
+
+        let used_at = old_token.used_at.expect("Old refresh token should be marked as used");
+        let refreshed_at = refreshed_session.state().refreshed_at().naive_utc();
+        assert!((used_at - refreshed_at).num_milliseconds().abs() < 1);
+        assert!(tokens.iter().any(|t| t.id == got.id && t.used_at.is_none()));
+
+        // check if the refreshed session can be reissued within the reuse grace period
+        let active_session = database.get_active_session_by_id(session.id())
+            .await
+            .expect("Failed to get active session by id")
+            .expect("Failed to find active session by id");
+
+        let latest_access_token = active_session.state().latest_access_token.as_ref()
+            .expect("Latest access token should be saved with the refreshed session");
+        assert_eq!(latest_access_token.get_id(), refreshed_session.state().new_access_token().get_id());
+        assert!(active_session.state().parent_reusable_until.is_some());
+        assert_eq!(*active_session.device(), device);
+
+        match active_session.refresh(session.state().refresh_token().clone(), random_device(), &policy) {
+            RefreshOutcome::Reissued(reissued) => {
+                assert_eq!(reissued.state().refresh_token().get_id(), refreshed_session.state().new_refresh_token().get_id());
+                assert_eq!(reissued.state().access_token().get_id(), refreshed_session.state().new_access_token().get_id());
+            }
+            _ => panic!("Expected refreshed session to be reissued")
+        }
     }
 }
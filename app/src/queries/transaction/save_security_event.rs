@@ -0,0 +1,36 @@
+use sqlx::{query_file, Executor};
+
+use domain::security_event::security_event::SecurityEvent;
+
+use crate::queries::transaction::_transaction::Transaction;
+use crate::telemetry::TelemetryRecord;
+
+impl Transaction {
+
+    #[tracing::instrument(
+        name = "Saving security event",
+        skip(self, event),
+        fields (
+            user_id = tracing::field::Empty,
+            kind = tracing::field::Empty,
+        )
+    )]
+    pub async fn save_security_event(&mut self, event: &SecurityEvent) -> sqlx::Result<()> {
+        event.user_id.record_in_telemetry("user_id");
+        event.kind.to_string().record_in_telemetry("kind");
+
+        let query = query_file!(
+            "src/queries/transaction/save_security_event.sql",
+            event.id,
+            event.user_id.0,
+            event.session_id,
+            event.token_id(),
+            event.kind.to_string(),
+            event.occurred_at.naive_utc(),
+        );
+
+        self.0.execute(query).await?;
+
+        Ok(())
+    }
+}
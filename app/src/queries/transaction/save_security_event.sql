@@ -0,0 +1,2 @@
+INSERT INTO security_events (id, user_id, session_id, token_id, kind, occurred_at)
+VALUES ($1, $2, $3, $4, $5, $6);
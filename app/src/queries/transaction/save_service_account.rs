@@ -0,0 +1,44 @@
+use sqlx::{query_file, Executor};
+
+use domain::role::role::SystemRole;
+use domain::user::service_account::ServiceAccount;
+
+use crate::queries::records::service_account_record::ServiceAccountRecord;
+use crate::queries::records::user_role_record::SystemRoleType;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Saves the service account together with its user, which has no password.
+    #[tracing::instrument(
+    name = "Saving service account to Postgres",
+    skip(self, service_account, name),
+    fields(user_id = % service_account.user_id.0, client_id = % service_account.client_id)
+    )]
+    pub async fn save_service_account(
+        &mut self,
+        service_account: &ServiceAccount,
+        name: &str,
+        system_role: Option<SystemRole>,
+    ) -> sqlx::Result<()> {
+        let record = ServiceAccountRecord::from(service_account);
+        let role = system_role.map(SystemRoleType::from);
+
+        self.0.execute(query_file!(
+            "src/queries/transaction/save_service_account_user.sql",
+            record.user_id,
+            name,
+            role as Option<SystemRoleType>,
+        )).await?;
+
+        self.0.execute(query_file!(
+            "src/queries/transaction/save_service_account.sql",
+            record.user_id,
+            record.client_id,
+            record.client_secret_hash,
+            record.created_at,
+        )).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+INSERT INTO service_accounts (user_id, client_id, client_secret_hash, created_at)
+VALUES ($1, $2, $3, $4);
@@ -0,0 +1,2 @@
+INSERT INTO users (user_id, username, password_hash, system_role)
+VALUES ($1, $2, NULL, $3);
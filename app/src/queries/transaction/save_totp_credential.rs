@@ -0,0 +1,29 @@
+use sqlx::{query_file, Executor};
+
+use crate::queries::records::totp_credential_record::TotpCredentialRecord;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Saves the newly enrolled TOTP credential, replacing the previous credential of the user
+    /// unless it has been confirmed. Returns whether the credential was saved.
+    #[tracing::instrument(
+    name = "Saving TOTP credential to Postgres",
+    skip(self, record),
+    fields(user_id = % record.user_id)
+    )]
+    pub async fn save_totp_credential(
+        &mut self,
+        record: &TotpCredentialRecord,
+    ) -> Result<bool, sqlx::Error> {
+        let result = self.0.execute(query_file!("src/queries/transaction/save_totp_credential.sql",
+            record.user_id,
+            record.encrypted_secret,
+            record.created_at,
+            record.confirmed_at,
+            record.last_used_step
+        )).await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
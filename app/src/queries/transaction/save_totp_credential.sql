@@ -0,0 +1,8 @@
+INSERT INTO totp_credentials (user_id, encrypted_secret, created_at, confirmed_at, last_used_step)
+VALUES ($1, $2, $3, $4, $5)
+ON CONFLICT (user_id) DO UPDATE
+SET encrypted_secret = EXCLUDED.encrypted_secret,
+    created_at = EXCLUDED.created_at,
+    confirmed_at = EXCLUDED.confirmed_at,
+    last_used_step = EXCLUDED.last_used_step
+WHERE totp_credentials.confirmed_at IS NULL;
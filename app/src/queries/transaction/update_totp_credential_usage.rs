@@ -0,0 +1,29 @@
+use sqlx::{query_file, Executor};
+
+use domain::user::totp_credential::TotpCredential;
+
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+
+    /// Saves the confirmation and the time step of the last accepted code of the credential.
+    /// Returns false when a code of the same or a later time step got accepted in the meantime,
+    /// in which case the code should be rejected as it has already been used.
+    #[tracing::instrument(
+    name = "Updating usage of TOTP credential in Postgres",
+    skip(self, credential),
+    fields(user_id = % credential.user_id)
+    )]
+    pub async fn update_totp_credential_usage(
+        &mut self,
+        credential: &TotpCredential,
+    ) -> Result<bool, sqlx::Error> {
+        let result = self.0.execute(query_file!("src/queries/transaction/update_totp_credential_usage.sql",
+            credential.user_id.0,
+            credential.confirmed_at.map(|confirmed_at| confirmed_at.naive_utc()),
+            credential.last_used_step
+        )).await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
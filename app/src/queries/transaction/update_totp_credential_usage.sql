@@ -0,0 +1,5 @@
+UPDATE totp_credentials
+SET confirmed_at = $2,
+    last_used_step = $3
+WHERE user_id = $1
+  AND (last_used_step IS NULL OR last_used_step < $3);
@@ -0,0 +1,29 @@
+use sqlx::{query_file, Executor};
+use uuid::Uuid;
+
+use domain::sessions::device::Device;
+
+use crate::queries::records::user_session_record::device_columns;
+use crate::queries::transaction::_transaction::Transaction;
+
+impl Transaction {
+    #[tracing::instrument(
+    name = "Updating device of user session in Postgres",
+    skip(self, session_id, device),
+    fields(session_id = % session_id)
+    )]
+    pub async fn update_user_session_device(
+        &mut self,
+        session_id: &Uuid,
+        device: &Device,
+    ) -> Result<(), sqlx::Error> {
+        let (user_agent, ip_address) = device_columns(device);
+        self.0.execute(query_file!("src/queries/transaction/update_user_session_device.sql",
+            session_id,
+            user_agent,
+            ip_address
+        )).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+UPDATE user_sessions
+SET user_agent = $2,
+    ip_address = $3
+WHERE user_sessions.id = $1;
\ No newline at end of file
@@ -1,39 +1,110 @@
 use std::sync::Arc;
 
 use axum::{middleware, Router};
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post, put};
 use tower_http::trace::TraceLayer;
 
 use crate::app_state::AppState;
 use crate::handlers::v1::auth::login::login::login;
+use crate::handlers::v1::auth::login::login_recovery_code::login_recovery_code;
+use crate::handlers::v1::auth::login::login_totp::login_totp;
 use crate::handlers::v1::auth::logout::logout::logout;
+use crate::handlers::v1::auth::logout::logout_everywhere::logout_everywhere;
+use crate::handlers::v1::auth::password_reset::forgot_password::forgot_password;
+use crate::handlers::v1::auth::password_reset::reset_password::reset_password;
 use crate::handlers::v1::auth::refresh::refresh::refresh;
 use crate::handlers::v1::current_user::current_user;
+use crate::handlers::v1::oauth::token::token;
+use crate::handlers::v1::service_accounts::create_service_account::create_service_account;
 use crate::handlers::v1::teams::get_teams::get_teams;
 use crate::handlers::v1::teams::create_team::create_team;
 use crate::handlers::v1::teams::users::add_member::add_member;
 use crate::handlers::v1::teams::users::get_team_members::get_team_members;
 use crate::handlers::v1::users::me::me;
 use crate::handlers::v1::health_check::health_check;
+use crate::handlers::v1::sessions::get_token_lineage::get_token_lineage;
+use crate::handlers::v1::users::change_password::change_password;
 use crate::handlers::v1::users::create_user::create_user;
 use crate::handlers::v1::users::get_user_details::get_user_details;
+use crate::handlers::v1::users::sessions::end_session::end_session;
+use crate::handlers::v1::users::sessions::end_user_sessions::end_user_sessions;
+use crate::handlers::v1::users::sessions::get_sessions::get_sessions;
+use crate::handlers::v1::users::tokens::create_personal_access_token::create_personal_access_token;
+use crate::handlers::v1::users::tokens::get_personal_access_tokens::get_personal_access_tokens;
+use crate::handlers::v1::users::tokens::revoke_personal_access_token::revoke_personal_access_token;
+use crate::handlers::v1::users::unlock_user::unlock_user;
+use crate::handlers::v1::users::totp::confirm_totp::confirm_totp;
+use crate::handlers::v1::users::totp::enrol_totp::enrol_totp;
+use crate::handlers::v1::users::totp::regenerate_recovery_codes::regenerate_recovery_codes;
+use crate::handlers::well_known::paserk::paserk;
 use crate::middleware::capture_trace_data::print_request_response;
+use crate::middleware::rate_limit::{rate_limit, RateLimitState};
+use crate::middleware::rate_limiter::RateLimiter;
 
 pub fn router(app_state: AppState) -> Router {
-    Router::new()
-        .route("/v1/health_check", get(health_check))
+    let state = Arc::new(app_state);
+    let rate_limiters = state.rate_limiters.clone();
+
+    let auth_routes = Router::new()
         .route("/v1/auth/login", post(login))
+        .route("/v1/auth/login/totp", post(login_totp))
+        .route("/v1/auth/login/recovery_code", post(login_recovery_code))
         .route("/v1/auth/refresh", post(refresh))
         .route("/v1/auth/logout", post(logout))
+        .route("/v1/auth/logout/everywhere", post(logout_everywhere))
+        .route("/v1/auth/forgot_password", post(forgot_password))
+        .route("/v1/auth/reset_password", post(reset_password))
+        .route("/v1/oauth/token", post(token));
+
+    let read_routes = Router::new()
         .route("/v1/users/:user_id", get(get_user_details))
-        .route("/v1/users", post(create_user))
         .route("/v1/user/current", get(current_user))
         .route("/v1/users/me", get(me))
-        .route("/v1/teams", post(create_team))
+        .route("/v1/users/me/sessions", get(get_sessions))
+        .route("/v1/users/me/tokens", get(get_personal_access_tokens))
+        .route("/v1/sessions/:session_id/tokens", get(get_token_lineage))
         .route("/v1/teams", get(get_teams))
-        .route("/v1/teams/:team_id/users/:user_id", post(add_member))
-        .route("/v1/teams/:team_id/users", get(get_team_members))
+        .route("/v1/teams/:team_id/users", get(get_team_members));
+
+    let write_routes = Router::new()
+        .route("/v1/users/:user_id/sessions", delete(end_user_sessions))
+        .route("/v1/users/:user_id/lockout", delete(unlock_user))
+        .route("/v1/users", post(create_user))
+        .route("/v1/service_accounts", post(create_service_account))
+        .route("/v1/users/me/password", put(change_password))
+        .route("/v1/users/me/totp", post(enrol_totp))
+        .route("/v1/users/me/totp/confirm", post(confirm_totp))
+        .route("/v1/users/me/totp/recovery_codes", post(regenerate_recovery_codes))
+        .route("/v1/users/me/sessions/:session_id", delete(end_session))
+        .route("/v1/users/me/tokens", post(create_personal_access_token))
+        .route("/v1/users/me/tokens/:token_id", delete(revoke_personal_access_token))
+        .route("/v1/teams", post(create_team))
+        .route("/v1/teams/:team_id/users/:user_id", post(add_member));
+
+    Router::new()
+        .route("/v1/health_check", get(health_check))
+        .route("/.well-known/paserk", get(paserk))
+        .merge(rate_limited(auth_routes, &state, rate_limiters.as_ref().map(|limiters| &limiters.auth)))
+        .merge(rate_limited(read_routes, &state, rate_limiters.as_ref().map(|limiters| &limiters.read)))
+        .merge(rate_limited(write_routes, &state, rate_limiters.as_ref().map(|limiters| &limiters.write)))
         .layer(middleware::from_fn(print_request_response))
         .layer(TraceLayer::new_for_http())
-        .with_state(Arc::new(app_state))
-}
\ No newline at end of file
+        .with_state(state)
+}
+
+/// Limits the requests to the routes with the rate limiter of their route group, if rate limiting is enabled.
+fn rate_limited(
+    routes: Router<Arc<AppState>>,
+    state: &Arc<AppState>,
+    limiter: Option<&Arc<RateLimiter>>,
+) -> Router<Arc<AppState>> {
+    let Some(limiter) = limiter else {
+        return routes
+    };
+
+    let rate_limit_state = RateLimitState {
+        app_state: state.clone(),
+        limiter: limiter.clone(),
+    };
+    routes.route_layer(middleware::from_fn_with_state(rate_limit_state, rate_limit))
+}
@@ -0,0 +1,201 @@
+use std::fmt::{Debug, Formatter};
+
+use anyhow::{ensure, Context};
+use secrecy::{ExposeSecret, Secret};
+
+use domain::sessions::session_policy::SessionPolicy;
+use infrastructure::jwt::jwt_error::{JwtDecryptionError, JwtEncryptionError};
+use infrastructure::jwt::jwt_token_encryptor::JwtTokenEncryptor;
+use infrastructure::paseto::paseto_error::{PasetoV4DecryptionError, PasetoV4EncryptionError};
+use infrastructure::paseto::paseto_token_encryptor::LocalPasetoV4TokenEncryptor;
+use infrastructure::paseto::public_paseto_token_signer::{PaserkPublicKey, PublicPasetoV4TokenSigner};
+use lib_util::errors::errors::format_error_chain;
+use security::encryption::decryptor::Decryptor;
+use security::encryption::encryptor::Encryptor;
+use security::token::token::Token;
+use security::token::token_encryptor::{EncryptedToken, TokenEncryptor};
+
+use crate::configuration::application::{ApplicationConfig, TokenFormat};
+
+/// SessionTokenEncryptor encrypts and decrypts the session tokens in the configured token format.
+#[derive(Clone)]
+pub enum SessionTokenEncryptor {
+    Local(LocalPasetoV4TokenEncryptor),
+    Public(PublicPasetoV4TokenSigner),
+    Jwt(JwtTokenEncryptor),
+}
+
+impl SessionTokenEncryptor {
+    pub fn new(config: &ApplicationConfig, policy: &SessionPolicy) -> Result<Self, anyhow::Error> {
+        let issuer = policy.issuer.clone();
+        let audience = policy.audience.clone();
+
+        let encryptor = match config.token_format {
+            TokenFormat::PasetoLocal => SessionTokenEncryptor::Local(LocalPasetoV4TokenEncryptor {
+                keyring: config.encryption_keyring().context("Failed to parse encryption keys")?,
+                issuer,
+                audience,
+                accept_legacy_audience: policy.accept_legacy_audience,
+            }),
+            TokenFormat::PasetoPublic => SessionTokenEncryptor::Public(PublicPasetoV4TokenSigner::new(
+                config.signing_key()
+                    .context("Failed to parse signing key")?
+                    .context("A signing key is required for the paseto_public token format")?,
+                &config.verification_keys().context("Failed to parse verification keys")?,
+                issuer,
+                audience,
+            ).context("Failed to create paseto signer")?),
+            TokenFormat::JwtHs256 => {
+                let secret = config.jwt_secret.as_ref()
+                    .context("A jwt secret is required for the jwt_hs256 token format")?;
+                ensure!(secret.expose_secret().len() >= 32, "The jwt secret should be at least 32 bytes long");
+
+                SessionTokenEncryptor::Jwt(JwtTokenEncryptor::hs256(secret, issuer, audience))
+            },
+            TokenFormat::JwtEddsa => SessionTokenEncryptor::Jwt(JwtTokenEncryptor::eddsa(
+                &config.signing_key()
+                    .context("Failed to parse signing key")?
+                    .context("A signing key is required for the jwt_eddsa token format")?,
+                &config.verification_keys().context("Failed to parse verification keys")?,
+                issuer,
+                audience,
+            ).context("Failed to create jwt signer")?),
+        };
+
+        Ok(encryptor)
+    }
+
+    /// Decrypts the token like `decrypt`, except that tokens of which the expiration has passed
+    /// are not rejected. Should only be used to inspect expired tokens, never to authenticate.
+    pub fn decrypt_ignoring_expiration<'a, T: Token<'a>>(
+        &self,
+        encrypted_token: &Secret<String>
+    ) -> Result<T, SessionTokenDecryptionError> {
+        let token = match self {
+            SessionTokenEncryptor::Local(encryptor) => encryptor.decrypt_ignoring_expiration(encrypted_token)?,
+            SessionTokenEncryptor::Public(signer) => signer.decrypt_ignoring_expiration(encrypted_token)?,
+            SessionTokenEncryptor::Jwt(encryptor) => encryptor.decrypt_ignoring_expiration(encrypted_token)?,
+        };
+
+        Ok(token)
+    }
+
+    /// Returns the public keys with which the tokens can be verified,
+    /// which there are none of when the tokens are encrypted or signed symmetrically.
+    pub fn public_keys(&self) -> Vec<PaserkPublicKey> {
+        match self {
+            SessionTokenEncryptor::Local(_) => vec![],
+            SessionTokenEncryptor::Public(signer) => signer.public_keys(),
+            SessionTokenEncryptor::Jwt(encryptor) => encryptor.public_keys(),
+        }
+    }
+}
+
+impl<'a, T: Token<'a>> Encryptor<'a, T, EncryptedToken> for SessionTokenEncryptor {
+    type EncryptionError = SessionTokenEncryptionError;
+
+    fn encrypt(&self, token: &'a T) -> Result<EncryptedToken, Self::EncryptionError> {
+        let encrypted_token = match self {
+            SessionTokenEncryptor::Local(encryptor) => encryptor.encrypt(token)?,
+            SessionTokenEncryptor::Public(signer) => signer.encrypt(token)?,
+            SessionTokenEncryptor::Jwt(encryptor) => encryptor.encrypt(token)?,
+        };
+
+        Ok(encrypted_token)
+    }
+}
+
+impl<'a, T: Token<'a>> Decryptor<T> for SessionTokenEncryptor {
+    type DecryptionError = SessionTokenDecryptionError;
+
+    fn decrypt(&self, encrypted_token: &Secret<String>) -> Result<T, Self::DecryptionError> {
+        let token = match self {
+            SessionTokenEncryptor::Local(encryptor) => encryptor.decrypt(encrypted_token)?,
+            SessionTokenEncryptor::Public(signer) => signer.decrypt(encrypted_token)?,
+            SessionTokenEncryptor::Jwt(encryptor) => encryptor.decrypt(encrypted_token)?,
+        };
+
+        Ok(token)
+    }
+}
+
+impl<'a, T: Token<'a>> TokenEncryptor<'a, T> for SessionTokenEncryptor {}
+
+impl Debug for SessionTokenEncryptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionTokenEncryptor::Local(_) => f.write_str("SessionTokenEncryptor::Local"),
+            SessionTokenEncryptor::Public(_) => f.write_str("SessionTokenEncryptor::Public"),
+            SessionTokenEncryptor::Jwt(_) => f.write_str("SessionTokenEncryptor::Jwt"),
+        }
+    }
+}
+
+#[derive(thiserror::Error)]
+pub enum SessionTokenEncryptionError {
+    #[error(transparent)]
+    Paseto(#[from] PasetoV4EncryptionError),
+
+    #[error(transparent)]
+    Jwt(#[from] JwtEncryptionError),
+}
+
+impl Debug for SessionTokenEncryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
+
+#[derive(thiserror::Error)]
+pub enum SessionTokenDecryptionError {
+    #[error(transparent)]
+    Paseto(#[from] PasetoV4DecryptionError),
+
+    #[error(transparent)]
+    Jwt(#[from] JwtDecryptionError),
+}
+
+impl SessionTokenDecryptionError {
+
+    /// Returns whether the token was rejected only because its expiration has passed.
+    pub fn is_expired(&self) -> bool {
+        matches!(
+            self,
+            SessionTokenDecryptionError::Paseto(PasetoV4DecryptionError::TokenExpired)
+            | SessionTokenDecryptionError::Jwt(JwtDecryptionError::TokenExpired)
+        )
+    }
+
+    /// Returns whether the token failed validation, as opposed to
+    /// the decryption failing because of a malformed token or an unexpected error.
+    pub fn is_invalid_token(&self) -> bool {
+        matches!(
+            self,
+            SessionTokenDecryptionError::Paseto(
+                PasetoV4DecryptionError::TokenNotYetActive
+                | PasetoV4DecryptionError::TokenExpired
+                | PasetoV4DecryptionError::UnexpectedIssuer
+                | PasetoV4DecryptionError::UnexpectedAudience
+                | PasetoV4DecryptionError::MissingKeyId
+                | PasetoV4DecryptionError::UnknownKeyId
+                | PasetoV4DecryptionError::KeyRetired
+                | PasetoV4DecryptionError::UnexpectedCustomClaims(_)
+            )
+            | SessionTokenDecryptionError::Jwt(
+                JwtDecryptionError::TokenNotYetActive
+                | JwtDecryptionError::TokenExpired
+                | JwtDecryptionError::UnexpectedIssuer
+                | JwtDecryptionError::UnexpectedAudience
+                | JwtDecryptionError::UnknownKeyId
+                | JwtDecryptionError::InvalidSignature
+                | JwtDecryptionError::UnexpectedCustomClaims(_)
+            )
+        )
+    }
+}
+
+impl Debug for SessionTokenDecryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::configuration::configuration::Configuration;
+use crate::configuration::password_hashing::PasswordHashingConfig;
 use crate::queries::database::Database;
 use anyhow::Context;
 use axum::Router;
@@ -7,8 +8,10 @@ use domain::user::new_user::NewUser;
 use domain::user::password::Password;
 use password_hash::SaltString;
 use secrecy::ExposeSecret;
+use security::hash::argon2::configure_argon2;
 use sqlx::migrate::MigrateError;
 use sqlx::PgPool;
+use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use uuid::Uuid;
 
@@ -17,6 +20,12 @@ pub async fn migrate(db: PgPool) -> Result<(), MigrateError> {
         .await
 }
 
+/// Configures how passwords are hashed, which has to happen before any password is hashed.
+pub fn configure_password_hashing(config: &PasswordHashingConfig) -> anyhow::Result<()> {
+    configure_argon2(config.scheme())
+        .context("Failed to configure Argon2")
+}
+
 pub async fn create_root_user(db: &Database, config: &Configuration, salt_string: &SaltString) -> anyhow::Result<()> {
     let user = db.get_user_credentials(&config.admin.username.expose_secret())
         .await
@@ -31,6 +40,7 @@ pub async fn create_root_user(db: &Database, config: &Configuration, salt_string
         username: config.admin.username.expose_secret().to_string(),
         password: Password::new(config.admin.password.clone(), salt_string)
             .context("Could not parse and hash admin password")?,
+        email: None,
         system_role: Some(SystemRole::Root),
     };
 
@@ -48,7 +58,7 @@ pub async fn run(
 ) -> anyhow::Result<()> {
 
     let listener = TcpListener::from_std(std_listener)?;
-    axum::serve(listener, router).await?;
+    axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
\ No newline at end of file
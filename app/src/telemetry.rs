@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::future::Future;
 
 use opentelemetry::KeyValue;
 use opentelemetry::trace::{TraceError, TracerProvider as _};
@@ -9,7 +10,7 @@ use opentelemetry_sdk::trace::{Config, TracerProvider};
 use secrecy::ExposeSecret;
 use serde::Deserialize;
 use tokio::task::JoinHandle;
-use tracing::Subscriber;
+use tracing::{Instrument, Subscriber};
 use tracing::subscriber::set_global_default;
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_log::LogTracer;
@@ -106,6 +107,16 @@ pub fn spawn_blocking_with_tracing<F, R>(f: F) -> JoinHandle<R>
     tokio::task::spawn_blocking(move || current_span.in_scope(f))
 }
 
+/// spawn_with_tracing runs the future in a new tokio task within the current tracing Span,
+/// for work that should not delay the response of a request.
+pub fn spawn_with_tracing<F>(future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+{
+    tokio::spawn(future.instrument(tracing::Span::current()))
+}
+
 pub trait TelemetryRecord {
     fn record_in_telemetry(&self, name_of_field: &str);
 }
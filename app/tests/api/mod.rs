@@ -1 +1,2 @@
 mod v1;
+mod well_known;
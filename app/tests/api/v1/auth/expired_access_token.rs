@@ -0,0 +1,54 @@
+use chrono::Duration;
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
+
+#[sqlx::test]
+async fn test_expired_access_token_beyond_grace_period_ends_session(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.expired_access_token_grace_period_seconds = Some(60);
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let expired_user = app.with_expired_access_token(&user, Duration::minutes(2));
+    let response = app.current_user(&expired_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // session should have been ended, so it can no longer be refreshed
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let events = app.get_security_event_kinds(user.user_id).await;
+    assert_eq!(events, vec!["UsedExpiredAccessToken".to_string()]);
+}
+
+#[sqlx::test]
+async fn test_expired_access_token_within_grace_period_keeps_session(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.expired_access_token_grace_period_seconds = Some(60);
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let expired_user = app.with_expired_access_token(&user, Duration::seconds(10));
+    let response = app.current_user(&expired_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+    assert!(app.get_security_event_kinds(user.user_id).await.is_empty());
+}
+
+#[sqlx::test]
+async fn test_expired_access_token_without_detection_keeps_session(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await.login().await;
+
+    let expired_user = app.with_expired_access_token(&user, Duration::hours(1));
+    let response = app.current_user(&expired_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+    assert!(app.get_security_event_kinds(user.user_id).await.is_empty());
+}
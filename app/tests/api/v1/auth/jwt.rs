@@ -0,0 +1,118 @@
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use pasetors::keys::AsymmetricPublicKey;
+use pasetors::version4::V4;
+use reqwest::StatusCode;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::PgPool;
+
+use app::configuration::application::TokenFormat;
+
+use crate::util::spawn_app::{assert_status_eq, random_jwt_secret, spawn_app_with_configuration};
+
+#[derive(Deserialize)]
+struct ExpectedPaserkResponse {
+    keys: Vec<ExpectedPaserkKey>,
+}
+
+#[derive(Deserialize)]
+struct ExpectedPaserkKey {
+    kid: String,
+    key: String,
+}
+
+fn validation(algorithm: Algorithm) -> Validation {
+    let mut validation = Validation::new(algorithm);
+    validation.set_issuer(&["issuer"]);
+    validation.set_audience(&["audience"]);
+    validation.set_required_spec_claims(&["exp", "nbf", "iss", "aud", "sub"]);
+    validation
+}
+
+#[sqlx::test]
+async fn test_hs256_tokens_can_be_verified_with_the_shared_secret(db: PgPool) {
+    let jwt_secret = random_jwt_secret();
+    let app = spawn_app_with_configuration(db, |config| {
+        config.application.token_format = TokenFormat::JwtHs256;
+        config.application.jwt_secret = Some(jwt_secret.clone());
+        config.sessions.issuer = "issuer".to_string();
+        config.sessions.audience = "audience".to_string();
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // the access token is a plain JWT, with the same claims as the paseto tokens
+    let access_token = user.state.access_token.token.as_str();
+    let header = decode_header(access_token).expect("Access token should be a JWT");
+    assert_eq!(header.alg, Algorithm::HS256);
+
+    let decoding_key = DecodingKey::from_secret(jwt_secret.expose_secret().as_bytes());
+    let claims = decode::<Value>(access_token, &decoding_key, &validation(Algorithm::HS256))
+        .expect("Failed to verify access token with the shared secret")
+        .claims;
+    assert_eq!(claims["data"]["user_id"], Value::String(user.user_id.to_string()));
+    assert!(claims["jti"].is_string());
+    assert!(claims["iat"].is_i64());
+
+    let refreshed_user = user.refresh().await;
+    assert_eq!(refreshed_user.current_user().await.user_id, user.user_id);
+}
+
+#[sqlx::test]
+async fn test_hs256_tokens_signed_with_another_secret_are_rejected(db: PgPool) {
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.application.token_format = TokenFormat::JwtHs256;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let other_app = spawn_app_with_configuration(db, |config| {
+        config.application.token_format = TokenFormat::JwtHs256;
+        config.application.jwt_secret = Some(Secret::new("a".repeat(32)));
+    }).await;
+
+    let response = other_app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = other_app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_eddsa_tokens_can_be_verified_with_published_key(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.application.token_format = TokenFormat::JwtEddsa;
+        config.sessions.issuer = "issuer".to_string();
+        config.sessions.audience = "audience".to_string();
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.get_paserk().await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let paserk = response.json::<ExpectedPaserkResponse>().await
+        .expect("Failed to parse ExpectedPaserkResponse");
+    assert_eq!(paserk.keys.len(), 1);
+    let published_key = &paserk.keys[0];
+
+    // the access token can be verified offline with the published key, found by the kid in its header
+    let access_token = user.state.access_token.token.as_str();
+    let header = decode_header(access_token).expect("Access token should be a JWT");
+    assert_eq!(header.alg, Algorithm::EdDSA);
+    assert_eq!(header.kid.as_ref(), Some(&published_key.kid));
+
+    let public_key = AsymmetricPublicKey::<V4>::try_from(published_key.key.as_str())
+        .expect("Failed to parse published key");
+    let decoding_key = DecodingKey::from_ed_der(public_key.as_bytes());
+    let claims = decode::<Value>(access_token, &decoding_key, &validation(Algorithm::EdDSA))
+        .expect("Failed to verify access token with published key")
+        .claims;
+    assert_eq!(claims["data"]["user_id"], Value::String(user.user_id.to_string()));
+
+    let refreshed_user = user.refresh().await;
+    assert_eq!(refreshed_user.current_user().await.user_id, user.user_id);
+}
@@ -0,0 +1,59 @@
+use chrono::{Duration, Utc};
+use reqwest::StatusCode;
+use secrecy::Secret;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use app::configuration::application::RetiredEncryptionKeyConfig;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app_with_configuration};
+
+fn random_encryption_key() -> Secret<String> {
+    Secret::new(Uuid::new_v4().simple().to_string())
+}
+
+#[sqlx::test]
+async fn test_tokens_of_retired_key_are_accepted_until_retirement(db: PgPool) {
+    let old_key = random_encryption_key();
+    let new_key = random_encryption_key();
+
+    let old_app = spawn_app_with_configuration(db.clone(), |config| {
+        config.application.encryption_key = old_key.clone();
+    }).await;
+    let user = old_app.create_test_user().await.login().await;
+
+    // after rotating, the sessions started with the old key continue
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.application.encryption_key = new_key.clone();
+        config.application.retired_encryption_keys = vec![RetiredEncryptionKeyConfig {
+            key: old_key.clone(),
+            retire_at: Utc::now() + Duration::hours(4),
+        }];
+    }).await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    // once retired, the tokens of the old key are rejected
+    let retired_app = spawn_app_with_configuration(db.clone(), |config| {
+        config.application.encryption_key = new_key.clone();
+        config.application.retired_encryption_keys = vec![RetiredEncryptionKeyConfig {
+            key: old_key.clone(),
+            retire_at: Utc::now() - Duration::seconds(1),
+        }];
+    }).await;
+
+    let response = retired_app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // as are the tokens of unknown keys
+    let other_app = spawn_app_with_configuration(db, |config| {
+        config.application.encryption_key = new_key.clone();
+    }).await;
+
+    let response = other_app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
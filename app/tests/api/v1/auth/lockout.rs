@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use app::configuration::configuration::Configuration;
+use app::configuration::login_lockout::FailedLoginStoreKind;
+use app::failed_logins::failed_login_store::FailedLoginStore;
+use app::failed_logins::postgres_failed_login_store::PostgresFailedLoginStore;
+use app::queries::database::Database;
+use domain::user::login_lockout::LockoutPolicy;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app_with_configuration};
+use crate::util::test_app::TestApp;
+
+fn lock_after_three_attempts(config: &mut Configuration) {
+    config.login_lockout.max_failed_attempts = 3;
+    config.login_lockout.lockout_seconds = 60;
+}
+
+async fn spawn_app(db: PgPool) -> TestApp {
+    spawn_app_with_configuration(db, lock_after_three_attempts).await
+}
+
+#[sqlx::test]
+async fn test_login_is_locked_after_max_failed_attempts(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let wrong_password = user.with_password("wrong password".to_string());
+
+    for _ in 0..3 {
+        let response = app.login(&wrong_password).await;
+        assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+    }
+
+    // even the correct password is rejected while the account is locked
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+
+    let retry_after: i64 = response.headers()[RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!(0 < retry_after && retry_after <= 60, "{}", retry_after);
+}
+
+#[sqlx::test]
+async fn test_lockout_does_not_reveal_whether_username_exists(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let unknown_user = user.with_username(Uuid::new_v4().to_string());
+
+    for _ in 0..3 {
+        let response = app.login(&unknown_user).await;
+        assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+    }
+
+    let response = app.login(&unknown_user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+    assert!(response.headers().contains_key(RETRY_AFTER));
+
+    // other usernames are not affected
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+}
+
+#[sqlx::test]
+async fn test_successful_login_resets_failed_attempts(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let wrong_password = user.with_password("wrong password".to_string());
+
+    for _ in 0..2 {
+        for _ in 0..2 {
+            let response = app.login(&wrong_password).await;
+            assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+        }
+
+        let response = app.login(&user).await;
+        assert_status_eq(&response, StatusCode::OK, None);
+    }
+}
+
+#[sqlx::test]
+async fn test_admin_can_unlock_account(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let admin = root.create_admin().await;
+    let other_user = root.create_user().await;
+    let user = app.create_test_user().await;
+    let wrong_password = user.with_password("wrong password".to_string());
+
+    for _ in 0..3 {
+        app.login(&wrong_password).await;
+    }
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+
+    let response = app.unlock_user(&other_user, user.user_id).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.unlock_user(&admin, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    let response = app.unlock_user(&admin, user.user_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, Some("Login after unlock".to_string()));
+}
+
+#[sqlx::test]
+async fn test_postgres_store_locks_account_on_every_instance(db: PgPool) {
+    let configure = |config: &mut Configuration| {
+        lock_after_three_attempts(config);
+        config.login_lockout.store = FailedLoginStoreKind::Postgres;
+    };
+    let app = spawn_app_with_configuration(db.clone(), configure).await;
+    let other_app = spawn_app_with_configuration(db, configure).await;
+
+    let user = app.create_test_user().await;
+    let wrong_password = user.with_password("wrong password".to_string());
+
+    app.login(&wrong_password).await;
+    other_app.login(&wrong_password).await;
+    app.login(&wrong_password).await;
+
+    let response = other_app.login(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+}
+
+#[sqlx::test]
+async fn test_postgres_store_deletes_forgotten_failed_logins(db: PgPool) {
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.login_lockout.window_seconds = 1;
+        config.login_lockout.store = FailedLoginStoreKind::Postgres;
+    }).await;
+
+    let user = app.create_test_user().await;
+    app.login(&user.with_password("wrong password".to_string())).await;
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    let other_user = app.create_test_user().await;
+    app.login(&other_user.with_password("wrong password".to_string())).await;
+
+    let usernames = sqlx::query_scalar!("SELECT username FROM failed_logins")
+        .fetch_all(&db)
+        .await
+        .expect("Failed to get usernames of failed logins");
+    assert_eq!(usernames, vec![other_user.username]);
+}
+
+#[sqlx::test]
+async fn test_postgres_store_counts_concurrent_failures_of_new_username(db: PgPool) {
+    let store = Arc::new(PostgresFailedLoginStore::new(Database(db)));
+    let policy = LockoutPolicy {
+        max_failed_attempts: 100,
+        ..LockoutPolicy::default()
+    };
+    let username = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let mut failures = JoinSet::new();
+    for _ in 0..20 {
+        let store = store.clone();
+        let username = username.clone();
+        failures.spawn(async move { store.record_failure(&username, &policy, now).await });
+    }
+
+    while let Some(failure) = failures.join_next().await {
+        failure.expect("Failed to join task").expect("Failed to record failure");
+    }
+
+    let failed_logins = store.get(&username)
+        .await
+        .expect("Failed to get failed logins")
+        .expect("Expected failed logins of username");
+    assert_eq!(failed_logins.failed_attempts, 20);
+}
@@ -3,8 +3,13 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
+use password_hash::SaltString;
+use secrecy::Secret;
 use uuid::Uuid;
-use crate::util::spawn_app::{assert_status_eq, spawn_app};
+use domain::sessions::session_concurrency::SessionConcurrency;
+use security::hash::argon2::{Argon2Params, Argon2Scheme};
+use security::hash::scheme::Scheme;
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
 
 #[sqlx::test]
 async fn login_should_give_unprocessable_entity_for_invalid_body(db: PgPool) {
@@ -115,3 +120,140 @@ async fn login_should_reject_invalid_username(db: PgPool) {
 }
 
 
+
+#[sqlx::test]
+async fn test_login_with_single_session_concurrency_ends_other_sessions(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.concurrency = SessionConcurrency::Single;
+    }).await;
+
+    let user = app.create_test_user().await;
+    let first_device = user.clone().login().await;
+    let second_device = user.login().await;
+
+    let response = app.refresh(&first_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&second_device).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_concurrent_logins_with_single_session_concurrency_leave_one_session(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.concurrency = SessionConcurrency::Single;
+    }).await;
+
+    let user = app.create_test_user().await;
+    tokio::join!(
+        user.clone().login(),
+        user.clone().login(),
+        user.clone().login(),
+    );
+
+    let active_sessions = app.get_session_ending_reasons(user.user_id)
+        .await
+        .into_iter()
+        .filter(|ending_reason| ending_reason.is_none())
+        .count();
+    assert_eq!(active_sessions, 1);
+}
+
+#[sqlx::test]
+async fn test_login_with_max_session_concurrency_ends_oldest_sessions(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.concurrency = SessionConcurrency::Max(2);
+    }).await;
+
+    let user = app.create_test_user().await;
+    let first_device = user.clone().login().await;
+    let second_device = user.clone().login().await;
+
+    // both sessions should still be active while at the limit
+    assert_eq!(second_device.get_sessions().await.len(), 2);
+
+    let third_device = user.login().await;
+    let response = app.refresh(&first_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    assert_eq!(third_device.get_sessions().await.len(), 2);
+    let response = app.refresh(&second_device).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_login_rehashes_password_with_weaker_params(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+
+    let weak_scheme = Argon2Scheme::new(Argon2Params { m_cost: 8192, t_cost: 1, p_cost: 1 });
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    let weak_hash = weak_scheme.hash(Secret::new(user.password.clone()), &salt)
+        .expect("Failed to hash password with weak params")
+        .to_string();
+    app.set_password_hash(user.user_id, &weak_hash).await;
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let params = Argon2Params::default();
+    let password_hash = app.get_password_hash(user.user_id).await;
+    assert_ne!(password_hash, weak_hash);
+    assert!(password_hash.contains(&format!("m={},t={},p={}", params.m_cost, params.t_cost, params.p_cost)));
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, Some("Login with rehashed password".to_string()));
+}
+
+#[sqlx::test]
+async fn test_login_rehashes_legacy_passwords(db: PgPool) {
+    let app = spawn_app(db).await;
+
+    // hashes of `legacy password`, as imported from a legacy system
+    let legacy_hashes = [
+        "$2b$04$KBCwKxOzLha2MUDgW0PjXeWKWdSoZ0jRTMFN//AhIbZlYynU0YrlW",
+        "$pbkdf2-sha256$i=1000,l=32$c2FsdHNhbHRzYWx0$KBsyyEc464z6vc0vmylEJvYjcmuf+3lVAVWJ5sf4wXM",
+    ];
+
+    for legacy_hash in legacy_hashes {
+        let test_user = app.create_test_user().await;
+        let user = test_user.with_password("legacy password".to_string());
+        app.set_password_hash(user.user_id, legacy_hash).await;
+
+        let response = app.login(&user).await;
+        assert_status_eq(&response, StatusCode::OK, Some(legacy_hash.to_string()));
+
+        let password_hash = app.get_password_hash(user.user_id).await;
+        assert!(password_hash.starts_with("$argon2id$"), "{}", password_hash);
+
+        let response = app.login(&user).await;
+        assert_status_eq(&response, StatusCode::OK, Some(format!("Login after rehashing {}", legacy_hash)));
+
+        let response = app.login(&user.with_password("other password".to_string())).await;
+        assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+    }
+}
+
+#[sqlx::test]
+async fn test_login_rehashes_passwords_without_pepper(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+
+    // the hash of a password stored before the pepper was configured
+    let unpeppered_scheme = Argon2Scheme::new(Argon2Params::default());
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    let unpeppered_hash = unpeppered_scheme.hash(Secret::new(user.password.clone()), &salt)
+        .expect("Failed to hash password without pepper")
+        .to_string();
+    app.set_password_hash(user.user_id, &unpeppered_hash).await;
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let password_hash = app.get_password_hash(user.user_id).await;
+    assert_ne!(password_hash, unpeppered_hash);
+    assert!(password_hash.contains("keyid="), "{}", password_hash);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, Some("Login with peppered password".to_string()));
+}
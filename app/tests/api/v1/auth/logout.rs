@@ -1,6 +1,6 @@
 use reqwest::StatusCode;
 use sqlx::PgPool;
-use crate::util::spawn_app::{assert_status_eq, spawn_app};
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
 
 #[sqlx::test]
 async fn test_logging_out_will_invalidate_refresh_token(db: PgPool) {
@@ -21,4 +21,102 @@ async fn test_logging_out_will_invalidate_refresh_token(db: PgPool) {
     // Assert can no longer refresh tokens
     let response = app.refresh(&user).await;
     assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
-}
\ No newline at end of file
+}
+#[sqlx::test]
+async fn test_access_token_is_rejected_after_logout_with_revocation_check(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.revocation_check = true;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.logout(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // second request is rejected based on the cached ended session
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_access_token_is_accepted_after_logout_without_revocation_check(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.logout(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // without the revocation check, access tokens stay valid until they expire
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+}
+
+#[sqlx::test]
+async fn test_sessions_ended_by_logout_everywhere_are_cached_with_revocation_check(db: PgPool) {
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.sessions.revocation_check = true;
+    }).await;
+    let user = app.create_test_user().await;
+    let other_session = user.clone().login().await;
+    let logged_in_user = user.login().await;
+
+    let response = app.current_user(&other_session).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.logout_everywhere(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // the ended sessions are rejected without querying the database,
+    // so reactivating them in the database goes unnoticed
+    sqlx::query!("UPDATE user_sessions SET ended_at = NULL, ending_reason = NULL")
+        .execute(&db)
+        .await
+        .expect("Failed to reactivate sessions");
+
+    let response = app.current_user(&other_session).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_sessions_ended_by_other_instances_are_noticed_with_active_session_cache(db: PgPool) {
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.sessions.revocation_check = true;
+        config.sessions.active_session_cache_seconds = Some(0);
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // ending the session in the database, as another instance would
+    sqlx::query!("UPDATE user_sessions SET ended_at = now(), ending_reason = 'UserLogout'")
+        .execute(&db)
+        .await
+        .expect("Failed to end sessions");
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_sessions_ended_by_other_instances_are_not_noticed_without_active_session_cache(db: PgPool) {
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.sessions.revocation_check = true;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    // ending the session in the database, as another instance would
+    sqlx::query!("UPDATE user_sessions SET ended_at = now(), ending_reason = 'UserLogout'")
+        .execute(&db)
+        .await
+        .expect("Failed to end sessions");
+
+    // only the sessions ended by this instance are rejected, without querying the database
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+}
@@ -1,3 +1,10 @@
+mod expired_access_token;
+mod jwt;
+mod key_rotation;
+mod lockout;
 mod login;
 mod logout;
+mod password_reset;
 mod refresh;
+mod token_claims;
+mod totp;
\ No newline at end of file
@@ -0,0 +1,111 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+use sqlx::PgPool;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
+use crate::util::test_app::TestApp;
+
+/// Returns the reset token of the link in the password reset email.
+fn reset_token_of(email: &Value) -> String {
+    let text_body = email["text_body"].as_str().expect("Email should have a text body");
+    let (_, token) = text_body.split_once("?token=").expect("Email should contain a reset link");
+    token.split_whitespace().next().unwrap().to_string()
+}
+
+/// Requests a password reset for the user and returns the token sent by email.
+async fn request_reset_token(app: &TestApp, username: &str) -> String {
+    let sent_emails = app.get_sent_emails().len();
+    let response = app.forgot_password(username).await;
+    assert_status_eq(&response, StatusCode::ACCEPTED, None);
+
+    let emails = app.wait_for_sent_emails(sent_emails + 1).await;
+    reset_token_of(emails.last().expect("A password reset email should have been sent"))
+}
+
+#[sqlx::test]
+async fn test_reset_password(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    app.set_email(user.user_id, "user@example.com").await;
+    let logged_in_user = user.clone().login().await;
+
+    let response = app.forgot_password(&user.username).await;
+    assert_status_eq(&response, StatusCode::ACCEPTED, None);
+
+    let emails = app.wait_for_sent_emails(1).await;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0]["recipient"], "user@example.com");
+    assert!(emails[0]["sender"].is_string());
+    let older_token = reset_token_of(&emails[0]);
+    let token = request_reset_token(&app, &user.username).await;
+
+    let response = app.reset_password(&token, "").await;
+    assert_status_eq(&response, StatusCode::UNPROCESSABLE_ENTITY, Some("Empty password".to_string()));
+
+    let response = app.reset_password(&token, "new password").await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, Some("Login with old password".to_string()));
+
+    let response = app.login(&user.with_password("new password".to_string())).await;
+    assert_status_eq(&response, StatusCode::OK, Some("Login with new password".to_string()));
+
+    // every session is ended, as someone else may know the old password
+    let response = app.refresh(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, Some("Refresh session from before reset".to_string()));
+
+    // the token is single-use, and resetting the password invalidates the other tokens as well
+    let response = app.reset_password(&token, "another password").await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, Some("Reuse of reset token".to_string()));
+
+    let response = app.reset_password(&older_token, "another password").await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, Some("Older reset token".to_string()));
+}
+
+#[sqlx::test]
+async fn test_forgot_password_does_not_reveal_users(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user_without_email = app.create_test_user().await;
+
+    let response = app.forgot_password("unknown user").await;
+    assert_status_eq(&response, StatusCode::ACCEPTED, Some("Unknown user".to_string()));
+
+    let response = app.forgot_password(&user_without_email.username).await;
+    assert_status_eq(&response, StatusCode::ACCEPTED, Some("User without email".to_string()));
+
+    // the emails are sent in the background, after responding
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert!(app.get_sent_emails().is_empty());
+}
+
+#[sqlx::test]
+async fn test_invalid_reset_tokens_are_rejected(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    app.set_email(user.user_id, "user@example.com").await;
+    let token = request_reset_token(&app, &user.username).await;
+
+    let response = app.reset_password("not a token", "new password").await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, Some("Malformed token".to_string()));
+
+    let (id, _) = token.split_once('.').unwrap();
+    let response = app.reset_password(&format!("{}.{}", id, "a".repeat(32)), "new password").await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, Some("Token with wrong secret".to_string()));
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, Some("Password should be unchanged".to_string()));
+}
+
+#[sqlx::test]
+async fn test_expired_reset_token_is_rejected(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.password_reset.token_lifetime_seconds = 0;
+    }).await;
+    let user = app.create_test_user().await;
+    app.set_email(user.user_id, "user@example.com").await;
+    let token = request_reset_token(&app, &user.username).await;
+
+    let response = app.reset_password(&token, "new password").await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+}
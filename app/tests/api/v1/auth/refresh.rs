@@ -1,7 +1,7 @@
 use reqwest::StatusCode;
 use sqlx::PgPool;
 
-use crate::util::spawn_app::{assert_status_eq, spawn_app};
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
 use crate::util::test_user::test_user::ExpectedRefreshResponse;
 
 #[sqlx::test]
@@ -68,4 +68,59 @@ async fn test_that_refresh_token_cannot_be_used_twice(db: PgPool) {
     // check if refresh user will not also no longer work
     let response = app.refresh(&refreshed_user).await;
     assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
-}
\ No newline at end of file
+}
+#[sqlx::test]
+async fn test_refresh_token_reused_within_grace_period_receives_same_tokens(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.refresh_token_reuse_grace_period_seconds = 10;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    // send two concurrent refresh requests with the same refresh token
+    let (first_response, second_response) = tokio::join!(user.refresh(), user.refresh());
+
+    // only one of the requests refreshed the session, the other reissued its tokens
+    assert_eq!(app.count_refresh_tokens(user.user_id).await, 2);
+
+    // both responses contain the latest tokens, so both can be used to continue the session
+    let response = app.current_user(&first_response).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.current_user(&second_response).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.refresh(&second_response).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_refresh_after_max_session_age_ends_session(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.max_session_age_seconds = 1;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let ending_reasons = app.get_session_ending_reasons(user.user_id).await;
+    assert_eq!(ending_reasons, vec![Some("MaxSessionAgeReached".to_string())]);
+}
+
+#[sqlx::test]
+async fn test_refresh_after_idle_timeout_ends_session(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.idle_timeout_seconds = Some(1);
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let ending_reasons = app.get_session_ending_reasons(user.user_id).await;
+    assert_eq!(ending_reasons, vec![Some("IdleTimeoutReached".to_string())]);
+}
@@ -0,0 +1,73 @@
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app_with_configuration};
+
+#[sqlx::test]
+async fn test_access_tokens_must_have_configured_issuer_and_audience(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.issuer = "issuer".to_string();
+        config.sessions.audience = "audience".to_string();
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let foreign_issuer = app.with_access_token_issued_for(&user, "other_issuer", "audience");
+    let response = app.current_user(&foreign_issuer).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let foreign_audience = app.with_access_token_issued_for(&user, "issuer", "other_audience");
+    let response = app.current_user(&foreign_audience).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_tokens_issued_before_audience_was_configurable_are_accepted(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.accept_legacy_audience = true;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+    let legacy_user = app.with_legacy_tokens(&user).await;
+
+    let response = app.current_user(&legacy_user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.refresh(&legacy_user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_tokens_issued_before_audience_was_configurable_can_be_rejected(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.accept_legacy_audience = false;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+    let legacy_user = app.with_legacy_tokens(&user).await;
+
+    let response = app.current_user(&legacy_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&legacy_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_access_tokens_expire_after_configured_lifetime(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.access_token_lifetime_seconds = 1;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
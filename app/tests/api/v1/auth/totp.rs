@@ -0,0 +1,281 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use security::otp::totp::TotpSecret;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
+use crate::util::test_app::TestApp;
+use crate::util::test_user::anonymous::Anonymous;
+use crate::util::test_user::logged_in::LoggedIn;
+use crate::util::test_user::test_user::TestUser;
+use crate::util::test_user::user_state::Token;
+
+#[derive(Deserialize)]
+struct ExpectedEnrolTotpResponse {
+    secret: String,
+    otpauth_uri: String,
+    recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ExpectedRecoveryCodesResponse {
+    recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+enum ExpectedLoginResponse {
+    UserLoggedInSuccessfully {
+        access_token: String,
+        access_token_expiration: DateTime<Utc>,
+        refresh_token: String,
+        refresh_token_expiration: DateTime<Utc>,
+    },
+    MfaRequired {
+        mfa_token: String,
+        mfa_token_expiration: DateTime<Utc>,
+    },
+}
+
+/// Enrols the user in two-factor authentication and confirms it with the code of the previous time
+/// step, so that the code of the current time step has not been used yet.
+/// Returns the secret together with the recovery codes.
+async fn enable_totp(app: &TestApp, user: &TestUser<'_, LoggedIn>) -> (TotpSecret, Vec<String>) {
+    let response = app.enrol_totp(user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+    let enrolment = response.json::<ExpectedEnrolTotpResponse>().await
+        .expect("Failed to parse ExpectedEnrolTotpResponse");
+    let secret = TotpSecret::from_base32(&enrolment.secret).expect("Failed to parse TOTP secret");
+
+    let response = app.confirm_totp(user, &secret.code_at(Utc::now() - Duration::seconds(30))).await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+
+    (secret, enrolment.recovery_codes)
+}
+
+async fn login_response(response: Response) -> ExpectedLoginResponse {
+    assert_status_eq(&response, StatusCode::OK, None);
+    response.json::<ExpectedLoginResponse>().await
+        .expect("Failed to parse ExpectedLoginResponse")
+}
+
+async fn mfa_token_of(response: Response) -> String {
+    match login_response(response).await {
+        ExpectedLoginResponse::MfaRequired { mfa_token, mfa_token_expiration } => {
+            assert!(mfa_token_expiration > Utc::now());
+            mfa_token
+        },
+        response => panic!("Expected a one-time password to be required, got {:?}", response),
+    }
+}
+
+#[sqlx::test]
+async fn test_enrolment_requires_confirmation(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+
+    let response = app.enrol_totp(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+    let enrolment = response.json::<ExpectedEnrolTotpResponse>().await
+        .expect("Failed to parse ExpectedEnrolTotpResponse");
+    assert!(enrolment.otpauth_uri.starts_with(&format!("otpauth://totp/rust_backend_setup:{}?", user.username)));
+    assert!(enrolment.otpauth_uri.contains(&format!("secret={}", enrolment.secret)));
+    assert_eq!(enrolment.recovery_codes.len(), 10);
+
+    // the secret is stored encrypted
+    let encrypted_secret = app.get_encrypted_totp_secret(user.user_id).await
+        .expect("Expected TOTP credential to be stored");
+    assert!(encrypted_secret.starts_with("v4.local."));
+    assert!(!encrypted_secret.contains(&enrolment.secret));
+
+    // an unconfirmed secret does not protect the account
+    let response = app.login(&user).await;
+    assert!(matches!(login_response(response).await, ExpectedLoginResponse::UserLoggedInSuccessfully { .. }));
+
+    let secret = TotpSecret::from_base32(&enrolment.secret).expect("Failed to parse TOTP secret");
+    let wrong_code = secret.code_at(Utc::now() + Duration::minutes(10));
+    let response = app.confirm_totp(&logged_in_user, &wrong_code).await;
+    assert_status_eq(&response, StatusCode::UNPROCESSABLE_ENTITY, None);
+
+    let response = app.confirm_totp(&logged_in_user, &secret.code_at(Utc::now())).await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+
+    // a confirmed secret cannot be replaced or confirmed again
+    let response = app.enrol_totp(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::CONFLICT, None);
+
+    let response = app.confirm_totp(&logged_in_user, &secret.code_at(Utc::now() + Duration::seconds(30))).await;
+    assert_status_eq(&response, StatusCode::CONFLICT, None);
+}
+
+#[sqlx::test]
+async fn test_login_with_totp(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let (secret, _) = enable_totp(&app, &user.clone().login().await).await;
+
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+
+    let wrong_code = secret.code_at(Utc::now() + Duration::minutes(10));
+    let response = app.login_totp(&mfa_token, &wrong_code).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let code = secret.code_at(Utc::now());
+    let response = login_response(app.login_totp(&mfa_token, &code).await).await;
+    let logged_in_user = logged_in(&app, &user, response);
+    assert_eq!(logged_in_user.current_user().await.user_id, user.user_id);
+
+    // a code can only be used once, even with a new mfa token
+    let response = app.login_totp(&mfa_token, &code).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    let response = app.login_totp(&mfa_token, &code).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_login_with_totp_requires_correct_password(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let _ = enable_totp(&app, &user.clone().login().await).await;
+
+    let response = app.login(&user.with_password("wrong password".to_string())).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_failed_second_factors_lock_account(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.login_lockout.max_failed_attempts = 3;
+        config.login_lockout.lockout_seconds = 60;
+    }).await;
+    let user = app.create_test_user().await;
+    let (secret, recovery_codes) = enable_totp(&app, &user.clone().login().await).await;
+
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    let wrong_code = secret.code_at(Utc::now() + Duration::minutes(10));
+    app.login_totp(&mfa_token, &wrong_code).await;
+    app.login_totp(&mfa_token, &wrong_code).await;
+    app.login_recovery_code(&mfa_token, "aaaaa-aaaaa").await;
+
+    // the mfa token of the password entered before the lockout cannot be used either
+    let response = app.login_totp(&mfa_token, &secret.code_at(Utc::now())).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+
+    let response = app.login_recovery_code(&mfa_token, &recovery_codes[0]).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+}
+
+#[sqlx::test]
+async fn test_mfa_token_cannot_be_used_as_access_token(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+    let (secret, _) = enable_totp(&app, &logged_in_user).await;
+
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    let mut mfa_user = logged_in_user.clone();
+    mfa_user.state.access_token.token = mfa_token;
+    let response = app.current_user(&mfa_user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // an access token is not accepted in place of the mfa token either
+    let access_token = logged_in_user.state.access_token.token.as_str();
+    let response = app.login_totp(access_token, &secret.code_at(Utc::now())).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_expired_mfa_token_is_rejected(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.sessions.mfa_pending_token_lifetime_seconds = 1;
+    }).await;
+    let user = app.create_test_user().await;
+    let (secret, _) = enable_totp(&app, &user.clone().login().await).await;
+
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    let response = app.login_totp(&mfa_token, &secret.code_at(Utc::now())).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+
+fn logged_in<'a>(app: &'a TestApp, user: &TestUser<'a, Anonymous>, response: ExpectedLoginResponse) -> TestUser<'a, LoggedIn> {
+    match response {
+        ExpectedLoginResponse::UserLoggedInSuccessfully {
+            access_token,
+            access_token_expiration,
+            refresh_token,
+            refresh_token_expiration
+        } => TestUser {
+            user_id: user.user_id,
+            username: user.username.clone(),
+            password: user.password.clone(),
+            state: LoggedIn {
+                access_token: Token { token: access_token, expiration: access_token_expiration },
+                refresh_token: Token { token: refresh_token, expiration: refresh_token_expiration },
+            },
+            app,
+        },
+        response => panic!("Expected user to be logged in, got {:?}", response),
+    }
+}
+
+#[sqlx::test]
+async fn test_login_with_recovery_code(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let (_, recovery_codes) = enable_totp(&app, &user.clone().login().await).await;
+
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    let response = app.login_recovery_code(&mfa_token, "aaaaa-aaaaa").await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // recovery codes may be entered in upper case
+    let response = app.login_recovery_code(&mfa_token, &recovery_codes[0].to_uppercase()).await;
+    let logged_in_user = logged_in(&app, &user, login_response(response).await);
+    assert_eq!(logged_in_user.current_user().await.user_id, user.user_id);
+
+    // every recovery code can only be used once
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    let response = app.login_recovery_code(&mfa_token, &recovery_codes[0]).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.login_recovery_code(&mfa_token, &recovery_codes[1]).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+}
+
+#[sqlx::test]
+async fn test_regenerate_recovery_codes(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+
+    // users without two-factor authentication have no use for recovery codes
+    let response = app.regenerate_recovery_codes(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    let (_, old_recovery_codes) = enable_totp(&app, &logged_in_user).await;
+
+    let response = app.regenerate_recovery_codes(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+    let new_recovery_codes = response.json::<ExpectedRecoveryCodesResponse>().await
+        .expect("Failed to parse ExpectedRecoveryCodesResponse")
+        .recovery_codes;
+    assert_eq!(new_recovery_codes.len(), 10);
+
+    // the old recovery codes can no longer be used
+    let mfa_token = mfa_token_of(app.login(&user).await).await;
+    let response = app.login_recovery_code(&mfa_token, &old_recovery_codes[0]).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.login_recovery_code(&mfa_token, &new_recovery_codes[0]).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+}
@@ -3,3 +3,6 @@ mod current_user;
 mod teams;
 mod health_check;
 mod users;
+mod sessions;
+mod rate_limit;
+mod service_accounts;
@@ -0,0 +1,58 @@
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use app::middleware::rate_limiter::RateLimit;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app_with_configuration};
+
+/// A rate limit which is not refilled during a test.
+const TWO_REQUESTS: RateLimit = RateLimit { capacity: 2, refill_per_second: 0.001 };
+
+#[sqlx::test]
+async fn test_login_is_rate_limited_per_ip(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.rate_limit.enabled = true;
+        config.rate_limit.auth = TWO_REQUESTS;
+    }).await;
+    let user = app.create_test_user().await;
+
+    let response = app.login(&user.with_password("wrong password".to_string())).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+
+    let retry_after: u64 = response.headers()[RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!(retry_after > 0, "{}", retry_after);
+}
+
+#[sqlx::test]
+async fn test_authenticated_requests_are_rate_limited_per_user(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.rate_limit.enabled = true;
+        config.rate_limit.read = TWO_REQUESTS;
+    }).await;
+    let user = app.create_test_user().await.login().await;
+    let other_user = app.create_test_user().await.login().await;
+
+    for _ in 0..2 {
+        let response = app.current_user(&user).await;
+        assert_status_eq(&response, StatusCode::OK, None);
+    }
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, None);
+    assert!(response.headers().contains_key(RETRY_AFTER));
+
+    // requests of other users from the same address are limited separately
+    let response = app.current_user(&other_user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // and so are the requests to other route groups
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+
+use reqwest::header::{CACHE_CONTROL, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app};
+use crate::util::test_app::TestApp;
+use crate::util::test_user::logged_in::LoggedIn;
+use crate::util::test_user::test_user::TestUser;
+
+struct ClientCredentials {
+    user_id: Uuid,
+    client_id: String,
+    client_secret: String,
+}
+
+/// Creates a service account with the request body, returns its client credentials.
+async fn create_service_account(app: &TestApp, user: &TestUser<'_, LoggedIn>, body: Value) -> ClientCredentials {
+    let response = app.create_service_account(user, body).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    let body = response.json::<Value>().await.expect("Failed to parse created service account");
+    ClientCredentials {
+        user_id: Uuid::parse_str(body["user_id"].as_str().unwrap()).unwrap(),
+        client_id: body["client_id"].as_str().unwrap().to_string(),
+        client_secret: body["client_secret"].as_str().unwrap().to_string(),
+    }
+}
+
+/// Requests an access token for the service account with the scope, returns the access token.
+async fn request_access_token(app: &TestApp, credentials: &ClientCredentials, scope: Option<&str>) -> String {
+    let mut form = vec![("grant_type", "client_credentials")];
+    if let Some(scope) = scope {
+        form.push(("scope", scope));
+    }
+
+    let response = app.request_token(Some((&credentials.client_id, &credentials.client_secret)), &form).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let body = response.json::<Value>().await.expect("Failed to parse token response");
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+#[sqlx::test]
+async fn test_service_account_with_admin_role_can_manage_teams(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let credentials = create_service_account(&app, &root, json!({
+        "id": Uuid::new_v4(),
+        "name": "provisioning-service",
+        "role": "Admin",
+    })).await;
+
+    let response = app.request_token(Some((&credentials.client_id, &credentials.client_secret)), &[
+        ("grant_type", "client_credentials"),
+    ]).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    assert_eq!(response.headers().get(CACHE_CONTROL).unwrap(), "no-store");
+
+    let body = response.json::<Value>().await.expect("Failed to parse token response");
+    assert_eq!(body["token_type"], "Bearer");
+    assert_eq!(body["scope"], "read write");
+    assert!(body["expires_in"].as_i64().unwrap() > 0);
+    assert!(body.get("refresh_token").is_none());
+
+    let service_account = app.with_bearer_token(&root, body["access_token"].as_str().unwrap());
+    let team_id = Uuid::new_v4();
+    let response = app.create_team(&service_account, team_id).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    let response = app.add_team_member(&service_account, team_id, credentials.user_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.get_teams(&service_account).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let teams = response.json::<HashSet<Uuid>>().await.expect("Failed to parse get_teams result");
+    assert!(teams.contains(&team_id));
+}
+
+#[sqlx::test]
+async fn test_service_account_is_subject_to_team_membership(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let team_id = Uuid::new_v4();
+    let response = app.create_team(&root, team_id).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    let credentials = create_service_account(&app, &root, json!({
+        "id": Uuid::new_v4(),
+        "name": "reporting-service",
+        "role": null,
+        "team_id": team_id,
+    })).await;
+    let access_token = request_access_token(&app, &credentials, None).await;
+    let service_account = app.with_bearer_token(&root, &access_token);
+
+    // members only see their own teams
+    let response = app.get_teams(&service_account).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let teams = response.json::<HashSet<Uuid>>().await.expect("Failed to parse get_teams result");
+    assert_eq!(teams, HashSet::from([team_id]));
+
+    let response = app.create_team(&service_account, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.add_team_member(&service_account, team_id, root.user_id).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+}
+
+#[sqlx::test]
+async fn test_service_account_access_token_is_limited_to_requested_scopes(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let credentials = create_service_account(&app, &root, json!({
+        "id": Uuid::new_v4(),
+        "name": "audit-service",
+        "role": "Root",
+    })).await;
+    let access_token = request_access_token(&app, &credentials, Some("read")).await;
+    let service_account = app.with_bearer_token(&root, &access_token);
+
+    let response = app.get_teams(&service_account).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.create_team(&service_account, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.request_token(Some((&credentials.client_id, &credentials.client_secret)), &[
+        ("grant_type", "client_credentials"),
+        ("scope", "read admin"),
+    ]).await;
+    assert_status_eq(&response, StatusCode::BAD_REQUEST, None);
+    let body = response.json::<Value>().await.expect("Failed to parse error response");
+    assert_eq!(body["error"], "invalid_scope");
+}
+
+#[sqlx::test]
+async fn test_service_account_cannot_use_session_endpoints(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let name = Uuid::new_v4().to_string();
+    let credentials = create_service_account(&app, &root, json!({
+        "id": Uuid::new_v4(),
+        "name": name,
+        "role": null,
+    })).await;
+    let access_token = request_access_token(&app, &credentials, None).await;
+    let service_account = app.with_bearer_token(&root, &access_token);
+
+    let response = app.get_sessions(&service_account).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    // service accounts have no password to log in with
+    let response = app.post_login(json!({
+        "username": name,
+        "password": credentials.client_secret,
+    })).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_token_endpoint_accepts_client_credentials_in_body(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let credentials = create_service_account(&app, &root, json!({
+        "id": Uuid::new_v4(),
+        "name": "billing-service",
+        "role": null,
+    })).await;
+
+    let response = app.request_token(None, &[
+        ("grant_type", "client_credentials"),
+        ("client_id", &credentials.client_id),
+        ("client_secret", &credentials.client_secret),
+    ]).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // clients must not authenticate with more than one method
+    let response = app.request_token(Some((&credentials.client_id, &credentials.client_secret)), &[
+        ("grant_type", "client_credentials"),
+        ("client_id", &credentials.client_id),
+        ("client_secret", &credentials.client_secret),
+    ]).await;
+    assert_status_eq(&response, StatusCode::BAD_REQUEST, None);
+}
+
+#[sqlx::test]
+async fn test_token_endpoint_rejects_invalid_requests(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let credentials = create_service_account(&app, &root, json!({
+        "id": Uuid::new_v4(),
+        "name": "billing-service",
+        "role": null,
+    })).await;
+    let client_credentials = Some((credentials.client_id.as_str(), credentials.client_secret.as_str()));
+
+    let invalid_requests = [
+        (client_credentials, vec![], StatusCode::BAD_REQUEST, "invalid_request"),
+        (client_credentials, vec![("grant_type", "password")], StatusCode::BAD_REQUEST, "unsupported_grant_type"),
+        (None, vec![("grant_type", "client_credentials")], StatusCode::UNAUTHORIZED, "invalid_client"),
+        (Some((credentials.client_id.as_str(), "wrong")), vec![("grant_type", "client_credentials")], StatusCode::UNAUTHORIZED, "invalid_client"),
+        (Some(("sa_unknown", credentials.client_secret.as_str())), vec![("grant_type", "client_credentials")], StatusCode::UNAUTHORIZED, "invalid_client"),
+    ];
+    for (client_credentials, form, status_code, error) in invalid_requests {
+        let response = app.request_token(client_credentials, &form).await;
+        assert_status_eq(&response, status_code, Some(error.to_string()));
+        if status_code == StatusCode::UNAUTHORIZED {
+            assert_eq!(response.headers().get(WWW_AUTHENTICATE).unwrap(), "Basic");
+        }
+
+        let body = response.json::<Value>().await.expect("Failed to parse error response");
+        assert_eq!(body["error"], error);
+    }
+}
+
+#[sqlx::test]
+async fn test_user_without_role_cannot_create_service_account(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await.login().await;
+
+    let response = app.create_service_account(&user, json!({
+        "id": Uuid::new_v4(),
+        "name": "billing-service",
+        "role": null,
+    })).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+}
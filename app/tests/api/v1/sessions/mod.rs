@@ -0,0 +1 @@
+mod token_lineage;
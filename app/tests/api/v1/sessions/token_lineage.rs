@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app};
+
+#[derive(Deserialize)]
+struct TokenLineageResponse {
+    session_id: Uuid,
+    ended_at: Option<DateTime<Utc>>,
+    ending_reason: Option<String>,
+    ending_token_id: Option<Uuid>,
+    refresh_tokens: Vec<RefreshTokenResponse>
+}
+
+#[derive(Deserialize)]
+struct RefreshTokenResponse {
+    id: Uuid,
+    parent_id: Option<Uuid>,
+    used_at: Option<DateTime<Utc>>,
+}
+
+#[sqlx::test]
+async fn test_token_lineage_shows_replayed_refresh_token(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let admin = root.create_admin().await;
+    let user = app.create_test_user().await.login().await;
+    let session_id = user.current_user().await.session_id;
+
+    let refreshed_user = user.refresh().await;
+    let _ = refreshed_user.refresh().await;
+
+    let response = app.get_token_lineage(&admin, session_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let lineage: TokenLineageResponse = response.json().await.expect("Failed to parse token lineage");
+
+    assert_eq!(lineage.session_id, session_id);
+    assert!(lineage.ended_at.is_none());
+    assert_eq!(lineage.refresh_tokens.len(), 3);
+
+    // every token is the child of the previous token, and all but the latest are used
+    assert!(lineage.refresh_tokens[0].parent_id.is_none());
+    for (parent, child) in lineage.refresh_tokens.iter().zip(lineage.refresh_tokens.iter().skip(1)) {
+        assert_eq!(child.parent_id, Some(parent.id));
+        assert!(parent.used_at.is_some());
+    }
+    assert!(lineage.refresh_tokens[2].used_at.is_none());
+
+    // replaying the first refresh token ends the session
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let lineage: TokenLineageResponse = app.get_token_lineage(&root, session_id).await
+        .json()
+        .await
+        .expect("Failed to parse token lineage");
+
+    assert!(lineage.ended_at.is_some());
+    assert_eq!(lineage.ending_reason.as_deref(), Some("AttemptedToReuseRefreshToken"));
+    assert_eq!(lineage.ending_token_id, Some(lineage.refresh_tokens[0].id));
+}
+
+#[sqlx::test]
+async fn test_token_lineage_is_only_readable_by_admins(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let user = app.create_test_user().await.login().await;
+    let session_id = user.current_user().await.session_id;
+
+    let response = app.get_token_lineage(&user, session_id).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.get_token_lineage(&root, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+}
@@ -1,6 +1,7 @@
-use crate::util::spawn_app::{assert_status_eq, spawn_app};
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
 use crate::util::test_app::NewUserBody;
 use reqwest::StatusCode;
+use serde_json::{json, Value};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -112,3 +113,39 @@ async fn test_admin_can_create_user_without_roles(db: PgPool) {
     let details = user.get_user_details().await;
     assert!(details.system_role.is_none());
 }
+
+#[sqlx::test]
+async fn test_create_user_rejects_password_violating_policy(db: PgPool) {
+    let breached_passwords_file = std::env::temp_dir().join(format!("breached_passwords_{}.txt", Uuid::new_v4().simple()));
+    std::fs::write(&breached_passwords_file, "password123\nqwertyuiop\n")
+        .expect("Failed to write breached passwords file");
+
+    let app = spawn_app_with_configuration(db, |config| {
+        config.password_policy.max_length = 32;
+        config.password_policy.breached_passwords_file = Some(breached_passwords_file.clone());
+    }).await;
+    let root = app.get_root_user().await;
+
+    let mut user = create_new_user_body(None);
+    user.username = "johndoe".to_string();
+
+    let cases = [
+        ("short", json!([{ "reason": "too_short", "min_length": 8 }])),
+        (&"a".repeat(33), json!([{ "reason": "too_long", "max_length": 32 }])),
+        ("my-JohnDoe-password", json!([{ "reason": "contains_username" }])),
+        ("qwertyuiop", json!([{ "reason": "breached" }])),
+    ];
+    for (password, expected_violations) in cases {
+        user.password = password.to_string();
+        let response = app.create_user(&root, user.clone()).await;
+        assert_status_eq(&response, StatusCode::UNPROCESSABLE_ENTITY, Some(password.to_string()));
+
+        let body = response.json::<Value>().await.expect("Failed to parse violations");
+        assert_eq!(body["violations"], expected_violations, "{}", password);
+    }
+
+    let response = app.get_user_details(&root, user.id).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    std::fs::remove_file(&breached_passwords_file).expect("Failed to remove breached passwords file");
+}
@@ -1 +1,4 @@
-mod create_user;
\ No newline at end of file
+mod create_user;
+mod password;
+mod sessions;
+mod personal_access_tokens;
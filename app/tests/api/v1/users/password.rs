@@ -0,0 +1,134 @@
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use domain::user::password::CharacterClass;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
+
+#[sqlx::test]
+async fn test_change_password(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+    let other_device = user.clone().login().await;
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": "not the current password",
+        "new_password": "new password",
+    })).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, Some("Wrong current password".to_string()));
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": user.password,
+        "new_password": user.password,
+    })).await;
+    assert_status_eq(&response, StatusCode::UNPROCESSABLE_ENTITY, Some("Unchanged password".to_string()));
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": user.password,
+        "new_password": "new password",
+    })).await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+
+    let response = app.login(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, Some("Login with old password".to_string()));
+
+    let response = app.login(&user.with_password("new password".to_string())).await;
+    assert_status_eq(&response, StatusCode::OK, Some("Login with new password".to_string()));
+
+    // other sessions are kept unless requested otherwise
+    let response = app.refresh(&other_device).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_change_password_ends_other_sessions(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+    let other_device = user.clone().login().await;
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": user.password,
+        "new_password": "new password",
+        "end_other_sessions": true,
+    })).await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+
+    let response = app.refresh(&other_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // the session that changed the password stays active
+    let response = app.refresh(&logged_in_user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    let ending_reasons = app.get_session_ending_reasons(user.user_id).await;
+    assert_eq!(ending_reasons, vec![None, Some("PasswordChanged".to_string())]);
+}
+
+#[sqlx::test]
+async fn test_change_password_enforces_password_policy(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.password_policy.required_character_classes = vec![CharacterClass::Uppercase, CharacterClass::Digit];
+    }).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": user.password,
+        "new_password": "",
+    })).await;
+    assert_status_eq(&response, StatusCode::UNPROCESSABLE_ENTITY, Some("Empty password".to_string()));
+    let body = response.json::<Value>().await.expect("Failed to parse violations");
+    assert_eq!(body["violations"], json!([
+        { "reason": "too_short", "min_length": 8 },
+        { "reason": "missing_character_class", "character_class": "uppercase" },
+        { "reason": "missing_character_class", "character_class": "digit" },
+    ]));
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": user.password,
+        "new_password": "New password 1",
+    })).await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+}
+
+#[sqlx::test]
+async fn test_wrong_current_passwords_lock_account(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.login_lockout.max_failed_attempts = 3;
+        config.login_lockout.lockout_seconds = 60;
+    }).await;
+    let user = app.create_test_user().await;
+    let logged_in_user = user.clone().login().await;
+    let wrong_current_password = json!({
+        "current_password": "not the current password",
+        "new_password": "new password",
+    });
+
+    // a successful change forgets the previous wrong passwords
+    for _ in 0..2 {
+        let response = app.change_password(&logged_in_user, wrong_current_password.clone()).await;
+        assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+    }
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": user.password,
+        "new_password": "new password",
+    })).await;
+    assert_status_eq(&response, StatusCode::NO_CONTENT, None);
+
+    for _ in 0..3 {
+        let response = app.change_password(&logged_in_user, wrong_current_password.clone()).await;
+        assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+    }
+
+    let response = app.change_password(&logged_in_user, json!({
+        "current_password": "new password",
+        "new_password": "newer password",
+    })).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, Some("Change password while locked".to_string()));
+
+    let response = app.login(&user.with_password("new password".to_string())).await;
+    assert_status_eq(&response, StatusCode::TOO_MANY_REQUESTS, Some("Login while locked".to_string()));
+}
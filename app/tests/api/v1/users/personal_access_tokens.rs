@@ -0,0 +1,178 @@
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app};
+use crate::util::test_app::{NewUserBody, TestApp};
+use crate::util::test_user::logged_in::LoggedIn;
+use crate::util::test_user::test_user::TestUser;
+
+fn new_user_body() -> NewUserBody {
+    NewUserBody {
+        id: Uuid::new_v4(),
+        username: Uuid::new_v4().to_string(),
+        password: Uuid::new_v4().to_string(),
+        role: None,
+    }
+}
+
+/// Creates a personal access token with the scopes for the user, returns the created token.
+async fn create_token(app: &TestApp, user: &TestUser<'_, LoggedIn>, scopes: Value) -> Value {
+    let response = app.create_personal_access_token(user, json!({
+        "name": "ci",
+        "scopes": scopes,
+    })).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    response.json::<Value>().await.expect("Failed to parse created personal access token")
+}
+
+#[sqlx::test]
+async fn test_personal_access_token_can_be_used_instead_of_access_token(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let created = create_token(&app, &root, json!(["read", "write"])).await;
+    let token = created["token"].as_str().unwrap();
+    assert!(token.starts_with(created["prefix"].as_str().unwrap()));
+    assert_eq!(created["scopes"], json!(["read", "write"]));
+    assert_eq!(created["expires_at"], Value::Null);
+
+    let automation = app.with_bearer_token(&root, token);
+    let response = app.get_teams(&automation).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.create_user(&automation, new_user_body()).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_read_only_personal_access_token_cannot_write(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let created = create_token(&app, &root, json!(["read"])).await;
+    let automation = app.with_bearer_token(&root, created["token"].as_str().unwrap());
+
+    let response = app.get_teams(&automation).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // root could create the user, but the token only allows reading
+    let response = app.create_user(&automation, new_user_body()).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.create_team(&automation, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+}
+
+#[sqlx::test]
+async fn test_revoked_personal_access_token_is_rejected(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let first = create_token(&app, &root, json!(["read"])).await;
+    let second = create_token(&app, &root, json!(["write"])).await;
+
+    let response = app.get_personal_access_tokens(&root).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let tokens = response.json::<Vec<Value>>().await.expect("Failed to parse personal access tokens");
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens.iter().all(|token| token.get("token").is_none()));
+
+    let first_id = Uuid::parse_str(first["id"].as_str().unwrap()).unwrap();
+    let response = app.revoke_personal_access_token(&root, first_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let automation = app.with_bearer_token(&root, first["token"].as_str().unwrap());
+    let response = app.get_teams(&automation).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.get_personal_access_tokens(&root).await;
+    let tokens = response.json::<Vec<Value>>().await.expect("Failed to parse personal access tokens");
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0]["id"], second["id"]);
+
+    // revoking an already revoked token is not possible
+    let response = app.revoke_personal_access_token(&root, first_id).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+}
+
+#[sqlx::test]
+async fn test_personal_access_token_of_other_user_cannot_be_revoked(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let user = app.create_test_user().await.login().await;
+
+    let created = create_token(&app, &root, json!(["read"])).await;
+    let token_id = Uuid::parse_str(created["id"].as_str().unwrap()).unwrap();
+
+    let response = app.revoke_personal_access_token(&user, token_id).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+}
+
+#[sqlx::test]
+async fn test_personal_access_token_cannot_be_used_for_session_endpoints(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let created = create_token(&app, &root, json!(["read", "write"])).await;
+    let automation = app.with_bearer_token(&root, created["token"].as_str().unwrap());
+
+    let response = app.get_sessions(&automation).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    // a leaked token must not be able to create tokens which outlive its revocation
+    let response = app.create_personal_access_token(&automation, json!({
+        "name": "ci",
+        "scopes": ["read"],
+    })).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+}
+
+#[sqlx::test]
+async fn test_malformed_personal_access_tokens_are_rejected(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let created = create_token(&app, &root, json!(["read"])).await;
+    let token = created["token"].as_str().unwrap();
+
+    let forged_tokens = [
+        "pat_".to_string(),
+        format!("pat_{}_secret", Uuid::new_v4().simple()),
+        format!("{}x", token),
+    ];
+    for forged_token in forged_tokens {
+        let automation = app.with_bearer_token(&root, &forged_token);
+        let response = app.get_teams(&automation).await;
+        assert_status_eq(&response, StatusCode::UNAUTHORIZED, Some(forged_token));
+    }
+}
+
+#[sqlx::test]
+async fn test_create_personal_access_token_validates_request(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+
+    let invalid_requests = [
+        json!({ "name": " ", "scopes": ["read"] }),
+        json!({ "name": "ci", "scopes": [] }),
+        json!({ "name": "ci", "scopes": ["read"], "expires_in_days": 0 }),
+        json!({ "name": "ci", "scopes": ["read"], "expires_in_days": 3651 }),
+        json!({ "name": "ci", "scopes": ["read"], "expires_in_days": u32::MAX }),
+    ];
+    for request in invalid_requests {
+        let response = app.create_personal_access_token(&root, request.clone()).await;
+        assert_status_eq(&response, StatusCode::UNPROCESSABLE_ENTITY, Some(request.to_string()));
+    }
+
+    let response = app.create_personal_access_token(&root, json!({
+        "name": "ci",
+        "scopes": ["read"],
+        "expires_in_days": 30,
+    })).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+    let created = response.json::<Value>().await.expect("Failed to parse created personal access token");
+    assert_ne!(created["expires_at"], Value::Null);
+}
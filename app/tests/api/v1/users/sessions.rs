@@ -0,0 +1,216 @@
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::util::spawn_app::{assert_status_eq, spawn_app, spawn_app_with_configuration};
+
+const CHROME_ON_LINUX: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/51.0.2704.103 Safari/537.36";
+const SAFARI_ON_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 13_5_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/13.1.1 Mobile/15E148 Safari/604.1";
+
+fn device_headers(user_agent: &str, forwarded_for: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_str(user_agent).unwrap());
+    headers.insert("x-forwarded-for", HeaderValue::from_str(forwarded_for).unwrap());
+    headers
+}
+
+#[sqlx::test]
+async fn test_get_sessions_returns_every_active_session(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let first_device = user.clone().login().await;
+    let second_device = user.login().await;
+
+    let sessions = first_device.get_sessions().await;
+    assert_eq!(sessions.len(), 2);
+
+    let current_sessions: Vec<_> = sessions.iter().filter(|s| s.current).collect();
+    assert_eq!(current_sessions.len(), 1);
+
+    let current_session_id = first_device.current_user().await.session_id;
+    assert_eq!(current_sessions[0].id, current_session_id);
+
+    for session in sessions {
+        assert!(session.created_at <= session.last_refreshed_at);
+    }
+
+    // sessions ended by logging out should no longer be listed
+    let response = app.logout(&second_device).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let sessions = first_device.get_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, current_session_id);
+}
+
+#[sqlx::test]
+async fn test_end_session_ends_session_of_other_device(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let first_device = user.clone().login().await;
+    let second_device = user.login().await;
+
+    let second_session_id = second_device.current_user().await.session_id;
+    let response = app.end_session(&first_device, second_session_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // the ended session can no longer be refreshed
+    let response = app.refresh(&second_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let sessions = first_device.get_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_ne!(sessions[0].id, second_session_id);
+
+    // ending an already ended session is not possible
+    let response = app.end_session(&first_device, second_session_id).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+}
+
+#[sqlx::test]
+async fn test_end_session_cannot_end_session_of_other_user(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await.login().await;
+    let other_user = app.create_test_user().await.login().await;
+
+    let other_session_id = other_user.current_user().await.session_id;
+    let response = app.end_session(&user, other_session_id).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    let response = app.end_session(&user, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    // session of the other user should still be active
+    let response = app.refresh(&other_user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_logout_everywhere_ends_every_session_of_user(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await;
+    let first_device = user.clone().login().await;
+    let second_device = user.login().await;
+    let other_user = app.create_test_user().await.login().await;
+
+    let response = app.logout_everywhere(&first_device).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.refresh(&first_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&second_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // sessions of other users should not be affected
+    let response = app.refresh(&other_user).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_admin_can_end_every_session_of_user(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let admin = root.create_admin().await;
+    let user = root.create_user().await;
+    let second_device = app
+        .test_user_from(user.user_id, user.username.clone(), user.password.clone())
+        .login()
+        .await;
+
+    let response = app.end_user_sessions(&admin, user.user_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.refresh(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    let response = app.refresh(&second_device).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+
+    // the sessions of the admin should still be active
+    let response = app.refresh(&admin).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+}
+
+#[sqlx::test]
+async fn test_only_admin_and_root_can_end_sessions_of_others(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let admin = root.create_admin().await;
+    let other_root = root.create_root().await;
+    let user = root.create_user().await;
+    let other_user = root.create_user().await;
+
+    let response = app.end_user_sessions(&user, other_user.user_id).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.end_user_sessions(&admin, other_root.user_id).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+
+    let response = app.refresh(&other_root).await;
+    assert_status_eq(&response, StatusCode::CREATED, None);
+
+    let response = app.end_user_sessions(&root, other_root.user_id).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    let response = app.refresh(&other_root).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_ending_sessions_of_unknown_user_is_not_found(db: PgPool) {
+    let app = spawn_app(db).await;
+    let root = app.get_root_user().await;
+    let admin = root.create_admin().await;
+    let user = root.create_user().await;
+
+    let response = app.end_user_sessions(&admin, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    let response = app.end_user_sessions(&root, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::NOT_FOUND, None);
+
+    // users without a system role are still forbidden, regardless of whether the user exists
+    let response = app.end_user_sessions(&user, Uuid::new_v4()).await;
+    assert_status_eq(&response, StatusCode::FORBIDDEN, None);
+}
+
+#[sqlx::test]
+async fn test_sessions_show_device_of_last_login_or_refresh(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.application.trusted_proxies = vec!["127.0.0.1".parse().unwrap()];
+    }).await;
+    let user = app.create_test_user().await
+        .login_with_headers(device_headers(CHROME_ON_LINUX, "10.0.0.5"))
+        .await;
+
+    let sessions = user.get_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].device, "Chrome on Linux from 10.0.0.5");
+    assert_eq!(sessions[0].browser.as_deref(), Some("Chrome"));
+    assert_eq!(sessions[0].os.as_deref(), Some("Linux"));
+    assert_eq!(sessions[0].device_class, "desktop");
+    assert_eq!(sessions[0].ip_address.as_deref(), Some("10.0.0.5"));
+
+    // the client may pretend to be forwarded, only the address appended by the trusted proxy is used
+    let user = user.refresh_with_headers(device_headers(SAFARI_ON_IPHONE, "6.6.6.6, 10.0.0.6")).await;
+
+    let sessions = user.get_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].device, "Safari on iOS from 10.0.0.6");
+    assert_eq!(sessions[0].device_class, "mobile");
+}
+
+#[sqlx::test]
+async fn test_forwarded_for_is_ignored_without_trusted_proxies(db: PgPool) {
+    let app = spawn_app(db).await;
+    let user = app.create_test_user().await
+        .login_with_headers(device_headers(CHROME_ON_LINUX, "10.0.0.5"))
+        .await;
+
+    let sessions = user.get_sessions().await;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].device, "Chrome on Linux from 127.0.0.1");
+    assert_eq!(sessions[0].ip_address.as_deref(), Some("127.0.0.1"));
+}
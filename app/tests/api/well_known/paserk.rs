@@ -0,0 +1,104 @@
+use pasetors::keys::AsymmetricPublicKey;
+use pasetors::token::UntrustedToken;
+use pasetors::version4::{PublicToken, V4};
+use pasetors::Public;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use app::configuration::application::TokenFormat;
+
+use crate::util::spawn_app::{assert_status_eq, random_signing_key, spawn_app, spawn_app_with_configuration};
+
+#[derive(Deserialize)]
+struct ExpectedPaserkResponse {
+    keys: Vec<ExpectedPaserkKey>,
+}
+
+#[derive(Deserialize)]
+struct ExpectedPaserkKey {
+    kid: String,
+    key: String,
+}
+
+#[sqlx::test]
+async fn test_public_tokens_can_be_verified_with_published_key(db: PgPool) {
+    let app = spawn_app_with_configuration(db, |config| {
+        config.application.token_format = TokenFormat::PasetoPublic;
+    }).await;
+
+    let response = app.get_paserk().await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let paserk = response.json::<ExpectedPaserkResponse>().await
+        .expect("Failed to parse ExpectedPaserkResponse");
+
+    assert_eq!(paserk.keys.len(), 1);
+    let published_key = &paserk.keys[0];
+    assert!(published_key.kid.starts_with("k4.pid."));
+    assert!(published_key.key.starts_with("k4.public."));
+
+    let user = app.create_test_user().await.login().await;
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // the access token can be verified offline with the published key, found by the kid in its footer
+    let access_token = UntrustedToken::<Public, V4>::try_from(user.state.access_token.token.as_str())
+        .expect("Access token should be a v4.public token");
+    let footer = String::from_utf8(access_token.untrusted_footer().to_vec()).unwrap();
+    assert!(footer.contains(&published_key.kid));
+
+    let public_key = AsymmetricPublicKey::<V4>::try_from(published_key.key.as_str())
+        .expect("Failed to parse published key");
+    PublicToken::verify(&public_key, &access_token, None, None)
+        .expect("Failed to verify access token with published key");
+
+    let refreshed_user = user.refresh().await;
+    assert_eq!(refreshed_user.current_user().await.user_id, user.user_id);
+}
+
+#[sqlx::test]
+async fn test_tokens_of_retired_signing_key_remain_valid_while_published(db: PgPool) {
+    let old_signing_key = random_signing_key();
+    let old_app = spawn_app_with_configuration(db.clone(), |config| {
+        config.application.token_format = TokenFormat::PasetoPublic;
+        config.application.signing_key = Some(old_signing_key.clone());
+    }).await;
+    let old_public_key = old_app.get_paserk().await
+        .json::<ExpectedPaserkResponse>().await
+        .expect("Failed to parse ExpectedPaserkResponse")
+        .keys.remove(0).key;
+
+    let user = old_app.create_test_user().await.login().await;
+
+    let app = spawn_app_with_configuration(db.clone(), |config| {
+        config.application.token_format = TokenFormat::PasetoPublic;
+        config.application.verification_keys = vec![old_public_key.clone()];
+    }).await;
+
+    let paserk = app.get_paserk().await
+        .json::<ExpectedPaserkResponse>().await
+        .expect("Failed to parse ExpectedPaserkResponse");
+    assert_eq!(paserk.keys.len(), 2);
+    assert!(paserk.keys.iter().any(|key| key.key == old_public_key));
+
+    let response = app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::OK, None);
+
+    // without the old public key, the tokens signed by it are rejected
+    let other_app = spawn_app_with_configuration(db, |config| {
+        config.application.token_format = TokenFormat::PasetoPublic;
+    }).await;
+    let response = other_app.current_user(&user).await;
+    assert_status_eq(&response, StatusCode::UNAUTHORIZED, None);
+}
+
+#[sqlx::test]
+async fn test_no_keys_are_published_for_local_tokens(db: PgPool) {
+    let app = spawn_app(db).await;
+
+    let response = app.get_paserk().await;
+    assert_status_eq(&response, StatusCode::OK, None);
+    let paserk = response.json::<ExpectedPaserkResponse>().await
+        .expect("Failed to parse ExpectedPaserkResponse");
+    assert!(paserk.keys.is_empty());
+}
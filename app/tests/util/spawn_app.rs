@@ -1,21 +1,28 @@
+use std::net::SocketAddr;
 use std::path::Path;
 
 use once_cell::sync::Lazy;
-use pasetors::keys::{Generate, SymmetricKey};
+use pasetors::keys::{AsymmetricKeyPair, Generate};
+use pasetors::paserk::FormatAsPaserk;
 use pasetors::version4::V4;
+use secrecy::Secret;
 use reqwest::{Response, StatusCode};
 use sqlx::PgPool;
+use uuid::Uuid;
 use tracing::Subscriber;
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_subscriber::{EnvFilter, Registry};
 use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
 
-use app::app_state::AppState;
-use app::configuration::configuration::get_configuration;
+use app::app_state::{new_active_session_cache, new_dummy_password_hash, new_ended_session_cache, new_totp_secret_encryptor, AppState};
+use app::configuration::application::TokenFormat;
+use app::configuration::configuration::{get_configuration, Configuration};
+use app::configuration::email_client::EmailOutput;
 use app::queries::database::Database;
 use app::routes::router;
-use app::startup::create_root_user;
+use app::session_token_encryptor::SessionTokenEncryptor;
+use app::startup::{configure_password_hashing, create_root_user};
 use app::telemetry::init_subscriber;
 use test_utility::random::_common::random_salt;
 
@@ -59,6 +66,11 @@ where
 }
 
 pub async fn spawn_app(db: PgPool) -> TestApp {
+    spawn_app_with_configuration(db, |_| {}).await
+}
+
+/// Spawns the app like spawn_app, but allows the configuration to be adjusted before the app starts.
+pub async fn spawn_app_with_configuration(db: PgPool, configure: impl FnOnce(&mut Configuration)) -> TestApp {
     // Start logging
     Lazy::force(&TRACING);
 
@@ -79,6 +91,25 @@ pub async fn spawn_app(db: PgPool) -> TestApp {
         // By setting application port to 0, the http server will
         // serve on a random port
         config.application.port = 0;
+        config.application.encryption_key = Secret::new(Uuid::new_v4().simple().to_string());
+
+        // Every test app writes its emails to its own file, from which tests can read them
+        let email_file = std::env::temp_dir().join(format!("test_emails_{}.jsonl", Uuid::new_v4().simple()));
+        config.email_client.output = EmailOutput::File(email_file);
+
+        // Tests send many requests in a short time from the same address, tests of the rate limits enable them
+        config.rate_limit.enabled = false;
+        configure(&mut config);
+
+        let signs_asymmetrically = matches!(config.application.token_format, TokenFormat::PasetoPublic | TokenFormat::JwtEddsa);
+        if signs_asymmetrically && config.application.signing_key.is_none() {
+            config.application.signing_key = Some(random_signing_key());
+        }
+
+        if config.application.token_format == TokenFormat::JwtHs256 && config.application.jwt_secret.is_none() {
+            config.application.jwt_secret = Some(random_jwt_secret());
+        }
+
         config
     };
 
@@ -89,25 +120,70 @@ pub async fn spawn_app(db: PgPool) -> TestApp {
     
     let app_port = listener.local_addr().unwrap().port();
 
+    configure_password_hashing(&configuration.password_hashing)
+        .expect("Failed to configure password hashing");
+
     create_root_user(&Database(db.clone()), &configuration, &random_salt())
         .await
         .expect("Failed to create root user");
     
     let app_db = db.clone();
+    let session_concurrency = configuration.sessions.concurrency;
+    let session_policy = configuration.sessions.policy()
+        .expect("Failed to create session policy");
+    let ended_sessions = new_ended_session_cache(configuration.sessions.revocation_check, &session_policy);
+    let active_sessions = new_active_session_cache(
+        configuration.sessions.revocation_check,
+        configuration.sessions.active_session_cache_seconds
+    );
+    let expired_access_token_grace_period = configuration.sessions.expired_access_token_grace_period()
+        .expect("Failed to get expired access token grace period");
+    let trusted_proxies = configuration.application.trusted_proxies.clone();
+    let token_encryptor = SessionTokenEncryptor::new(&configuration.application, &session_policy)
+        .expect("Failed to create token encryptor");
+    let app_token_encryptor = token_encryptor.clone();
+    let totp_secret_encryptor = new_totp_secret_encryptor(&configuration.application)
+        .expect("Failed to create totp secret encryptor");
+    let email_client = configuration.email_client.client();
+    let password_reset = configuration.password_reset.clone();
+    let password_policy = configuration.password_policy.policy()
+        .expect("Failed to create password policy");
+    let lockout_policy = configuration.login_lockout.policy()
+        .expect("Failed to create lockout policy");
+    let failed_logins = configuration.login_lockout.store(&Database(db.clone()));
+    let rate_limiters = configuration.rate_limit.rate_limiters()
+        .expect("Failed to create rate limiters");
+    let dummy_password_hash = new_dummy_password_hash()
+        .expect("Failed to create dummy password hash");
     let _server = AbortOnDrop(tokio::spawn(async move {
         let app = router(
             // AppState::try_from(app_config).expect("Failed to build AppState")
             AppState {
                 db: Database(app_db),
-                encryption_key: SymmetricKey::<V4>::generate()
-                    .expect("Failed to random encryption key")
+                token_encryptor: app_token_encryptor,
+                session_concurrency,
+                ended_sessions,
+                active_sessions,
+                expired_access_token_grace_period,
+                session_policy,
+                trusted_proxies,
+                totp_secret_encryptor,
+                email_client,
+                password_reset,
+                password_policy,
+                lockout_policy,
+                failed_logins,
+                rate_limiters,
+                dummy_password_hash,
             }
         );
         
         let listener = tokio::net::TcpListener::from_std(listener)
             .expect("Failed to get tcp listener from tokio");
 
-        axum::serve(listener, app).await.expect("Failed to serve axum server");
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .await
+            .expect("Failed to serve axum server");
     }));
 
     let address = format!("http://localhost:{}", app_port);
@@ -119,6 +195,7 @@ pub async fn spawn_app(db: PgPool) -> TestApp {
             app_address: address.clone()
         },
         configuration,
+        token_encryptor,
 
         // server must be saved in order for the task that starts,
         // the http server (axum) to keep running, or its lifetime gets dropped.
@@ -127,6 +204,19 @@ pub async fn spawn_app(db: PgPool) -> TestApp {
     );
 }
 
+/// Returns a new random signing key formatted as PASERK.
+pub fn random_jwt_secret() -> Secret<String> {
+    Secret::new(format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()))
+}
+
+pub fn random_signing_key() -> Secret<String> {
+    let key_pair = AsymmetricKeyPair::<V4>::generate().expect("Failed to generate signing key");
+
+    let mut signing_key = String::new();
+    key_pair.secret.fmt(&mut signing_key).expect("Failed to format signing key as PASERK");
+    Secret::new(signing_key)
+}
+
 pub fn assert_status_eq(
     response: &Response,
     status_code: StatusCode,
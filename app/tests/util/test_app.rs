@@ -1,3 +1,4 @@
+use chrono::{Duration, Utc};
 use password_hash::SaltString;
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
 use reqwest::Response;
@@ -7,6 +8,13 @@ use serde_json::{json, Value};
 use sqlx::PgPool;
 use uuid::Uuid;
 use app::configuration::configuration::Configuration;
+use app::configuration::email_client::EmailOutput;
+use domain::sessions::tokens::{AccessToken, RefreshToken};
+use domain::sessions::user_session_token::UserSessionToken;
+use app::session_token_encryptor::SessionTokenEncryptor;
+use security::encryption::decryptor::Decryptor;
+use security::encryption::encryptor::Encryptor;
+use security::token::token::Token;
 use security::hash::scheme::{get_latest_scheme, Scheme};
 use crate::util::api_client::ApiClient;
 use crate::util::test_user::anonymous::Anonymous;
@@ -29,9 +37,18 @@ pub struct TestApp {
     pg_pool: PgPool,
     api_client: ApiClient,
     configuration: Configuration,
+    token_encryptor: SessionTokenEncryptor,
     _server: AbortOnDrop,
 }
 
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        if let EmailOutput::File(path) = &self.configuration.email_client.output {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
 impl TestApp {
 
     pub fn new(
@@ -40,6 +57,7 @@ impl TestApp {
         pg_pool: PgPool,
         api_client: ApiClient,
         configuration: Configuration,
+        token_encryptor: SessionTokenEncryptor,
         _server: AbortOnDrop
     ) -> Self {
         Self {
@@ -48,6 +66,7 @@ impl TestApp {
             pg_pool,
             api_client,
             configuration,
+            token_encryptor,
             _server
         }
     }
@@ -110,6 +129,252 @@ impl TestApp {
         }
     }
     
+    /// Returns a copy of the user, of which the access token has expired the given duration ago.
+    pub fn with_expired_access_token<'a>(&self, user: &TestUser<'a, LoggedIn>, expired_for: Duration) -> TestUser<'a, LoggedIn> {
+        self.with_modified_access_token(user, |access_token| {
+            let expiration = Utc::now() - expired_for;
+            UserSessionToken::<AccessToken>::new(
+                Uuid::new_v4(),
+                access_token.get_subject().to_string(),
+                access_token.get_audience().to_string(),
+                access_token.get_issuer().to_string(),
+                expiration,
+                expiration - Duration::minutes(5),
+                expiration - Duration::minutes(5),
+                access_token.get_custom_claims().clone(),
+            )
+        })
+    }
+
+    /// Returns a copy of the user, of which the access token has the given issuer and audience.
+    pub fn with_access_token_issued_for<'a>(&self, user: &TestUser<'a, LoggedIn>, issuer: &str, audience: &str) -> TestUser<'a, LoggedIn> {
+        self.with_modified_access_token(user, |access_token| {
+            UserSessionToken::<AccessToken>::new(
+                Uuid::new_v4(),
+                access_token.get_subject().to_string(),
+                audience.to_string(),
+                issuer.to_string(),
+                *access_token.get_expiration(),
+                *access_token.get_not_before(),
+                *access_token.get_issued_at(),
+                access_token.get_custom_claims().clone(),
+            )
+        })
+    }
+
+    /// Returns a copy of the user with tokens like those issued before the audience became configurable,
+    /// which had the user id as audience. Their refresh token is saved with the backfilled audience of
+    /// the migration as well.
+    pub async fn with_legacy_tokens<'a>(&self, user: &TestUser<'a, LoggedIn>) -> TestUser<'a, LoggedIn> {
+        let legacy_audience = user.user_id.to_string();
+        let mut user = self.with_modified_access_token(user, |access_token| {
+            UserSessionToken::<AccessToken>::new(
+                *access_token.get_id(),
+                access_token.get_subject().to_string(),
+                legacy_audience.clone(),
+                access_token.get_issuer().to_string(),
+                *access_token.get_expiration(),
+                *access_token.get_not_before(),
+                *access_token.get_issued_at(),
+                access_token.get_custom_claims().clone(),
+            )
+        });
+
+        let refresh_token: UserSessionToken<RefreshToken> = self.token_encryptor
+            .decrypt(&Secret::new(user.state.refresh_token.token.clone()))
+            .expect("Failed to decrypt refresh token of user");
+        let legacy_refresh_token = UserSessionToken::<RefreshToken>::new(
+            *refresh_token.get_id(),
+            refresh_token.get_subject().to_string(),
+            legacy_audience.clone(),
+            refresh_token.get_issuer().to_string(),
+            *refresh_token.get_expiration(),
+            *refresh_token.get_not_before(),
+            *refresh_token.get_issued_at(),
+            refresh_token.get_custom_claims().clone(),
+        );
+        user.state.refresh_token.token = self.token_encryptor
+            .encrypt(&legacy_refresh_token)
+            .expect("Failed to encrypt legacy refresh token")
+            .token
+            .expose_secret()
+            .clone();
+
+        sqlx::query!(
+            "UPDATE refresh_tokens SET audience = $2 WHERE id = $1",
+            refresh_token.get_id(),
+            legacy_audience,
+        )
+            .execute(&self.pg_pool)
+            .await
+            .expect("Failed to save legacy audience of refresh token");
+
+        user
+    }
+
+    /// Returns a copy of the user, which authenticates with the given bearer token instead of its access token,
+    /// e.g. a personal access token or the access token of a service account.
+    pub fn with_bearer_token<'a>(&self, user: &TestUser<'a, LoggedIn>, token: &str) -> TestUser<'a, LoggedIn> {
+        let mut user = user.clone();
+        user.state.access_token.token = token.to_string();
+        user
+    }
+
+    fn with_modified_access_token<'a>(
+        &self,
+        user: &TestUser<'a, LoggedIn>,
+        modify: impl FnOnce(UserSessionToken<AccessToken>) -> UserSessionToken<AccessToken>
+    ) -> TestUser<'a, LoggedIn> {
+        let access_token: UserSessionToken<AccessToken> = self.token_encryptor
+            .decrypt(&Secret::new(user.state.access_token.token.clone()))
+            .expect("Failed to decrypt access token of user");
+
+        let modified_access_token = modify(access_token);
+        let encrypted_access_token = self.token_encryptor
+            .encrypt(&modified_access_token)
+            .expect("Failed to encrypt modified access token");
+
+        let mut user = user.clone();
+        user.state.access_token.token = encrypted_access_token.token.expose_secret().clone();
+        user
+    }
+
+    pub async fn get_security_event_kinds(&self, user_id: Uuid) -> Vec<String> {
+        sqlx::query!(
+            r#"
+            SELECT kind FROM security_events
+            WHERE user_id = $1
+            ORDER BY occurred_at
+            "#,
+            user_id,
+        )
+            .fetch_all(&self.pg_pool)
+            .await
+            .expect("Failed to get security events of user")
+            .into_iter()
+            .map(|row| row.kind)
+            .collect()
+    }
+
+    pub async fn get_encrypted_totp_secret(&self, user_id: Uuid) -> Option<String> {
+        sqlx::query!(
+            r#"
+            SELECT encrypted_secret FROM totp_credentials
+            WHERE user_id = $1
+            "#,
+            user_id,
+        )
+            .fetch_optional(&self.pg_pool)
+            .await
+            .expect("Failed to get TOTP credential of user")
+            .map(|row| row.encrypted_secret)
+    }
+
+    pub async fn get_session_ending_reasons(&self, user_id: Uuid) -> Vec<Option<String>> {
+        sqlx::query!(
+            r#"
+            SELECT ending_reason FROM user_sessions
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+            user_id,
+        )
+            .fetch_all(&self.pg_pool)
+            .await
+            .expect("Failed to get sessions of user")
+            .into_iter()
+            .map(|row| row.ending_reason)
+            .collect()
+    }
+
+    pub async fn count_refresh_tokens(&self, user_id: Uuid) -> i64 {
+        sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM refresh_tokens
+            JOIN user_sessions ON user_sessions.id = refresh_tokens.session_id
+            WHERE user_sessions.user_id = $1
+            "#,
+            user_id,
+        )
+            .fetch_one(&self.pg_pool)
+            .await
+            .expect("Failed to count refresh tokens of user")
+            .count
+    }
+
+    pub async fn get_password_hash(&self, user_id: Uuid) -> String {
+        sqlx::query!(
+            r#"
+            SELECT password_hash AS "password_hash!" FROM users
+            WHERE user_id = $1
+            "#,
+            user_id,
+        )
+            .fetch_one(&self.pg_pool)
+            .await
+            .expect("Failed to get password hash of user")
+            .password_hash
+    }
+
+    pub async fn set_password_hash(&self, user_id: Uuid, password_hash: &str) {
+        sqlx::query!(
+            r#"
+            UPDATE users SET password_hash = $2
+            WHERE user_id = $1
+            "#,
+            user_id,
+            password_hash,
+        )
+            .execute(&self.pg_pool)
+            .await
+            .expect("Failed to set password hash of user");
+    }
+
+    pub async fn set_email(&self, user_id: Uuid, email: &str) {
+        sqlx::query!(
+            r#"
+            UPDATE users SET email = $2
+            WHERE user_id = $1
+            "#,
+            user_id,
+            email,
+        )
+            .execute(&self.pg_pool)
+            .await
+            .expect("Failed to set email of user");
+    }
+
+    /// Returns the emails sent by the app so far, which are written to a file during tests.
+    pub fn get_sent_emails(&self) -> Vec<Value> {
+        let EmailOutput::File(path) = &self.configuration.email_client.output else {
+            panic!("Emails of the test app should be written to a file")
+        };
+
+        match std::fs::read_to_string(path) {
+            Ok(emails) => emails
+                .lines()
+                .map(|line| serde_json::from_str(line).expect("Failed to parse sent email"))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => panic!("Failed to read sent emails: {}", e),
+        }
+    }
+
+    /// Waits until the app has sent the given amount of emails, as some emails are sent
+    /// in the background after responding. Returns the emails sent so far.
+    pub async fn wait_for_sent_emails(&self, count: usize) -> Vec<Value> {
+        for _ in 0..50 {
+            let emails = self.get_sent_emails();
+            if emails.len() >= count {
+                return emails
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        panic!("Expected {} emails to be sent", count)
+    }
+
     pub fn test_user_from(&self, id: Uuid, username: String, password: String) -> TestUser<Anonymous> {
         TestUser {
             user_id: id,
@@ -126,8 +391,13 @@ impl TestApp {
 impl TestApp {
 
     pub async fn login(&self, user: &TestUser<'_, Anonymous>) -> Response {
+        self.login_with_headers(user, HeaderMap::new()).await
+    }
+
+    pub async fn login_with_headers(&self, user: &TestUser<'_, Anonymous>, headers: HeaderMap) -> Response {
         self.api_client
             .post("/v1/auth/login")
+            .headers(headers)
             .json(&json!({
                 "username": user.username,
                 "password": user.password
@@ -137,6 +407,30 @@ impl TestApp {
             .expect("Failed to send logic requests")
     }
 
+    pub async fn login_totp(&self, mfa_token: &str, code: &str) -> Response {
+        self.api_client
+            .post("/v1/auth/login/totp")
+            .json(&json!({
+                "mfa_token": mfa_token,
+                "code": code
+            }))
+            .send()
+            .await
+            .expect("Failed to send login_totp request")
+    }
+
+    pub async fn login_recovery_code(&self, mfa_token: &str, recovery_code: &str) -> Response {
+        self.api_client
+            .post("/v1/auth/login/recovery_code")
+            .json(&json!({
+                "mfa_token": mfa_token,
+                "recovery_code": recovery_code
+            }))
+            .send()
+            .await
+            .expect("Failed to send login_recovery_code request")
+    }
+
     pub async fn current_user(&self, user: &TestUser<'_, LoggedIn>) -> Response {
         self.api_client
             .get("/v1/user/current")
@@ -147,8 +441,13 @@ impl TestApp {
     }
 
     pub async fn refresh(&self, user: &TestUser<'_, LoggedIn>) -> Response {
+        self.refresh_with_headers(user, HeaderMap::new()).await
+    }
+
+    pub async fn refresh_with_headers(&self, user: &TestUser<'_, LoggedIn>, headers: HeaderMap) -> Response {
         self.api_client
             .post("/v1/auth/refresh")
+            .headers(headers)
             .json(&json!({
                 "refresh_token": user.state.refresh_token.token
             }))
@@ -166,6 +465,15 @@ impl TestApp {
             .expect("Failed to send logout request")
     }
 
+    pub async fn logout_everywhere(&self, user: &TestUser<'_, LoggedIn>) -> Response {
+        self.api_client
+            .post("/v1/auth/logout/everywhere")
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send logout_everywhere request")
+    }
+
     fn auth_header<T: UserState + Clone>(&self, user: &TestUser<T>) -> HeaderMap {
         match user.state.access_token() {
             None => HeaderMap::new(),
@@ -238,6 +546,172 @@ impl TestApp {
             .await
             .expect("Failed to send get_user_details request")
     }
+
+    pub async fn get_sessions(&self, user: &TestUser<'_, LoggedIn>) -> Response {
+        self.api_client
+            .get("/v1/users/me/sessions")
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send get_sessions request")
+    }
+
+    pub async fn end_session(&self, user: &TestUser<'_, LoggedIn>, session_id: Uuid) -> Response {
+        self.api_client
+            .delete(format!("/v1/users/me/sessions/{}", session_id).as_str())
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send end_session request")
+    }
+
+    pub async fn enrol_totp(&self, user: &TestUser<'_, LoggedIn>) -> Response {
+        self.api_client
+            .post("/v1/users/me/totp")
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send enrol_totp request")
+    }
+
+    pub async fn confirm_totp(&self, user: &TestUser<'_, LoggedIn>, code: &str) -> Response {
+        self.api_client
+            .post("/v1/users/me/totp/confirm")
+            .headers(self.auth_header(user))
+            .json(&json!({
+                "code": code
+            }))
+            .send()
+            .await
+            .expect("Failed to send confirm_totp request")
+    }
+
+    pub async fn regenerate_recovery_codes(&self, user: &TestUser<'_, LoggedIn>) -> Response {
+        self.api_client
+            .post("/v1/users/me/totp/recovery_codes")
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send regenerate_recovery_codes request")
+    }
+
+    pub async fn change_password(&self, user: &TestUser<'_, LoggedIn>, body: Value) -> Response {
+        self.api_client
+            .put("/v1/users/me/password")
+            .headers(self.auth_header(user))
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to send change_password request")
+    }
+
+    pub async fn forgot_password(&self, username: &str) -> Response {
+        self.api_client
+            .post("/v1/auth/forgot_password")
+            .json(&json!({ "username": username }))
+            .send()
+            .await
+            .expect("Failed to send forgot_password request")
+    }
+
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Response {
+        self.api_client
+            .post("/v1/auth/reset_password")
+            .json(&json!({
+                "token": token,
+                "new_password": new_password,
+            }))
+            .send()
+            .await
+            .expect("Failed to send reset_password request")
+    }
+
+    pub async fn get_paserk(&self) -> Response {
+        self.api_client
+            .get("/.well-known/paserk")
+            .send()
+            .await
+            .expect("Failed to send get_paserk request")
+    }
+
+    pub async fn get_token_lineage(&self, user: &TestUser<'_, LoggedIn>, session_id: Uuid) -> Response {
+        self.api_client
+            .get(format!("/v1/sessions/{}/tokens", session_id).as_str())
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send get_token_lineage request")
+    }
+
+    pub async fn end_user_sessions(&self, user: &TestUser<'_, LoggedIn>, user_id: Uuid) -> Response {
+        self.api_client
+            .delete(format!("/v1/users/{}/sessions", user_id).as_str())
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send end_user_sessions request")
+    }
+
+    pub async fn unlock_user(&self, user: &TestUser<'_, LoggedIn>, user_id: Uuid) -> Response {
+        self.api_client
+            .delete(format!("/v1/users/{}/lockout", user_id).as_str())
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send unlock_user request")
+    }
+
+    pub async fn create_personal_access_token(&self, user: &TestUser<'_, LoggedIn>, body: Value) -> Response {
+        self.api_client
+            .post("/v1/users/me/tokens")
+            .headers(self.auth_header(user))
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to send create_personal_access_token request")
+    }
+
+    pub async fn get_personal_access_tokens(&self, user: &TestUser<'_, LoggedIn>) -> Response {
+        self.api_client
+            .get("/v1/users/me/tokens")
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send get_personal_access_tokens request")
+    }
+
+    pub async fn revoke_personal_access_token(&self, user: &TestUser<'_, LoggedIn>, token_id: Uuid) -> Response {
+        self.api_client
+            .delete(format!("/v1/users/me/tokens/{}", token_id).as_str())
+            .headers(self.auth_header(user))
+            .send()
+            .await
+            .expect("Failed to send revoke_personal_access_token request")
+    }
+
+    pub async fn create_service_account(&self, user: &TestUser<'_, LoggedIn>, body: Value) -> Response {
+        self.api_client
+            .post("/v1/service_accounts")
+            .headers(self.auth_header(user))
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to send create_service_account request")
+    }
+
+    /// Requests an access token at the token endpoint, authenticating with HTTP Basic
+    /// authentication when client credentials are given.
+    pub async fn request_token(&self, client_credentials: Option<(&str, &str)>, form: &[(&str, &str)]) -> Response {
+        let mut request = self.api_client.post("/v1/oauth/token").form(form);
+        if let Some((client_id, client_secret)) = client_credentials {
+            request = request.basic_auth(client_id, Some(client_secret));
+        }
+
+        request
+            .send()
+            .await
+            .expect("Failed to send token request")
+    }
 }
 
 impl TestApp {
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
 use reqwest::{Response, StatusCode};
 use serde::Deserialize;
 use uuid::Uuid;
@@ -31,11 +32,11 @@ impl<'a, State: UserState + Clone> TestUser<'a, State> {
         }
     }
 
-    pub fn with_username(&self, new_password: String) -> TestUser<State> {
+    pub fn with_username(&self, new_username: String) -> TestUser<State> {
         TestUser {
             user_id: self.user_id.clone(),
-            username: self.username.clone(),
-            password: new_password,
+            username: new_username,
+            password: self.password.clone(),
             state: self.state.clone(),
             app: self.app,
         }
@@ -44,7 +45,11 @@ impl<'a, State: UserState + Clone> TestUser<'a, State> {
 
 impl <'a> TestUser<'a, Anonymous> {
     pub async fn login(self) -> TestUser<'a, LoggedIn> {
-        let login_response = self.app.login(&self)
+        self.login_with_headers(HeaderMap::new()).await
+    }
+
+    pub async fn login_with_headers(self, headers: HeaderMap) -> TestUser<'a, LoggedIn> {
+        let login_response = self.app.login_with_headers(&self, headers)
             .await
             .error_for_status()
             .expect("Failed to receive 200 status code")
@@ -90,13 +95,18 @@ pub struct ExpectedRefreshResponse {
 
 #[derive(Deserialize)]
 pub struct ExpectedCurrentUserResponse {
-    pub user_id: Uuid
+    pub user_id: Uuid,
+    pub session_id: Uuid
 }
 
 impl<'a> TestUser<'a, LoggedIn> {
 
     pub async fn refresh(&self) -> TestUser<'a, LoggedIn> {
-        let response = self.app.refresh(&self)
+        self.refresh_with_headers(HeaderMap::new()).await
+    }
+
+    pub async fn refresh_with_headers(&self, headers: HeaderMap) -> TestUser<'a, LoggedIn> {
+        let response = self.app.refresh_with_headers(self, headers)
             .await
             .json::<ExpectedRefreshResponse>()
             .await
@@ -156,6 +166,14 @@ impl<'a> TestUser<'a, LoggedIn> {
             .expect("Failed to parse get_team_members result")
     }
 
+    pub async fn get_sessions(&self) -> Vec<GetSessionResponse> {
+        self.app.get_sessions(self)
+            .await
+            .json()
+            .await
+            .expect("Failed to parse get_sessions result")
+    }
+
     pub async fn get_user_details(&self) -> GetUserDetailsResponse {
         self.get_user_details_of(self.user_id).await
     }
@@ -214,6 +232,19 @@ impl<'a> TestUser<'a, LoggedIn> {
     }
 }
 
+#[derive(Deserialize)]
+pub struct GetSessionResponse {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    pub current: bool,
+    pub device: String,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device_class: String,
+    pub ip_address: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct GetUserDetailsResponse {
     pub id: Uuid,
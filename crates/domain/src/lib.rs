@@ -2,4 +2,5 @@ pub mod sessions;
 pub mod shared;
 pub mod user;
 pub mod team;
-pub mod role;
\ No newline at end of file
+pub mod role;
+pub mod security_event;
\ No newline at end of file
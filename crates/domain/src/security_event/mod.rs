@@ -0,0 +1 @@
+pub mod security_event;
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::user::user_id::UserId;
+
+/// SecurityEvent records activity of a user which is likely to be malicious,
+/// to be able to audit it afterwards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SecurityEvent {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub session_id: Option<Uuid>,
+    pub kind: SecurityEventKind,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SecurityEventKind {
+    /// An access token was used after its expiration, beyond the configured grace period.
+    UsedExpiredAccessToken {
+        token_id: Uuid
+    },
+}
+
+impl SecurityEvent {
+    pub fn new(user_id: UserId, session_id: Option<Uuid>, kind: SecurityEventKind) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            session_id,
+            kind,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// Returns the id of the token that caused the event, if any.
+    pub fn token_id(&self) -> Option<Uuid> {
+        match self.kind {
+            SecurityEventKind::UsedExpiredAccessToken { token_id } => Some(token_id),
+        }
+    }
+}
+
+impl SecurityEventKind {
+    pub fn to_string(&self) -> &str {
+        match self {
+            SecurityEventKind::UsedExpiredAccessToken { .. } => "UsedExpiredAccessToken",
+        }
+    }
+}
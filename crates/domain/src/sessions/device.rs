@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+
+use crate::sessions::user_agent::UserAgent;
+
+/// Device describes the client of a session, as last seen when the session was created or refreshed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Device {
+    pub user_agent: Option<UserAgent>,
+    pub ip_address: Option<IpAddr>,
+}
+
+impl Device {
+    pub fn new(user_agent: Option<UserAgent>, ip_address: Option<IpAddr>) -> Self {
+        Self {
+            user_agent,
+            ip_address,
+        }
+    }
+}
+
+impl Display for Device {
+    /// Formats the device as e.g. `Chrome on Linux from 10.0.0.5`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.user_agent {
+            Some(user_agent) => write!(f, "{}", user_agent)?,
+            None => write!(f, "Unknown device")?,
+        }
+
+        match &self.ip_address {
+            Some(ip_address) => write!(f, " from {}", ip_address),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use crate::sessions::device::Device;
+    use crate::sessions::user_agent::UserAgent;
+
+    #[test]
+    fn test_display() {
+        let user_agent = UserAgent::new("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/51.0.2704.103 Safari/537.36")
+            .expect("Failed to parse user agent");
+        let ip_address = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5));
+
+        let device = Device::new(Some(user_agent), Some(ip_address));
+        assert_eq!(device.to_string(), "Chrome on Linux from 10.0.0.5");
+
+        let device = Device::new(None, Some(ip_address));
+        assert_eq!(device.to_string(), "Unknown device from 10.0.0.5");
+
+        let device = Device::default();
+        assert_eq!(device.to_string(), "Unknown device");
+    }
+}
@@ -1,5 +1,9 @@
+pub mod device;
+pub mod refresh_outcome;
+pub mod session_concurrency;
+pub mod session_policy;
 pub mod state;
 pub mod tokens;
-mod user_agent;
+pub mod user_agent;
 pub mod user_session;
 pub mod user_session_token;
@@ -0,0 +1,17 @@
+use crate::sessions::state::just_ended::JustEnded;
+use crate::sessions::state::refreshed::Refreshed;
+use crate::sessions::state::reissued::Reissued;
+use crate::sessions::user_session::UserSession;
+
+/// RefreshOutcome is the result of refreshing a session.
+pub enum RefreshOutcome {
+    /// The refresh token got rotated and new tokens were issued.
+    Refreshed(UserSession<Refreshed>),
+
+    /// The refresh token was rotated moments ago,
+    /// the tokens that were issued back then are handed out again.
+    Reissued(UserSession<Reissued>),
+
+    /// The refresh token or the session was no longer valid, the session got ended.
+    Ended(UserSession<JustEnded>),
+}
\ No newline at end of file
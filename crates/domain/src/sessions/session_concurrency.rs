@@ -0,0 +1,113 @@
+use serde::Deserialize;
+use crate::sessions::state::active::Active;
+use crate::sessions::user_session::UserSession;
+
+/// SessionConcurrency determines how many sessions a single user may have active at once.
+/// When a user signs in while already at the limit, its oldest sessions are ended.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionConcurrency {
+    /// Users can have any number of sessions.
+    #[default]
+    Unlimited,
+
+    /// Signing in ends every other session of the user.
+    Single,
+
+    /// Users can have at most the given number of sessions.
+    Max(usize)
+}
+
+impl SessionConcurrency {
+
+    /// Returns the sessions that should be ended to make room for a new session,
+    /// given the currently active sessions of a user.
+    pub fn sessions_to_end(&self, mut active_sessions: Vec<UserSession<Active>>) -> Vec<UserSession<Active>> {
+        let max_sessions = match self {
+            SessionConcurrency::Unlimited => return vec![],
+            SessionConcurrency::Single => 1,
+            // a limit of zero would prevent the user from ever signing in
+            SessionConcurrency::Max(max) => (*max).max(1)
+        };
+
+        // the new session is counted as well, hence the + 1
+        let sessions_over_limit = (active_sessions.len() + 1).saturating_sub(max_sessions);
+
+        active_sessions.sort_by_key(|s| s.created_at);
+        active_sessions.truncate(sessions_over_limit);
+        active_sessions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+    use crate::sessions::device::Device;
+    use crate::sessions::session_concurrency::SessionConcurrency;
+    use crate::sessions::session_policy::SessionPolicy;
+    use crate::sessions::state::active::Active;
+    use crate::sessions::state::newly_created::NewlyCreated;
+    use crate::sessions::user_session::UserSession;
+
+    fn active_sessions(amount: i64) -> Vec<UserSession<Active>> {
+        let user_id = Uuid::new_v4().into();
+
+        // newest session first, to ensure the sessions get sorted by age
+        (0..amount).map(|i| {
+            let session = UserSession::<NewlyCreated>::new(user_id, Device::default(), &SessionPolicy::default());
+            UserSession::<Active>::new(
+                session.id,
+                session.user_id,
+                Utc::now() - Duration::minutes(i),
+                session.device,
+                Active::new(session.state.refresh_token)
+            )
+        }).collect()
+    }
+
+    #[test]
+    fn test_unlimited_never_ends_sessions() {
+        let to_end = SessionConcurrency::Unlimited.sessions_to_end(active_sessions(10));
+        assert!(to_end.is_empty());
+    }
+
+    #[test]
+    fn test_single_ends_every_session() {
+        let to_end = SessionConcurrency::Single.sessions_to_end(active_sessions(3));
+        assert_eq!(to_end.len(), 3);
+
+        let to_end = SessionConcurrency::Single.sessions_to_end(vec![]);
+        assert!(to_end.is_empty());
+    }
+
+    #[test]
+    fn test_max_ends_oldest_sessions_over_limit() {
+        let sessions = active_sessions(4);
+        let oldest = sessions[3].id;
+        let second_oldest = sessions[2].id;
+
+        let to_end = SessionConcurrency::Max(3).sessions_to_end(sessions);
+        assert_eq!(to_end.len(), 2);
+        assert_eq!(to_end[0].id, oldest);
+        assert_eq!(to_end[1].id, second_oldest);
+
+        let to_end = SessionConcurrency::Max(5).sessions_to_end(active_sessions(4));
+        assert!(to_end.is_empty());
+
+        let to_end = SessionConcurrency::Max(0).sessions_to_end(active_sessions(4));
+        assert_eq!(to_end.len(), 4);
+    }
+
+    #[test]
+    fn test_deserialize_session_concurrency() {
+        let got: SessionConcurrency = serde_json::from_str("\"unlimited\"").unwrap();
+        assert_eq!(got, SessionConcurrency::Unlimited);
+
+        let got: SessionConcurrency = serde_json::from_str("\"single\"").unwrap();
+        assert_eq!(got, SessionConcurrency::Single);
+
+        let got: SessionConcurrency = serde_json::from_str("{\"max\": 3}").unwrap();
+        assert_eq!(got, SessionConcurrency::Max(3));
+    }
+}
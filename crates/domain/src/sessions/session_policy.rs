@@ -0,0 +1,49 @@
+use chrono::Duration;
+
+/// SessionPolicy determines the lifetimes and claims of the tokens that are issued to a session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionPolicy {
+    /// How long an access token is valid after being issued.
+    pub access_token_lifetime: Duration,
+
+    /// How long a refresh token is valid after being issued.
+    pub refresh_token_lifetime: Duration,
+
+    /// How long a session may last since it was created, regardless of how often it is refreshed.
+    pub max_session_age: Duration,
+
+    /// How long a session may go without being refreshed before it ends, if limited.
+    pub idle_timeout: Option<Duration>,
+
+    /// How long a refresh token may be used again after it was rotated, to receive the same new tokens.
+    pub refresh_token_reuse_grace_period: Duration,
+
+    /// How long a user has to enter their one-time password after entering their password.
+    pub mfa_pending_token_lifetime: Duration,
+
+    /// The issuer claim of every issued token.
+    pub issuer: String,
+
+    /// The audience claim of every issued token.
+    pub audience: String,
+
+    /// Whether received tokens may have the id of their user as audience instead, like the tokens
+    /// issued before the audience became configurable.
+    pub accept_legacy_audience: bool,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self {
+            access_token_lifetime: Duration::minutes(5),
+            refresh_token_lifetime: Duration::hours(4),
+            max_session_age: Duration::days(30),
+            idle_timeout: None,
+            refresh_token_reuse_grace_period: Duration::zero(),
+            mfa_pending_token_lifetime: Duration::minutes(5),
+            issuer: "rust_backend_setup".to_string(),
+            audience: "rust_backend_setup".to_string(),
+            accept_legacy_audience: true,
+        }
+    }
+}
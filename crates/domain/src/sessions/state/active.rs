@@ -1,9 +1,44 @@
-
+use chrono::{DateTime, Utc};
 use crate::sessions::state::state::{State};
 use crate::sessions::user_session_token::UserSessionToken;
-use crate::sessions::tokens::RefreshToken;
+use crate::sessions::tokens::{AccessToken, RefreshToken};
 
 pub struct Active {
     pub latest_refresh_token: UserSessionToken<RefreshToken>,
+
+    /// latest_access_token is the access token that got issued together with the latest refresh token.
+    /// Only known when the latest refresh token was issued by refreshing the session.
+    pub latest_access_token: Option<UserSessionToken<AccessToken>>,
+
+    /// parent_reusable_until is the time until which the parent of the latest refresh token may be
+    /// used again, to receive the latest tokens instead of ending the session for token reuse.
+    pub parent_reusable_until: Option<DateTime<Utc>>,
 }
+
+impl Active {
+    pub fn new(latest_refresh_token: UserSessionToken<RefreshToken>) -> Self {
+        Self {
+            latest_refresh_token,
+            latest_access_token: None,
+            parent_reusable_until: None,
+        }
+    }
+
+    /// Returns the latest tokens if the given refresh token is the parent of the latest refresh token,
+    /// and it is still within its reuse grace period.
+    pub(in crate::sessions) fn reusable_tokens_for(
+        &self,
+        refresh_token: &UserSessionToken<RefreshToken>
+    ) -> Option<&UserSessionToken<AccessToken>> {
+        let is_parent_of_latest = self.latest_refresh_token.custom_claims.parent_id == Some(refresh_token.id);
+        let within_grace_period = self.parent_reusable_until.is_some_and(|until| Utc::now() <= until);
+
+        if is_parent_of_latest && within_grace_period {
+            return self.latest_access_token.as_ref()
+        }
+
+        None
+    }
+}
+
 impl State for Active {}
\ No newline at end of file
@@ -4,4 +4,5 @@ pub mod already_ended;
 pub mod just_ended;
 pub mod newly_created;
 pub mod refreshed;
+pub mod reissued;
 pub mod state;
@@ -1,4 +1,5 @@
 
+use chrono::{DateTime, Utc};
 use crate::sessions::state::state::State;
 use crate::sessions::user_session_token::UserSessionToken;
 use crate::sessions::tokens::{AccessToken, RefreshToken};
@@ -7,6 +8,10 @@ pub struct Refreshed {
     pub(in crate::sessions) new_access_token: UserSessionToken<AccessToken>,
     pub(in crate::sessions) new_refresh_token: UserSessionToken<RefreshToken>,
     pub(in crate::sessions) old_refresh_token: UserSessionToken<RefreshToken>,
+    /// refreshed_at is the time the old refresh token got used to refresh the session.
+    pub(in crate::sessions) refreshed_at: DateTime<Utc>,
+    /// old_refresh_token_reusable_until is the end of the reuse grace period of the old refresh token.
+    pub(in crate::sessions) old_refresh_token_reusable_until: Option<DateTime<Utc>>,
 }
 
 impl Refreshed {
@@ -19,6 +24,12 @@ impl Refreshed {
     pub fn old_refresh_token(&self) -> &UserSessionToken<RefreshToken> {
         &self.old_refresh_token
     }
+    pub fn refreshed_at(&self) -> &DateTime<Utc> {
+        &self.refreshed_at
+    }
+    pub fn old_refresh_token_reusable_until(&self) -> Option<&DateTime<Utc>> {
+        self.old_refresh_token_reusable_until.as_ref()
+    }
 }
 
 impl State for Refreshed {}
\ No newline at end of file
@@ -0,0 +1,22 @@
+use crate::sessions::state::state::State;
+use crate::sessions::user_session_token::UserSessionToken;
+use crate::sessions::tokens::{AccessToken, RefreshToken};
+
+/// Reissued is the state of a session of which a just rotated refresh token got used again
+/// within its reuse grace period. Instead of issuing new tokens, the already issued tokens are
+/// handed out again, which happens when clients send concurrent refresh requests.
+pub struct Reissued {
+    pub(in crate::sessions) access_token: UserSessionToken<AccessToken>,
+    pub(in crate::sessions) refresh_token: UserSessionToken<RefreshToken>,
+}
+
+impl Reissued {
+    pub fn access_token(&self) -> &UserSessionToken<AccessToken> {
+        &self.access_token
+    }
+    pub fn refresh_token(&self) -> &UserSessionToken<RefreshToken> {
+        &self.refresh_token
+    }
+}
+
+impl State for Reissued {}
\ No newline at end of file
@@ -7,6 +7,21 @@ pub enum SessionEndReason {
     /// User ended the session by logging out.
     UserLogout,
 
+    /// User ended the session from another session, e.g. by revoking it from
+    /// the list of signed-in devices.
+    RevokedByUser,
+
+    /// User ended all of its sessions at once, e.g. by logging out of every device.
+    UserLoggedOutEverywhere,
+
+    /// An admin or root user forcefully ended the session, e.g. because the account
+    /// was compromised.
+    RevokedByAdmin,
+
+    /// User changed their password and chose to end all of its other sessions, e.g. because
+    /// the old password may have been known to someone else.
+    PasswordChanged,
+
     /// UserSignedInOnOtherDevice A user can have only active session at the time.
     /// If logged in onto another device, it will any other sessions.
     UserSignedInOnOtherDevice,
@@ -14,6 +29,14 @@ pub enum SessionEndReason {
     /// Expired happens when the expiration date and time of the latest refresh token has past.
     LatestRefreshTokenExpired,
 
+    /// MaxSessionAgeReached happens when the session is refreshed after it reached the maximum age,
+    /// measured since it was created, forcing the user to sign in again.
+    MaxSessionAgeReached,
+
+    /// IdleTimeoutReached happens when the session is refreshed after not being refreshed
+    /// for longer than the idle timeout.
+    IdleTimeoutReached,
+
     /// AttemptedToReuseRefreshToken happens when a previously used refresh token
     /// is attempted to be used twice. May happen if the refresh token got stolen/leaked.
     AttemptedToReuseRefreshToken {
@@ -29,8 +52,14 @@ impl SessionEndReason {
     pub fn to_string(&self) -> &str {
         match self {
             SessionEndReason::UserLogout => "UserLogout",
+            SessionEndReason::RevokedByUser => "RevokedByUser",
+            SessionEndReason::UserLoggedOutEverywhere => "UserLoggedOutEverywhere",
+            SessionEndReason::RevokedByAdmin => "RevokedByAdmin",
+            SessionEndReason::PasswordChanged => "PasswordChanged",
             SessionEndReason::UserSignedInOnOtherDevice => "UserSignedInOnOtherDevice",
             SessionEndReason::LatestRefreshTokenExpired => "LatestRefreshTokenExpired",
+            SessionEndReason::MaxSessionAgeReached => "MaxSessionAgeReached",
+            SessionEndReason::IdleTimeoutReached => "IdleTimeoutReached",
             SessionEndReason::AttemptedToReuseRefreshToken { .. } => "AttemptedToReuseRefreshToken",
             SessionEndReason::UsedExpiredAccessToken => "UsedExpiredAccessToken",
         }
@@ -1,8 +1,11 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use security::token::token::{Token};
+use security::token::token::Token;
+use crate::sessions::session_policy::SessionPolicy;
 use crate::sessions::user_session_token::UserSessionToken;
+use crate::shared::timestamp;
+use crate::user::personal_access_token::Scope;
 
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -12,22 +15,26 @@ pub struct AccessToken {
     pub refresh_token_id: Uuid,
 }
 
-impl Into<UserSessionToken<AccessToken>> for AccessToken {
-    fn into(self) -> UserSessionToken<AccessToken> {
-
-        let now = Utc::now();
+impl AccessToken {
+    /// Issues the access token according to the given policy.
+    pub fn issue(self, policy: &SessionPolicy) -> UserSessionToken<AccessToken> {
+        let now = timestamp::now();
 
         UserSessionToken::new(
             Uuid::new_v4(),
-            "access_token".to_string(),
-            self.user_id.to_string(),
-            "rust_backend_setup".to_string(),
-            now + Duration::minutes(5),
-            now.clone(),
+            AccessToken::subject().to_string(),
+            policy.audience.clone(),
+            policy.issuer.clone(),
+            now + policy.access_token_lifetime,
+            now,
             now,
             self,
         )
     }
+
+    pub fn subject() -> &'static str {
+        "access_token"
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -37,29 +44,87 @@ pub struct RefreshToken {
     pub parent_id: Option<Uuid>,
 }
 
-impl Into<UserSessionToken<RefreshToken>> for RefreshToken {
-    fn into(self) -> UserSessionToken<RefreshToken> {
-        let now = Utc::now();
+impl RefreshToken {
+    /// Issues the refresh token according to the given policy. The token never outlives the
+    /// maximum age of the session, which was created at `session_created_at`.
+    pub fn issue(self, policy: &SessionPolicy, session_created_at: DateTime<Utc>) -> UserSessionToken<RefreshToken> {
+        let now = timestamp::now();
+        let expiration = (now + policy.refresh_token_lifetime)
+            .min(session_created_at + policy.max_session_age);
 
         UserSessionToken::new(
             Uuid::new_v4(),
             RefreshToken::subject().to_string(),
-            self.user_id.to_string(),
-            RefreshToken::issuer().to_string(),
-            now + Duration::hours(4),
+            policy.audience.clone(),
+            policy.issuer.clone(),
+            expiration,
             now,
             now,
             self,
         )
     }
-}
 
-impl RefreshToken {
     pub fn subject() -> &'static str {
         "refresh_token"
     }
+}
+
+/// MfaPendingToken is issued after a user with two-factor authentication entered their password,
+/// and is exchanged for a session once the user entered a valid one-time password as well.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MfaPendingToken {
+    pub user_id: Uuid,
+}
+
+impl MfaPendingToken {
+    /// Issues the mfa pending token according to the given policy.
+    pub fn issue(self, policy: &SessionPolicy) -> UserSessionToken<MfaPendingToken> {
+        let now = timestamp::now();
+
+        UserSessionToken::new(
+            Uuid::new_v4(),
+            MfaPendingToken::subject().to_string(),
+            policy.audience.clone(),
+            policy.issuer.clone(),
+            now + policy.mfa_pending_token_lifetime,
+            now,
+            now,
+            self,
+        )
+    }
+
+    pub fn subject() -> &'static str {
+        "mfa_pending_token"
+    }
+}
+
+/// ServiceAccountAccessToken is issued to service accounts by the client credentials grant. It is not
+/// part of a session, so it cannot be refreshed, service accounts request a new one instead.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ServiceAccountAccessToken {
+    pub user_id: Uuid,
+    pub client_id: String,
+    pub scopes: Vec<Scope>,
+}
+
+impl ServiceAccountAccessToken {
+    /// Issues the access token according to the given policy, lasting as long as the access token of a session.
+    pub fn issue(self, policy: &SessionPolicy) -> UserSessionToken<ServiceAccountAccessToken> {
+        let now = timestamp::now();
+
+        UserSessionToken::new(
+            Uuid::new_v4(),
+            ServiceAccountAccessToken::subject().to_string(),
+            policy.audience.clone(),
+            policy.issuer.clone(),
+            now + policy.access_token_lifetime,
+            now,
+            now,
+            self,
+        )
+    }
 
-    pub fn issuer() -> &'static str {
-        "rust_backend_setup"
+    pub fn subject() -> &'static str {
+        "service_account_access_token"
     }
 }
@@ -1,56 +1,235 @@
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use std::sync::LazyLock;
 
+use regex::Regex;
+
+use serde::Serialize;
+
+/// UserAgent is the `User-Agent` header of a client, parsed into the browser,
+/// operating system and class of device, to be able to tell the sessions of a user apart.
 #[derive(Debug, Clone, PartialEq)]
-struct UserAgent {
+pub struct UserAgent {
     user_agent: String,
+    browser: Option<String>,
+    os: Option<String>,
+    device_class: DeviceClass,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceClass {
+    Desktop,
+    Mobile,
+    Tablet,
+    Bot,
+
+    /// Unknown clients are those that do not describe their device, e.g. command line tools.
+    Unknown,
 }
 
 #[derive(thiserror::Error, Debug, Clone, PartialEq)]
-enum Error {
+pub enum Error {
+    #[error("User agent is malformed")]
+    MalformedUserAgent,
+
     #[error("User agent is too long")]
-    MalformedUserAgent
+    UserAgentTooLong,
 }
 
-const USER_AGENT_REGEX: &str = r"\((?P<info>.*?)\)(\s|$)|(?P<name>.*?)/(?P<version>.*?)(\s|$)";
+static USER_AGENT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\((?P<info>.*?)\)(\s|$)|(?P<name>.*?)/(?P<version>.*?)(\s|$)")
+        .expect("Invalid user agent regex")
+});
+
+/// Longer user agents are rejected, as they are stored with the session.
+const MAX_USER_AGENT_LENGTH: usize = 512;
 
 impl FromStr for UserAgent {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let regex = regex::Regex::new(USER_AGENT_REGEX).expect("Invalid user agent regex");
-        let valid_user_agent = regex.is_match(s);
-        if valid_user_agent {
-            return Ok(Self {
-                user_agent: s.to_string(),
-            });
+        if s.len() > MAX_USER_AGENT_LENGTH {
+            return Err(Error::UserAgentTooLong);
         }
 
-        return Err(Error::MalformedUserAgent);
-
-        // For now just validate the user agent & create it
-        // for cap in re.captures_iter(s) {
-        //     if let Some(info) = cap.name("info") {
-        //         println!("info: {}", info.as_str());
-        //     }
-        //
-        //     if let Some(name) = cap.name("name") {
-        //         println!("name: {}", name.as_str());
-        //     }
-        //
-        //     if let Some(version) = cap.name("version") {
-        //         println!("version: {}", version.as_str());
-        //     }
-        // }
+        let valid_user_agent = USER_AGENT_REGEX.is_match(s);
+        if !valid_user_agent {
+            return Err(Error::MalformedUserAgent);
+        }
+
+        let comments = comments(s);
+        let products = products(s);
+
+        let os = parse_os(&comments);
+        let bot = parse_bot(&comments, &products);
+        let device_class = match bot {
+            Some(_) => DeviceClass::Bot,
+            None => parse_device_class(s, os),
+        };
+
+        Ok(Self {
+            user_agent: s.to_string(),
+            browser: bot.or_else(|| parse_browser(&comments, &products)).map(str::to_string),
+            os: os.map(str::to_string),
+            device_class,
+        })
     }
 }
 
 impl UserAgent {
-    fn new(user_agent: &str) -> Result<Self, Error> {
-        return UserAgent::from_str(user_agent);
+    pub fn new(user_agent: &str) -> Result<Self, Error> {
+        UserAgent::from_str(user_agent)
+    }
+
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Returns the name of the browser, or of the client if it is not a browser, e.g. `curl`.
+    pub fn browser(&self) -> Option<&str> {
+        self.browser.as_deref()
+    }
+
+    pub fn os(&self) -> Option<&str> {
+        self.os.as_deref()
     }
 
-    fn user_agent(&self) -> &str {
-        return &self.user_agent;
+    pub fn device_class(&self) -> DeviceClass {
+        self.device_class
+    }
+}
+
+impl Display for UserAgent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.browser(), self.os()) {
+            (Some(browser), Some(os)) => write!(f, "{} on {}", browser, os),
+            (Some(browser), None) => write!(f, "{}", browser),
+            (None, Some(os)) => write!(f, "Unknown browser on {}", os),
+            (None, None) => write!(f, "Unknown device"),
+        }
+    }
+}
+
+/// Returns the parts between parentheses, e.g. `Windows NT 10.0; Win64; x64`.
+fn comments(user_agent: &str) -> Vec<&str> {
+    let mut comments = vec![];
+    let mut rest = user_agent;
+    while let Some(start) = rest.find('(') {
+        let Some(end) = rest[start..].find(')') else {
+            break;
+        };
+
+        comments.push(&rest[start + 1..start + end]);
+        rest = &rest[start + end + 1..];
+    }
+
+    comments
+}
+
+/// Returns the products outside of the comments by name and version, e.g. `("Chrome", "76.0.3809.100")`.
+fn products(user_agent: &str) -> Vec<(&str, &str)> {
+    let mut products = vec![];
+    let mut depth = 0;
+    for part in user_agent.split_whitespace() {
+        if depth == 0 && !part.starts_with('(') {
+            if let Some(product) = part.split_once('/') {
+                products.push(product);
+            }
+        }
+
+        depth += part.matches('(').count();
+        depth = depth.saturating_sub(part.matches(')').count());
+    }
+
+    products
+}
+
+fn parse_bot<'a>(comments: &[&'a str], products: &[(&'a str, &str)]) -> Option<&'a str> {
+    let is_bot = |name: &str| {
+        let name = name.to_lowercase();
+        name.contains("bot") || name.contains("crawler") || name.contains("spider")
+    };
+
+    let bot_in_comments = comments.iter()
+        .flat_map(|comment| comment.split(';'))
+        .filter_map(|part| part.trim().split_once('/'))
+        .map(|(name, _)| name);
+
+    products.iter()
+        .map(|(name, _)| *name)
+        .chain(bot_in_comments)
+        .find(|name| is_bot(name))
+}
+
+fn parse_browser<'a>(comments: &[&str], products: &[(&'a str, &str)]) -> Option<&'a str> {
+    let has_product = |name: &str| products.iter().any(|(product, _)| *product == name);
+    let in_comments = |value: &str| comments.iter().any(|comment| comment.contains(value));
+
+    // the order matters, as browsers mention the browsers they are based on as well
+    if has_product("Edg") || has_product("Edge") {
+        return Some("Edge")
+    }
+    if has_product("OPR") || has_product("Opera") {
+        return Some("Opera")
+    }
+    if has_product("Firefox") {
+        return Some("Firefox")
+    }
+    if in_comments("MSIE") || in_comments("Trident") {
+        return Some("Internet Explorer")
+    }
+    if has_product("Chrome") {
+        return Some("Chrome")
+    }
+    if has_product("Safari") {
+        return Some("Safari")
+    }
+
+    products.iter()
+        .map(|(name, _)| *name)
+        .find(|name| *name != "Mozilla")
+}
+
+fn parse_os(comments: &[&str]) -> Option<&'static str> {
+    let in_comments = |value: &str| comments.iter().any(|comment| comment.contains(value));
+
+    // the order matters, e.g. Android is based on Linux and iOS is `like Mac OS X`
+    if in_comments("Windows") {
+        return Some("Windows")
+    }
+    if in_comments("Android") {
+        return Some("Android")
+    }
+    if in_comments("iPhone") || in_comments("iPad") || in_comments("iPod") {
+        return Some("iOS")
+    }
+    if in_comments("CrOS") {
+        return Some("ChromeOS")
+    }
+    if in_comments("Macintosh") || in_comments("Mac OS X") {
+        return Some("macOS")
+    }
+    if in_comments("Linux") || in_comments("X11") {
+        return Some("Linux")
+    }
+
+    None
+}
+
+fn parse_device_class(user_agent: &str, os: Option<&str>) -> DeviceClass {
+    let is_android = os == Some("Android");
+    if user_agent.contains("iPad") || user_agent.contains("Tablet") || (is_android && !user_agent.contains("Mobile")) {
+        return DeviceClass::Tablet
+    }
+
+    if user_agent.contains("Mobi") || is_android || os == Some("iOS") {
+        return DeviceClass::Mobile
+    }
+
+    match os {
+        Some(_) => DeviceClass::Desktop,
+        None => DeviceClass::Unknown,
     }
 }
 
@@ -91,17 +270,14 @@ mod tests {
         struct TestConfig {
             test_name: String,
             user_agent: String,
-            expected: Result<UserAgent, Error>,
+            expected: Result<String, Error>,
         }
 
         let mut tests = user_agents.iter().enumerate().map(|(i, user_agent)| {
-            let expected = UserAgent {
-                user_agent: user_agent.to_string(),
-            };
             return TestConfig {
                 test_name: format!("test_{}", i),
                 user_agent: user_agent.to_string(),
-                expected: Ok(expected),
+                expected: Ok(user_agent.to_string()),
             }
         }).collect::<Vec<TestConfig>>();
 
@@ -111,8 +287,15 @@ mod tests {
             expected: Err(Error::MalformedUserAgent),
         });
 
+        tests.push(TestConfig {
+            test_name: "Too long user agent".to_string(),
+            user_agent: format!("curl/{}", "7".repeat(MAX_USER_AGENT_LENGTH)),
+            expected: Err(Error::UserAgentTooLong),
+        });
+
         for test in tests {
-            let result = UserAgent::new(&test.user_agent);
+            let result = UserAgent::new(&test.user_agent)
+                .map(|user_agent| user_agent.user_agent().to_string());
             assert_eq!(result, test.expected, "{}", test.test_name);
         }
     }
@@ -120,11 +303,63 @@ mod tests {
     #[test]
     fn test_user_agent() {
         let user_agent = "Mozilla/5.0 (Linux; Android 6.0.1; RedMi Note 5 Build/RB3N5C; wv) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/68.0.3440.91 Mobile Safari/537.36";
-        let expected = UserAgent {
-            user_agent: user_agent.to_string(),
-        };
+        let expected = UserAgent::new(user_agent).expect("Failed to parse user agent");
 
         assert_eq!(expected.user_agent(), user_agent);
         assert_eq!(expected.user_agent(), user_agent);
     }
+
+    #[test]
+    fn test_parse() {
+        let tests = vec![
+            (
+                "Mozilla/5.0 (Linux; Android 6.0.1; RedMi Note 5 Build/RB3N5C; wv) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/68.0.3440.91 Mobile Safari/537.36",
+                Some("Chrome"), Some("Android"), DeviceClass::Mobile, "Chrome on Android",
+            ),
+            (
+                "Mozilla/5.0 (X11; Fedora; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36",
+                Some("Chrome"), Some("Linux"), DeviceClass::Desktop, "Chrome on Linux",
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_10; rv:33.0) Gecko/20100101 Firefox/33.0",
+                Some("Firefox"), Some("macOS"), DeviceClass::Desktop, "Firefox on macOS",
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36 Edg/91.0.864.59",
+                Some("Edge"), Some("Windows"), DeviceClass::Desktop, "Edge on Windows",
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/51.0.2704.106 Safari/537.36 OPR/38.0.2220.41",
+                Some("Opera"), Some("Linux"), DeviceClass::Desktop, "Opera on Linux",
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 6.1; Win64; x64; Trident/7.0; rv:11.0) like Gecko",
+                Some("Internet Explorer"), Some("Windows"), DeviceClass::Desktop, "Internet Explorer on Windows",
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 13_5_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/13.1.1 Mobile/15E148 Safari/604.1",
+                Some("Safari"), Some("iOS"), DeviceClass::Mobile, "Safari on iOS",
+            ),
+            (
+                "Mozilla/5.0 (iPad; CPU OS 13_5_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/13.1.1 Safari/604.1",
+                Some("Safari"), Some("iOS"), DeviceClass::Tablet, "Safari on iOS",
+            ),
+            (
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+                Some("Googlebot"), None, DeviceClass::Bot, "Googlebot",
+            ),
+            (
+                "curl/7.64.1",
+                Some("curl"), None, DeviceClass::Unknown, "curl",
+            ),
+        ];
+
+        for (user_agent, browser, os, device_class, display) in tests {
+            let got = UserAgent::new(user_agent).expect("Failed to parse user agent");
+            assert_eq!(got.browser(), browser, "{}", user_agent);
+            assert_eq!(got.os(), os, "{}", user_agent);
+            assert_eq!(got.device_class(), device_class, "{}", user_agent);
+            assert_eq!(got.to_string(), display, "{}", user_agent);
+        }
+    }
 }
\ No newline at end of file
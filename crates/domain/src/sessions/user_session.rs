@@ -1,14 +1,19 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 use security::token::token::Token;
+use crate::sessions::device::Device;
 use crate::sessions::state::active::Active;
 use crate::sessions::state::already_ended::AlreadyEnded;
 use crate::sessions::state::just_ended::JustEnded;
 use crate::sessions::state::newly_created::NewlyCreated;
+use crate::sessions::refresh_outcome::RefreshOutcome;
+use crate::sessions::session_policy::SessionPolicy;
 use crate::sessions::state::refreshed::Refreshed;
+use crate::sessions::state::reissued::Reissued;
 use crate::sessions::state::state::{SessionEndReason, State};
 use crate::sessions::user_session_token::UserSessionToken;
 use crate::sessions::tokens::{AccessToken, RefreshToken};
+use crate::shared::timestamp;
 use crate::user::user_id::UserId;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -16,6 +21,7 @@ pub struct UserSession<T: State> {
     pub(crate) id: Uuid,
     pub(crate) user_id: UserId,
     pub(crate) created_at: DateTime<Utc>,
+    pub(crate) device: Device,
     pub(crate) state: T
 }
 
@@ -29,32 +35,36 @@ impl<T: State> UserSession<T> {
     pub fn created_at(&self) -> &DateTime<Utc> {
         &self.created_at
     }
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
     pub fn state(&self) -> &T {
         &self.state
     }
 }
 
 impl UserSession<NewlyCreated> {
-    pub fn new(user_id: UserId) -> UserSession<NewlyCreated> {
-        let created_at = Utc::now();
+    pub fn new(user_id: UserId, device: Device, policy: &SessionPolicy) -> UserSession<NewlyCreated> {
+        let created_at = timestamp::now();
         let session_id = Uuid::new_v4();
 
         let refresh_token: UserSessionToken<RefreshToken> = RefreshToken {
             user_id: user_id.0,
             session_id: session_id.clone(),
             parent_id: None,
-        }.into();
+        }.issue(policy, created_at);
 
         let access_token: UserSessionToken<AccessToken> = AccessToken {
             user_id: user_id.0,
             session_id: session_id.clone(),
             refresh_token_id: refresh_token.get_id().clone(),
-        }.into();
+        }.issue(policy);
 
         UserSession {
             id: session_id,
             user_id,
             created_at,
+            device,
             state: NewlyCreated {
                 refresh_token,
                 access_token
@@ -68,51 +78,65 @@ impl UserSession<Active> {
         id: Uuid,
         user_id: UserId,
         created_at: DateTime<Utc>,
+        device: Device,
         state: Active
     ) -> UserSession<Active> {
         UserSession {
             id,
             user_id,
             created_at,
+            device,
             state
         }
     }
 
+    /// Refreshes the session with the given refresh token, issuing new tokens according to the given policy.
+    /// Once refreshed, the session is considered to be on the device that refreshed it.
+    /// The session is ended instead when the refresh token or the session is no longer valid.
     pub fn refresh(
         self,
-        refresh_token: UserSessionToken<RefreshToken>
-    ) -> Result<UserSession<Refreshed>, UserSession<JustEnded>> {
+        refresh_token: UserSessionToken<RefreshToken>,
+        device: Device,
+        policy: &SessionPolicy
+    ) -> RefreshOutcome {
         let latest_refresh_token = &self.state.latest_refresh_token;
 
         // There can only be one valid refresh token at the time,
         // therefor it must only be the latest token
         let refresh_token_used_previously = *latest_refresh_token.get_id() != *refresh_token.get_id();
         if refresh_token_used_previously {
-            return Err(UserSession {
-                id: self.id,
-                user_id: self.user_id,
-                created_at: self.created_at,
-                state: JustEnded {
-                    latest_refresh_token: self.state.latest_refresh_token,
-                    reason_for_ending: SessionEndReason::AttemptedToReuseRefreshToken {
-                        caused_by: refresh_token,
+            // Unless the token was rotated moments ago, e.g. by a concurrent request of the same client.
+            if let Some(latest_access_token) = self.state.reusable_tokens_for(&refresh_token) {
+                return RefreshOutcome::Reissued(UserSession {
+                    id: self.id,
+                    user_id: self.user_id,
+                    created_at: self.created_at,
+                    device: self.device,
+                    state: Reissued {
+                        access_token: latest_access_token.clone(),
+                        refresh_token: self.state.latest_refresh_token,
                     },
-                    session_end_time: Utc::now(),
-                }
-            })
+                })
+            }
+
+            return RefreshOutcome::Ended(self.end(SessionEndReason::AttemptedToReuseRefreshToken {
+                caused_by: refresh_token,
+            }))
+        }
+
+        let now = Utc::now();
+        if now > self.created_at + policy.max_session_age {
+            return RefreshOutcome::Ended(self.end(SessionEndReason::MaxSessionAgeReached))
+        }
+
+        // the latest refresh token was issued when the session was last created or refreshed
+        let idle_since = *self.state.latest_refresh_token.get_issued_at();
+        if policy.idle_timeout.is_some_and(|idle_timeout| now > idle_since + idle_timeout) {
+            return RefreshOutcome::Ended(self.end(SessionEndReason::IdleTimeoutReached))
         }
 
         if self.state.latest_refresh_token.expired() {
-            return Err(UserSession {
-                id: self.id,
-                user_id: self.user_id,
-                created_at: self.created_at,
-                state: JustEnded {
-                    latest_refresh_token: self.state.latest_refresh_token,
-                    reason_for_ending: SessionEndReason::LatestRefreshTokenExpired,
-                    session_end_time: Utc::now(),
-                }
-            })
+            return RefreshOutcome::Ended(self.end(SessionEndReason::LatestRefreshTokenExpired))
         }
 
         // no need to check this currently as paseto token implementation will 
@@ -125,38 +149,75 @@ impl UserSession<Active> {
             user_id: self.user_id.0,
             session_id: self.id.clone(),
             refresh_token_id: latest_refresh_token.get_id().clone(),
-        }.into();
+        }.issue(policy);
 
         let new_refresh_token = RefreshToken {
             user_id: self.user_id.0,
             session_id: self.id.clone(),
             parent_id: Some(latest_refresh_token.get_id().clone()),
-        }.into();
+        }.issue(policy, self.created_at);
+
+        let reuse_grace_period = policy.refresh_token_reuse_grace_period;
+        let refreshed_at = timestamp::now();
+        let old_refresh_token_reusable_until = (reuse_grace_period > Duration::zero())
+            .then(|| refreshed_at + reuse_grace_period);
 
-        Ok(UserSession {
+        RefreshOutcome::Refreshed(UserSession {
             id: self.id,
             user_id: self.user_id,
             created_at: self.created_at,
+            device,
             state: Refreshed {
                 new_access_token,
                 new_refresh_token,
                 old_refresh_token: self.state.latest_refresh_token,
+                refreshed_at,
+                old_refresh_token_reusable_until,
             },
         })
     }
     
-    pub fn end_by_user_logout(self) -> UserSession<JustEnded> {
+    fn end(self, reason: SessionEndReason) -> UserSession<JustEnded> {
         UserSession {
             id: self.id,
             user_id: self.user_id,
             created_at: self.created_at,
+            device: self.device,
             state: JustEnded {
                 latest_refresh_token: self.state.latest_refresh_token,
-                reason_for_ending: SessionEndReason::UserLogout,
-                session_end_time: Utc::now(),
+                reason_for_ending: reason,
+                session_end_time: timestamp::now(),
             }
         }
     }
+
+    pub fn end_by_user_logout(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::UserLogout)
+    }
+
+    pub fn end_by_user_revocation(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::RevokedByUser)
+    }
+
+    pub fn end_by_user_logout_everywhere(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::UserLoggedOutEverywhere)
+    }
+
+    pub fn end_by_password_change(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::PasswordChanged)
+    }
+
+    pub fn end_by_sign_in_on_other_device(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::UserSignedInOnOtherDevice)
+    }
+
+    pub fn end_by_use_of_expired_access_token(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::UsedExpiredAccessToken)
+    }
+
+    pub fn end_by_admin_revocation(self) -> UserSession<JustEnded> {
+        self.end(SessionEndReason::RevokedByAdmin)
+    }
 }
 
 impl UserSession<AlreadyEnded> {
@@ -164,12 +225,14 @@ impl UserSession<AlreadyEnded> {
         id: Uuid,
         user_id: UserId,
         created_at: DateTime<Utc>,
+        device: Device,
         state: Active
     ) -> UserSession<Active> {
         UserSession {
             id,
             user_id,
             created_at,
+            device,
             state
         }
     }
@@ -180,6 +243,9 @@ mod tests {
     use chrono::{DateTime, Duration, Utc};
     use uuid::Uuid;
     use security::token::token::Token;
+    use crate::sessions::device::Device;
+    use crate::sessions::refresh_outcome::RefreshOutcome;
+    use crate::sessions::session_policy::SessionPolicy;
     use crate::sessions::state::active::Active;
     use crate::sessions::state::just_ended::JustEnded;
     use crate::sessions::state::newly_created::NewlyCreated;
@@ -191,7 +257,7 @@ mod tests {
     #[test]
     fn test_new_session() {
         let user_id = Uuid::new_v4().into();
-        let session = UserSession::<NewlyCreated>::new(user_id);
+        let session = UserSession::<NewlyCreated>::new(user_id, Device::default(), &SessionPolicy::default());
 
         // Check if user session has correct properties
         assert_eq!(session.user_id, user_id);
@@ -213,22 +279,21 @@ mod tests {
 
     #[test]
     fn test_refresh_should_succeed_with_good_refresh_token() {
-        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into());
+        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &SessionPolicy::default());
         let refresh_token = session.state.refresh_token.clone();
         let session: UserSession<Active> = UserSession {
             id: session.id,
             user_id: session.user_id,
             created_at: session.created_at,
-            state: Active {
-                latest_refresh_token: session.state.refresh_token
-            },
+            device: session.device,
+            state: Active::new(session.state.refresh_token),
         };
 
         let id = session.id.clone();
         let user_id = session.user_id.clone();
         let created_at = session.created_at.clone();
 
-        if let Ok(refresh_session) = session.refresh(refresh_token.clone()) {
+        if let RefreshOutcome::Refreshed(refresh_session) = session.refresh(refresh_token.clone(), Device::default(), &SessionPolicy::default()) {
             // Check if refresh_session has correct properties
             assert_eq!(id, refresh_session.id);
             assert_eq!(user_id, refresh_session.user_id);
@@ -248,7 +313,8 @@ mod tests {
             assert_eq!(refresh_session.state.new_refresh_token.get_custom_claims().parent_id.unwrap(), *refresh_token.get_id());
 
             // Check if old_refresh_token has correct properties
-            assert_eq!(refresh_session.state.old_refresh_token.get_id(), refresh_token.get_id())
+            assert_eq!(refresh_session.state.old_refresh_token.get_id(), refresh_token.get_id());
+            assert!(within_second(Utc::now(), refresh_session.state.refreshed_at))
         } else {
             panic!("Failed to refresh session")
         }
@@ -256,28 +322,27 @@ mod tests {
 
     #[test]
     fn test_refresh_should_end_session_with_previous_used_refresh_token() {
-        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into());
+        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &SessionPolicy::default());
         let invalid_refresh_token: UserSessionToken<RefreshToken> = RefreshToken {
             user_id: Uuid::new_v4(),
             session_id: Uuid::new_v4(),
             parent_id: None,
-        }.into();
+        }.issue(&SessionPolicy::default(), Utc::now());
 
         let session_token_id = session.state.refresh_token.get_id().clone();
         let session: UserSession<Active> = UserSession {
             id: session.id,
             user_id: session.user_id,
             created_at: session.created_at,
-            state: Active {
-                latest_refresh_token: session.state.refresh_token
-            },
+            device: session.device,
+            state: Active::new(session.state.refresh_token),
         };
 
         let id = session.id.clone();
         let user_id = session.user_id.clone();
         let created_at = session.created_at.clone();
 
-        if let Err(ended_session) = session.refresh(invalid_refresh_token.clone()) {
+        if let RefreshOutcome::Ended(ended_session) = session.refresh(invalid_refresh_token.clone(), Device::default(), &SessionPolicy::default()) {
             // Check if ended_session has correct properties
             assert_eq!(id, ended_session.id);
             assert_eq!(user_id, ended_session.user_id);
@@ -299,7 +364,7 @@ mod tests {
 
     #[test]
     fn test_refresh_should_end_session_with_expired_used_refresh_token() {
-        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into());
+        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &SessionPolicy::default());
         let now = Utc::now();
         let invalid_refresh_token = UserSessionToken::new(
             Uuid::new_v4(),
@@ -321,16 +386,15 @@ mod tests {
             id: session.id,
             user_id: session.user_id,
             created_at: session.created_at,
-            state: Active {
-                latest_refresh_token: invalid_refresh_token.clone()
-            },
+            device: session.device,
+            state: Active::new(invalid_refresh_token.clone()),
         };
 
         let id = session.id.clone();
         let user_id = session.user_id.clone();
         let created_at = session.created_at.clone();
 
-        if let Err(ended_session) = session.refresh(invalid_refresh_token.clone()) {
+        if let RefreshOutcome::Ended(ended_session) = session.refresh(invalid_refresh_token.clone(), Device::default(), &SessionPolicy::default()) {
             // Check if ended_session has correct properties
             assert_eq!(id, ended_session.id);
             assert_eq!(user_id, ended_session.user_id);
@@ -350,23 +414,155 @@ mod tests {
         }
     }
     
+    /// Refreshes a new session with the given policy, returning the refreshed session
+    /// as active session together with the refresh token that got rotated.
+    fn refreshed_active_session(policy: &SessionPolicy) -> (UserSession<Active>, UserSessionToken<RefreshToken>) {
+        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &SessionPolicy::default());
+        let old_refresh_token = session.state.refresh_token.clone();
+        let session = UserSession {
+            id: session.id,
+            user_id: session.user_id,
+            created_at: session.created_at,
+            device: session.device,
+            state: Active::new(session.state.refresh_token),
+        };
+
+        let refreshed = match session.refresh(old_refresh_token.clone(), Device::default(), policy) {
+            RefreshOutcome::Refreshed(refreshed) => refreshed,
+            _ => panic!("Failed to refresh session")
+        };
+
+        let session = UserSession {
+            id: refreshed.id,
+            user_id: refreshed.user_id,
+            created_at: refreshed.created_at,
+            device: refreshed.device,
+            state: Active {
+                latest_refresh_token: refreshed.state.new_refresh_token,
+                latest_access_token: Some(refreshed.state.new_access_token),
+                parent_reusable_until: refreshed.state.old_refresh_token_reusable_until,
+            },
+        };
+
+        (session, old_refresh_token)
+    }
+
+    fn policy_with_reuse_grace_period(reuse_grace_period: Duration) -> SessionPolicy {
+        SessionPolicy {
+            refresh_token_reuse_grace_period: reuse_grace_period,
+            ..SessionPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_refresh_within_reuse_grace_period_reissues_tokens() {
+        let policy = policy_with_reuse_grace_period(Duration::seconds(10));
+        let (session, old_refresh_token) = refreshed_active_session(&policy);
+        let latest_refresh_token_id = session.state.latest_refresh_token.id;
+        let latest_access_token_id = session.state.latest_access_token.as_ref().unwrap().id;
+
+        match session.refresh(old_refresh_token, Device::default(), &policy) {
+            RefreshOutcome::Reissued(reissued) => {
+                assert_eq!(reissued.state.refresh_token.id, latest_refresh_token_id);
+                assert_eq!(reissued.state.access_token.id, latest_access_token_id);
+            }
+            _ => panic!("Expected tokens to be reissued")
+        }
+    }
+
+    #[test]
+    fn test_refresh_after_reuse_grace_period_ends_session() {
+        let policy = policy_with_reuse_grace_period(Duration::seconds(10));
+        let (mut session, old_refresh_token) = refreshed_active_session(&policy);
+        session.state.parent_reusable_until = Some(Utc::now() - Duration::seconds(1));
+
+        match session.refresh(old_refresh_token, Device::default(), &policy) {
+            RefreshOutcome::Ended(ended) => assert_eq!(ended.state.reason_for_ending.to_string(), "AttemptedToReuseRefreshToken"),
+            _ => panic!("Expected session to end")
+        }
+
+        // without grace period, reusing the token immediately ends the session
+        let (session, old_refresh_token) = refreshed_active_session(&SessionPolicy::default());
+        assert!(session.state.parent_reusable_until.is_none());
+        assert!(matches!(session.refresh(old_refresh_token, Device::default(), &SessionPolicy::default()), RefreshOutcome::Ended(_)));
+    }
+
+    #[test]
+    fn test_refresh_after_max_session_age_ends_session() {
+        let policy = SessionPolicy::default();
+        let (mut session, _) = refreshed_active_session(&policy);
+        let latest_refresh_token = session.state.latest_refresh_token.clone();
+        session.created_at = Utc::now() - policy.max_session_age - Duration::seconds(1);
+
+        match session.refresh(latest_refresh_token, Device::default(), &policy) {
+            RefreshOutcome::Ended(ended) => assert_eq!(ended.state.reason_for_ending.to_string(), "MaxSessionAgeReached"),
+            _ => panic!("Expected session to end")
+        }
+    }
+
+    #[test]
+    fn test_refresh_after_idle_timeout_ends_session() {
+        let policy = SessionPolicy {
+            idle_timeout: Some(Duration::minutes(30)),
+            ..SessionPolicy::default()
+        };
+
+        let (session, _) = refreshed_active_session(&policy);
+        let latest_refresh_token = session.state.latest_refresh_token.clone();
+        assert!(matches!(session.refresh(latest_refresh_token, Device::default(), &policy), RefreshOutcome::Refreshed(_)));
+
+        let (mut session, _) = refreshed_active_session(&policy);
+        session.state.latest_refresh_token.issued_at = Utc::now() - Duration::minutes(31);
+        let latest_refresh_token = session.state.latest_refresh_token.clone();
+
+        match session.refresh(latest_refresh_token, Device::default(), &policy) {
+            RefreshOutcome::Ended(ended) => assert_eq!(ended.state.reason_for_ending.to_string(), "IdleTimeoutReached"),
+            _ => panic!("Expected session to end")
+        }
+    }
+
+    #[test]
+    fn test_tokens_are_issued_according_to_policy() {
+        let policy = SessionPolicy {
+            access_token_lifetime: Duration::minutes(1),
+            refresh_token_lifetime: Duration::hours(1),
+            max_session_age: Duration::minutes(30),
+            issuer: "issuer".to_string(),
+            audience: "audience".to_string(),
+            ..SessionPolicy::default()
+        };
+
+        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &policy);
+        let access_token = &session.state.access_token;
+        let refresh_token = &session.state.refresh_token;
+
+        assert_eq!(access_token.get_issuer(), "issuer");
+        assert_eq!(access_token.get_audience(), "audience");
+        assert_eq!(refresh_token.get_issuer(), "issuer");
+        assert_eq!(refresh_token.get_audience(), "audience");
+        assert!(within_second(session.created_at + Duration::minutes(1), *access_token.get_expiration()));
+
+        // the refresh token may not outlive the maximum age of the session
+        assert!(within_second(session.created_at + Duration::minutes(30), *refresh_token.get_expiration()));
+    }
+
     #[test]
     fn test_end_by_logout() {
         let user_id = Uuid::new_v4();
-        let session = UserSession::<NewlyCreated>::new(user_id.into());
+        let session = UserSession::<NewlyCreated>::new(user_id.into(), Device::default(), &SessionPolicy::default());
         let session: UserSession<Active> = UserSession {
             id: session.id,
             user_id: session.user_id,
             created_at: session.created_at,
-            state: Active {
-                latest_refresh_token: session.state.refresh_token.clone()
-            },
+            device: session.device,
+            state: Active::new(session.state.refresh_token.clone()),
         };
         
         let expected = UserSession {
             id: session.id.clone(),
             user_id: session.user_id.clone(),
             created_at: session.created_at.clone(),
+            device: session.device.clone(),
             state: JustEnded {
                 latest_refresh_token: session.state.latest_refresh_token.clone(),
                 reason_for_ending: SessionEndReason::UserLogout,
@@ -383,6 +579,70 @@ mod tests {
         assert_eq!(expected.state.reason_for_ending.to_string(), got.state.reason_for_ending.to_string());
         assert!(within_second(expected.state.session_end_time, got.state.session_end_time))
     }
+
+    #[test]
+    fn test_end_by_user_revocation() {
+        let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &SessionPolicy::default());
+        let session: UserSession<Active> = UserSession {
+            id: session.id,
+            user_id: session.user_id,
+            created_at: session.created_at,
+            device: session.device,
+            state: Active::new(session.state.refresh_token.clone()),
+        };
+
+        let id = session.id;
+        let latest_refresh_token_id = session.state.latest_refresh_token.id;
+
+        let got = session.end_by_user_revocation();
+
+        assert_eq!(id, got.id);
+        assert_eq!(latest_refresh_token_id, got.state.latest_refresh_token.id);
+        assert_eq!(got.state.reason_for_ending.to_string(), "RevokedByUser");
+        assert!(within_second(Utc::now(), got.state.session_end_time))
+    }
+
+    #[test]
+    fn test_end_by_logout_everywhere_admin_revocation_and_expired_access_token() {
+        let new_active_session = || {
+            let session = UserSession::<NewlyCreated>::new(Uuid::new_v4().into(), Device::default(), &SessionPolicy::default());
+            UserSession {
+                id: session.id,
+                user_id: session.user_id,
+                created_at: session.created_at,
+                device: session.device,
+                state: Active::new(session.state.refresh_token),
+            }
+        };
+
+        let session = new_active_session();
+        let id = session.id;
+        let got = session.end_by_user_logout_everywhere();
+        assert_eq!(id, got.id);
+        assert_eq!(got.state.reason_for_ending.to_string(), "UserLoggedOutEverywhere");
+        assert!(within_second(Utc::now(), got.state.session_end_time));
+
+        let session = new_active_session();
+        let id = session.id;
+        let got = session.end_by_admin_revocation();
+        assert_eq!(id, got.id);
+        assert_eq!(got.state.reason_for_ending.to_string(), "RevokedByAdmin");
+        assert!(within_second(Utc::now(), got.state.session_end_time));
+
+        let session = new_active_session();
+        let id = session.id;
+        let got = session.end_by_password_change();
+        assert_eq!(id, got.id);
+        assert_eq!(got.state.reason_for_ending.to_string(), "PasswordChanged");
+        assert!(within_second(Utc::now(), got.state.session_end_time));
+
+        let session = new_active_session();
+        let id = session.id;
+        let got = session.end_by_use_of_expired_access_token();
+        assert_eq!(id, got.id);
+        assert_eq!(got.state.reason_for_ending.to_string(), "UsedExpiredAccessToken");
+        assert!(within_second(Utc::now(), got.state.session_end_time))
+    }
     
     fn within_second(expected: DateTime<Utc>, got: DateTime<Utc>) -> bool {
         (got.timestamp_millis() - expected.timestamp_millis()) < Duration::seconds(1).num_milliseconds()
@@ -0,0 +1,91 @@
+use rand::distributions::{Alphanumeric, DistString};
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use security::hash::token_hash::{hash_token, token_matches};
+
+/// IdentifiedTokenFormat describes random tokens formatted as `<prefix><id><separator><secret>`,
+/// such as reset tokens and personal access tokens. The id allows finding the stored hash of a
+/// submitted token without knowing its user, the secret is what makes the token impossible to guess.
+pub struct IdentifiedTokenFormat {
+    pub prefix: &'static str,
+    pub separator: char,
+    pub secret_length: usize,
+}
+
+impl IdentifiedTokenFormat {
+
+    /// Generates a new token with the given id. Returns the token together with its hash to store.
+    pub fn generate(&self, id: Uuid) -> (Secret<String>, String) {
+        let secret = Alphanumeric.sample_string(&mut rand::thread_rng(), self.secret_length);
+        let token = Secret::new(format!("{}{}{}{}", self.prefix, id.simple(), self.separator, secret));
+        let hash = hash_token(&token);
+
+        (token, hash)
+    }
+
+    /// Returns whether the submitted token starts with the prefix of this format.
+    pub fn is_formatted(&self, token: &Secret<String>) -> bool {
+        token.expose_secret().starts_with(self.prefix)
+    }
+
+    /// Returns the id the submitted token claims to have, or None when the token is malformed.
+    pub fn id_of(&self, token: &Secret<String>) -> Option<Uuid> {
+        let (id, _) = token.expose_secret()
+            .strip_prefix(self.prefix)?
+            .split_once(self.separator)?;
+
+        Uuid::try_parse(id).ok()
+    }
+
+    /// Verifies whether the submitted token is the token with the given id and hash.
+    pub fn matches(&self, id: Uuid, hash: &str, submitted_token: &Secret<String>) -> bool {
+        self.id_of(submitted_token) == Some(id) && token_matches(submitted_token, hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::{ExposeSecret, Secret};
+    use uuid::Uuid;
+
+    use super::*;
+
+    const FORMAT: IdentifiedTokenFormat = IdentifiedTokenFormat {
+        prefix: "test_",
+        separator: '.',
+        secret_length: 16,
+    };
+
+    #[test]
+    fn test_generate() {
+        let id = Uuid::new_v4();
+        let (token, hash) = FORMAT.generate(id);
+
+        assert!(FORMAT.is_formatted(&token));
+        assert_eq!(FORMAT.id_of(&token), Some(id));
+        assert_eq!(token.expose_secret().len(), FORMAT.prefix.len() + 32 + 1 + FORMAT.secret_length);
+        assert!(FORMAT.matches(id, &hash, &token));
+    }
+
+    #[test]
+    fn test_malformed_tokens_have_no_id() {
+        let id = Uuid::new_v4().simple();
+
+        assert_eq!(FORMAT.id_of(&Secret::new(format!("{id}.secret"))), None);
+        assert_eq!(FORMAT.id_of(&Secret::new(format!("test_{id}_secret"))), None);
+        assert_eq!(FORMAT.id_of(&Secret::new("test_not-an-id.secret".to_string())), None);
+    }
+
+    #[test]
+    fn test_secret_only_matches_with_its_own_id() {
+        let id = Uuid::new_v4();
+        let (token, hash) = FORMAT.generate(id);
+
+        let (_, secret) = token.expose_secret().split_once(FORMAT.separator).unwrap();
+        let forged_token = Secret::new(format!("test_{}.{}", Uuid::new_v4().simple(), secret));
+
+        assert!(!FORMAT.matches(id, &hash, &forged_token));
+        assert!(!FORMAT.matches(Uuid::new_v4(), &hash, &token));
+    }
+}
@@ -1,3 +1,5 @@
 pub mod activation_time;
 pub mod expiration;
+pub mod identified_token;
 pub mod slug;
+pub mod timestamp;
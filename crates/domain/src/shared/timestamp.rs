@@ -0,0 +1,18 @@
+use chrono::{DateTime, SubsecRound, Utc};
+
+/// Returns the current time truncated to microseconds, the precision in which Postgres stores
+/// timestamps, such that sessions and their tokens are unchanged after they were saved and loaded again.
+pub fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+    use crate::shared::timestamp::now;
+
+    #[test]
+    fn test_now_is_truncated_to_microseconds() {
+        assert_eq!(now().nanosecond() % 1_000, 0);
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// LockoutPolicy determines when an account is locked after failed login attempts, to slow down
+/// brute-force attacks on the password of a user. Every consecutive lockout of the same account
+/// lasts twice as long as the previous one, up to `max_lockout`.
+#[derive(Debug, Clone, Copy)]
+pub struct LockoutPolicy {
+    /// max_failed_attempts is the amount of failed attempts within the window that lock the account.
+    pub max_failed_attempts: u32,
+
+    /// window is the period in which failed attempts are counted.
+    pub window: Duration,
+
+    /// lockout is the duration of the first lockout.
+    pub lockout: Duration,
+
+    /// max_lockout caps the duration of a lockout. Previous lockouts are forgotten when the
+    /// account has not been locked for this long.
+    pub max_lockout: Duration,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            window: Duration::minutes(15),
+            lockout: Duration::minutes(1),
+            max_lockout: Duration::hours(1),
+        }
+    }
+}
+
+/// FailedLogins are the failed login attempts for a username. Usernames of which no user exists
+/// are tracked as well, so a lockout does not reveal whether an account exists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedLogins {
+    /// failed_attempts are the failed attempts since the start of the window.
+    pub failed_attempts: u32,
+    pub window_started_at: DateTime<Utc>,
+
+    /// lockouts is the amount of consecutive lockouts, which determines the next lockout duration.
+    pub lockouts: u32,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl FailedLogins {
+
+    /// Returns how long the account is still locked at the given time, or None when it is not locked.
+    pub fn locked_for(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.locked_until
+            .filter(|locked_until| *locked_until > now)
+            .map(|locked_until| locked_until - now)
+    }
+}
+
+impl LockoutPolicy {
+
+    /// Records a failed attempt at the given time, locking the account when the attempt is the
+    /// last allowed attempt within the window. Attempts made while locked are not counted.
+    pub fn record_failure(&self, previous: Option<FailedLogins>, now: DateTime<Utc>) -> FailedLogins {
+        let mut failed_logins = previous
+            .filter(|failed_logins| !self.is_forgotten(failed_logins, now))
+            .unwrap_or(FailedLogins {
+                failed_attempts: 0,
+                window_started_at: now,
+                lockouts: 0,
+                locked_until: None,
+            });
+
+        if failed_logins.locked_for(now).is_some() {
+            return failed_logins
+        }
+
+        if failed_logins.window_started_at + self.window <= now {
+            failed_logins.failed_attempts = 0;
+            failed_logins.window_started_at = now;
+        }
+
+        failed_logins.failed_attempts += 1;
+        if failed_logins.failed_attempts >= self.max_failed_attempts {
+            failed_logins.locked_until = Some(now + self.lockout_duration(failed_logins.lockouts));
+            failed_logins.lockouts = failed_logins.lockouts.saturating_add(1);
+            failed_logins.failed_attempts = 0;
+            failed_logins.window_started_at = now;
+        }
+
+        failed_logins
+    }
+
+    /// Returns the duration of the lockout after the given amount of previous lockouts.
+    pub fn lockout_duration(&self, previous_lockouts: u32) -> Duration {
+        2i32.checked_pow(previous_lockouts)
+            .and_then(|factor| self.lockout.checked_mul(factor))
+            .map_or(self.max_lockout, |lockout| lockout.min(self.max_lockout))
+    }
+
+    /// Failed logins are forgotten once their window has passed and the account
+    /// has not been locked for the maximum lockout duration.
+    pub fn is_forgotten(&self, failed_logins: &FailedLogins, now: DateTime<Utc>) -> bool {
+        let window_passed = failed_logins.window_started_at + self.window <= now;
+        let lockout_forgotten = failed_logins.locked_until
+            .is_none_or(|locked_until| locked_until + self.max_lockout <= now);
+
+        window_passed && lockout_forgotten
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+
+    fn policy() -> LockoutPolicy {
+        LockoutPolicy {
+            max_failed_attempts: 3,
+            window: Duration::minutes(10),
+            lockout: Duration::minutes(1),
+            max_lockout: Duration::minutes(5),
+        }
+    }
+
+    fn fail_times(policy: &LockoutPolicy, previous: Option<FailedLogins>, times: u32, now: DateTime<Utc>) -> FailedLogins {
+        (0..times).fold(previous, |previous, _| Some(policy.record_failure(previous, now)))
+            .expect("Should have failed at least once")
+    }
+
+    #[test]
+    fn test_locks_after_max_failed_attempts() {
+        let policy = policy();
+        let now = Utc::now();
+
+        let failed_logins = fail_times(&policy, None, 2, now);
+        assert_eq!(failed_logins.failed_attempts, 2);
+        assert_eq!(failed_logins.locked_for(now), None);
+
+        let failed_logins = policy.record_failure(Some(failed_logins), now);
+        assert_eq!(failed_logins.locked_for(now), Some(Duration::minutes(1)));
+        assert_eq!(failed_logins.locked_for(now + Duration::minutes(1)), None);
+
+        // attempts made while locked do not extend the lockout
+        let locked = policy.record_failure(Some(failed_logins.clone()), now + Duration::seconds(30));
+        assert_eq!(locked, failed_logins);
+    }
+
+    #[test]
+    fn test_failed_attempts_outside_window_are_not_counted() {
+        let policy = policy();
+        let now = Utc::now();
+
+        let failed_logins = fail_times(&policy, None, 2, now);
+        let failed_logins = policy.record_failure(Some(failed_logins), now + Duration::minutes(10));
+
+        assert_eq!(failed_logins.failed_attempts, 1);
+        assert_eq!(failed_logins.locked_for(now + Duration::minutes(10)), None);
+    }
+
+    #[test]
+    fn test_lockouts_back_off_exponentially() {
+        let policy = policy();
+        let mut now = Utc::now();
+        let mut failed_logins = None;
+
+        for expected_lockout in [1, 2, 4, 5, 5] {
+            let locked = fail_times(&policy, failed_logins, 3, now);
+            assert_eq!(locked.locked_for(now), Some(Duration::minutes(expected_lockout)));
+
+            now += Duration::minutes(expected_lockout);
+            failed_logins = Some(locked);
+        }
+    }
+
+    #[test]
+    fn test_lockouts_are_forgotten_after_max_lockout() {
+        let policy = policy();
+        let now = Utc::now();
+
+        let locked = fail_times(&policy, None, 3, now);
+        assert_eq!(locked.lockouts, 1);
+
+        let later = now + Duration::minutes(1) + policy.window + policy.max_lockout;
+        let locked = fail_times(&policy, Some(locked), 3, later);
+        assert_eq!(locked.lockouts, 1);
+        assert_eq!(locked.locked_for(later), Some(Duration::minutes(1)));
+    }
+
+    #[test]
+    fn test_lockout_duration_does_not_overflow() {
+        let policy = policy();
+        assert_eq!(policy.lockout_duration(0), Duration::minutes(1));
+        assert_eq!(policy.lockout_duration(u32::MAX), policy.max_lockout);
+    }
+}
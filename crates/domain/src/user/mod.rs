@@ -3,3 +3,9 @@ pub mod user_credentials;
 pub mod user_id;
 pub mod user_details;
 pub mod new_user;
+pub mod totp_credential;
+pub mod recovery_code;
+pub mod password_reset_token;
+pub mod login_lockout;
+pub mod personal_access_token;
+pub mod service_account;
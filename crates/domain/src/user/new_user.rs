@@ -6,5 +6,6 @@ pub struct NewUser {
     pub id: UserId,
     pub username: String,
     pub password: Password,
+    pub email: Option<String>,
     pub system_role: Option<SystemRole>
 }
\ No newline at end of file
@@ -1,8 +1,11 @@
+use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 use password_hash::{PasswordHash, SaltString};
 use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
 use security::hash::error::Error;
-use security::hash::scheme::{get_latest_scheme, get_scheme, is_latest_schema, Scheme};
+use security::hash::scheme::{get_latest_scheme, get_scheme, is_outdated, to_phc_string, Scheme};
 use lib_util::errors::errors::format_error_chain;
 
 pub struct Password {
@@ -33,9 +36,9 @@ impl Password {
             .ok_or(MatchError::NoHashSchemeForPassword(password_algorithm.to_string()))?;
 
         match scheme.validate(&submitted_password, &password_hash) {
-            Ok(_) => match is_latest_schema(&password_hash.algorithm) {
-                true => Ok(MatchResult::Matches),
-                false => Ok(MatchResult::MatchesButSchemeOutdated),
+            Ok(_) => match is_outdated(&password_hash) {
+                false => Ok(MatchResult::Matches),
+                true => Ok(MatchResult::MatchesButSchemeOutdated),
             },
             Err(err) => match err {
                 Error::PasswordInvalid => Ok(MatchResult::DoesNotMatch),
@@ -85,7 +88,8 @@ pub enum MatchResult {
     Matches,
 
     /// MatchesButSchemeOutdated is returned when the password matches
-    /// but the password hash needs to be updated to a newer password Scheme
+    /// but the password hash needs to be updated to a newer password Scheme,
+    /// or to the current parameters of the scheme
     MatchesButSchemeOutdated
 }
 
@@ -108,7 +112,9 @@ impl TryFrom<PasswordHash<'_>> for Password {
 impl TryFrom<String> for Password {
     type Error = password_hash::Error;
 
+    /// Parses a hash in the PHC string format, or a bcrypt hash of a user imported from a legacy system.
     fn try_from(hash: String) -> Result<Self, Self::Error> {
+        let hash = to_phc_string(hash);
         PasswordHash::new(&hash)?;
         return Ok(Password {
             hash: Secret::new(hash)
@@ -116,12 +122,157 @@ impl TryFrom<String> for Password {
     }
 }
 
+/// PasswordPolicy determines which passwords users may choose. It is only applied to new
+/// passwords, existing passwords keep working when the policy becomes stricter.
+#[derive(Clone, Debug)]
+pub struct PasswordPolicy {
+    /// min_length is the least amount of characters of a password, passwords are never empty.
+    pub min_length: usize,
+
+    /// max_length is the most amount of characters of a password, which bounds the time
+    /// it takes to hash a password.
+    pub max_length: usize,
+
+    /// required_character_classes are the classes of which a password needs at least one character.
+    pub required_character_classes: Vec<CharacterClass>,
+
+    /// reject_username rejects passwords containing the username, ignoring casing.
+    pub reject_username: bool,
+
+    /// breached_passwords rejects passwords which are known to be leaked.
+    pub breached_passwords: Option<BreachedPasswords>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            required_character_classes: vec![],
+            reject_username: true,
+            breached_passwords: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+
+    /// Validates the password a user with the given username wants to use.
+    /// Returns every rule the password violates, so the user can fix them at once.
+    pub fn validate(&self, password: &Secret<String>, username: &str) -> Result<(), Vec<PasswordViolation>> {
+        let password = password.expose_secret();
+        let length = password.chars().count();
+        let mut violations = vec![];
+
+        let min_length = self.min_length.max(1);
+        if length < min_length {
+            violations.push(PasswordViolation::TooShort { min_length })
+        }
+
+        if length > self.max_length {
+            violations.push(PasswordViolation::TooLong { max_length: self.max_length })
+        }
+
+        for character_class in &self.required_character_classes {
+            if !password.chars().any(|c| character_class.contains(c)) {
+                violations.push(PasswordViolation::MissingCharacterClass { character_class: *character_class })
+            }
+        }
+
+        let username = username.trim().to_lowercase();
+        if self.reject_username && !username.is_empty() && password.to_lowercase().contains(&username) {
+            violations.push(PasswordViolation::ContainsUsername)
+        }
+
+        if self.breached_passwords.as_ref().is_some_and(|breached| breached.contains(password)) {
+            violations.push(PasswordViolation::Breached)
+        }
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+
+    /// Every character which is not a letter or digit, e.g. punctuation and whitespace.
+    Symbol,
+}
+
+impl CharacterClass {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            CharacterClass::Lowercase => c.is_lowercase(),
+            CharacterClass::Uppercase => c.is_uppercase(),
+            CharacterClass::Digit => c.is_numeric(),
+            CharacterClass::Symbol => !c.is_alphanumeric(),
+        }
+    }
+}
+
+/// PasswordViolation is a rule of the password policy which a password does not meet.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum PasswordViolation {
+    TooShort { min_length: usize },
+    TooLong { max_length: usize },
+    MissingCharacterClass { character_class: CharacterClass },
+    ContainsUsername,
+    Breached,
+}
+
+/// BreachedPasswords are passwords known to be leaked, e.g. from a public list of breached
+/// passwords, which are the first passwords attackers try.
+#[derive(Clone)]
+pub struct BreachedPasswords(Arc<HashSet<String>>);
+
+impl BreachedPasswords {
+
+    /// Creates the list from lines of passwords, ignoring empty lines.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let passwords = lines.into_iter()
+            .map(|line| line.trim_end_matches(['\r', '\n']))
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Self(Arc::new(passwords))
+    }
+
+    pub fn contains(&self, password: &str) -> bool {
+        self.0.contains(password)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Debug for BreachedPasswords {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BreachedPasswords({} passwords)", self.len())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use password_hash::{Ident, PasswordHash, SaltString};
     use secrecy::{ExposeSecret, Secret};
     use uuid::Uuid;
-    use crate::user::password::{MatchError, MatchResult, Password};
+    use security::hash::argon2::{Argon2Params, Argon2Scheme};
+    use security::hash::scheme::Scheme;
+    use crate::user::password::{BreachedPasswords, CharacterClass, MatchError, MatchResult, Password, PasswordPolicy, PasswordViolation};
 
     fn get_salt() -> SaltString {
         SaltString::generate(&mut rand::thread_rng())
@@ -155,6 +306,43 @@ mod tests {
         assert_eq!(password1.matches(&pw2).unwrap(), MatchResult::DoesNotMatch);
     }
 
+    #[test]
+    fn password_with_weaker_params_is_outdated() {
+        let pw1 = Secret::new(Uuid::new_v4().to_string());
+        let salt = get_salt();
+        let weak_scheme = Argon2Scheme::new(Argon2Params { m_cost: 8192, t_cost: 1, p_cost: 1 });
+        let weak_hash = weak_scheme.hash(pw1.clone(), &salt).unwrap();
+        let password1 = Password::try_from(weak_hash).unwrap();
+
+        assert_eq!(password1.matches(&pw1).unwrap(), MatchResult::MatchesButSchemeOutdated);
+
+        let pw2 = Secret::new(Uuid::new_v4().to_string());
+        assert_eq!(password1.matches(&pw2).unwrap(), MatchResult::DoesNotMatch);
+    }
+
+    #[test]
+    fn legacy_passwords_are_outdated() {
+        let legacy_hashes = [
+            "$2b$04$KBCwKxOzLha2MUDgW0PjXeWKWdSoZ0jRTMFN//AhIbZlYynU0YrlW",
+            "$pbkdf2-sha256$i=1000,l=32$c2FsdHNhbHRzYWx0$KBsyyEc464z6vc0vmylEJvYjcmuf+3lVAVWJ5sf4wXM",
+        ];
+
+        for legacy_hash in legacy_hashes {
+            let password = Password::try_from(legacy_hash).unwrap();
+
+            assert_eq!(
+                password.matches(&Secret::new("legacy password".to_string())).unwrap(),
+                MatchResult::MatchesButSchemeOutdated,
+                "{}", legacy_hash
+            );
+            assert_eq!(
+                password.matches(&Secret::new("other password".to_string())).unwrap(),
+                MatchResult::DoesNotMatch,
+                "{}", legacy_hash
+            );
+        }
+    }
+
     #[test]
     fn create_password_from() {
         let salt = get_salt();
@@ -204,4 +392,88 @@ mod tests {
         );
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_policy_rejects_length() {
+        let policy = PasswordPolicy {
+            min_length: 8,
+            max_length: 12,
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(policy.validate(&Secret::new("12345678".to_string()), "user"), Ok(()));
+        assert_eq!(
+            policy.validate(&Secret::new("1234567".to_string()), "user"),
+            Err(vec![PasswordViolation::TooShort { min_length: 8 }])
+        );
+        assert_eq!(
+            policy.validate(&Secret::new("1234567890123".to_string()), "user"),
+            Err(vec![PasswordViolation::TooLong { max_length: 12 }])
+        );
+
+        // characters are counted instead of bytes
+        assert_eq!(policy.validate(&Secret::new("éééééééé".to_string()), "user"), Ok(()));
+
+        // empty passwords are rejected regardless of the minimum length
+        let policy = PasswordPolicy { min_length: 0, ..PasswordPolicy::default() };
+        assert_eq!(
+            policy.validate(&Secret::new(String::new()), "user"),
+            Err(vec![PasswordViolation::TooShort { min_length: 1 }])
+        );
+    }
+
+    #[test]
+    fn test_policy_requires_character_classes() {
+        let policy = PasswordPolicy {
+            required_character_classes: vec![CharacterClass::Uppercase, CharacterClass::Digit, CharacterClass::Symbol],
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(policy.validate(&Secret::new("Correct horse 1".to_string()), "user"), Ok(()));
+        assert_eq!(
+            policy.validate(&Secret::new("correcthorse".to_string()), "user"),
+            Err(vec![
+                PasswordViolation::MissingCharacterClass { character_class: CharacterClass::Uppercase },
+                PasswordViolation::MissingCharacterClass { character_class: CharacterClass::Digit },
+                PasswordViolation::MissingCharacterClass { character_class: CharacterClass::Symbol },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_policy_rejects_username() {
+        let policy = PasswordPolicy::default();
+        assert_eq!(
+            policy.validate(&Secret::new("my-JohnDoe-password".to_string()), "johndoe"),
+            Err(vec![PasswordViolation::ContainsUsername])
+        );
+
+        let policy = PasswordPolicy { reject_username: false, ..PasswordPolicy::default() };
+        assert_eq!(policy.validate(&Secret::new("my-JohnDoe-password".to_string()), "johndoe"), Ok(()));
+    }
+
+    #[test]
+    fn test_policy_rejects_breached_passwords() {
+        let breached_passwords = BreachedPasswords::from_lines("password\r\n123456789\n\nqwertyuiop\n".lines());
+        assert_eq!(breached_passwords.len(), 3);
+
+        let policy = PasswordPolicy {
+            breached_passwords: Some(breached_passwords),
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(
+            policy.validate(&Secret::new("qwertyuiop".to_string()), "user"),
+            Err(vec![PasswordViolation::Breached])
+        );
+        assert_eq!(policy.validate(&Secret::new("qwertyuiop!".to_string()), "user"), Ok(()));
+    }
+
+    #[test]
+    fn test_violations_are_serialized_with_reason() {
+        let violation = PasswordViolation::MissingCharacterClass { character_class: CharacterClass::Digit };
+        assert_eq!(
+            serde_json::to_value(&violation).unwrap(),
+            serde_json::json!({ "reason": "missing_character_class", "character_class": "digit" })
+        );
+    }
+}
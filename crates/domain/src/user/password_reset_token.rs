@@ -0,0 +1,140 @@
+use chrono::{DateTime, Duration, Utc};
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use crate::shared::expiration::Expiration;
+use crate::shared::identified_token::IdentifiedTokenFormat;
+use crate::user::user_id::UserId;
+
+/// The amount of random characters of a reset token, following the id of the token.
+const SECRET_LENGTH: usize = 32;
+
+/// Separates the id of a reset token from its secret.
+const SEPARATOR: char = '.';
+
+const FORMAT: IdentifiedTokenFormat = IdentifiedTokenFormat {
+    prefix: "",
+    separator: SEPARATOR,
+    secret_length: SECRET_LENGTH,
+};
+
+/// PasswordResetToken allows a user who forgot their password to set a new one. The token is
+/// sent to the user by email, can only be used once and expires after a short while.
+/// Only the SHA-256 hash of the token is stored, which is fast to create so that requesting a
+/// reset takes as long for users with an email as for those without. The token is formatted
+/// as `<id>.<secret>`, so it can be found without knowing the user.
+pub struct PasswordResetToken {
+    pub id: Uuid,
+    pub user_id: UserId,
+    hash: Secret<String>,
+    pub created_at: DateTime<Utc>,
+    pub expiration: Expiration,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl PasswordResetToken {
+
+    /// Generates a new reset token for the user, valid for the given lifetime. Returns the token
+    /// to send to the user, together with the reset token to store.
+    pub fn generate(user_id: UserId, lifetime: Duration) -> (Secret<String>, Self) {
+        let now = Utc::now();
+        let id = Uuid::new_v4();
+        let (token, hash) = FORMAT.generate(id);
+
+        let reset_token = PasswordResetToken {
+            id,
+            user_id,
+            hash: Secret::new(hash),
+            created_at: now,
+            expiration: Expiration(now + lifetime),
+            used_at: None,
+        };
+
+        (token, reset_token)
+    }
+
+    pub fn from_hash(
+        id: Uuid,
+        user_id: UserId,
+        hash: Secret<String>,
+        created_at: DateTime<Utc>,
+        expiration: Expiration,
+        used_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            hash,
+            created_at,
+            expiration,
+            used_at,
+        }
+    }
+
+    /// Returns the id of the reset token the submitted token claims to be,
+    /// or None when the token is malformed.
+    pub fn id_of(token: &Secret<String>) -> Option<Uuid> {
+        FORMAT.id_of(token)
+    }
+
+    pub fn hash_string(&self) -> &Secret<String> {
+        &self.hash
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Verifies whether the submitted token is this reset token.
+    /// A used or expired reset token never matches.
+    pub fn matches(&self, submitted_token: &Secret<String>) -> bool {
+        if self.is_used() || self.expiration.has_passed() {
+            return false
+        }
+
+        FORMAT.matches(self.id, self.hash.expose_secret(), submitted_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::{ExposeSecret, Secret};
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let (token, reset_token) = PasswordResetToken::generate(Uuid::new_v4().into(), Duration::hours(1));
+
+        assert_eq!(PasswordResetToken::id_of(&token), Some(reset_token.id));
+        assert!(!reset_token.hash_string().expose_secret().contains(token.expose_secret().as_str()));
+        assert!(!reset_token.expiration.has_passed());
+        assert!(reset_token.expiration.has_passed_at(Utc::now() + Duration::hours(2)));
+    }
+
+    #[test]
+    fn test_matches() {
+        let user_id = Uuid::new_v4().into();
+        let (token, mut reset_token) = PasswordResetToken::generate(user_id, Duration::hours(1));
+        let (other_token, _) = PasswordResetToken::generate(user_id, Duration::hours(1));
+
+        assert!(reset_token.matches(&token));
+        assert!(!reset_token.matches(&other_token));
+        assert!(!reset_token.matches(&Secret::new("not a token".to_string())));
+
+        // the secret of the token only matches with its own id
+        let (_, secret) = token.expose_secret().split_once(SEPARATOR).unwrap();
+        let forged_token = Secret::new(format!("{}{}{}", Uuid::new_v4().simple(), SEPARATOR, secret));
+        assert!(!reset_token.matches(&forged_token));
+
+        reset_token.used_at = Some(Utc::now());
+        assert!(!reset_token.matches(&token));
+    }
+
+    #[test]
+    fn test_expired_token_does_not_match() {
+        let (token, reset_token) = PasswordResetToken::generate(Uuid::new_v4().into(), Duration::seconds(-1));
+        assert!(!reset_token.matches(&token));
+    }
+}
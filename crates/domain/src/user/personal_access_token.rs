@@ -0,0 +1,248 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, Utc};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::shared::expiration::Expiration;
+use crate::shared::identified_token::IdentifiedTokenFormat;
+use crate::user::user_id::UserId;
+
+/// Every personal access token starts with this prefix, so they can be told apart from
+/// session tokens and can be recognized by secret scanners when leaked.
+pub const TOKEN_PREFIX: &str = "pat_";
+
+/// The amount of random characters of a token, following the id of the token.
+const SECRET_LENGTH: usize = 40;
+
+/// Separates the id of a token from its secret.
+const SEPARATOR: char = '_';
+
+const FORMAT: IdentifiedTokenFormat = IdentifiedTokenFormat {
+    prefix: TOKEN_PREFIX,
+    separator: SEPARATOR,
+    secret_length: SECRET_LENGTH,
+};
+
+/// The amount of characters of the id shown to identify a token.
+const VISIBLE_ID_LENGTH: usize = 8;
+
+pub type NameOfScope = &'static str;
+pub const SCOPE_READ: NameOfScope = "read";
+pub const SCOPE_WRITE: NameOfScope = "write";
+
+/// Scope limits what a personal access token can be used for. Access tokens of sessions have every scope.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Read allows reading data.
+    Read,
+
+    /// Write allows modifying data.
+    Write,
+}
+
+impl Scope {
+    pub fn name(&self) -> NameOfScope {
+        match self {
+            Scope::Read => SCOPE_READ,
+            Scope::Write => SCOPE_WRITE,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            SCOPE_READ => Some(Scope::Read),
+            SCOPE_WRITE => Some(Scope::Write),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// PersonalAccessToken is a long-lived token of a user for scripts and automation, which is used
+/// without going through the refresh flow. It only carries its scopes and can be revoked at any time.
+/// Only the hash of the token is stored. The token is formatted as `pat_<id>_<secret>`,
+/// so it can be found without knowing the user.
+#[derive(Debug, Clone)]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    hash: String,
+    pub created_at: DateTime<Utc>,
+
+    /// expiration is None for tokens which never expire.
+    pub expiration: Option<Expiration>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl PersonalAccessToken {
+
+    /// Generates a new token for the user, which expires after the lifetime if given.
+    /// Returns the token to hand to the user, together with the personal access token to store,
+    /// or None when the lifetime is too long to represent its expiration.
+    pub fn generate(
+        user_id: UserId,
+        name: String,
+        scopes: Vec<Scope>,
+        lifetime: Option<Duration>,
+    ) -> Option<(Secret<String>, Self)> {
+        let now = Utc::now();
+        let expiration = match lifetime {
+            Some(lifetime) => Some(Expiration(now.checked_add_signed(lifetime)?)),
+            None => None,
+        };
+
+        let id = Uuid::new_v4();
+        let (token, hash) = FORMAT.generate(id);
+
+        let personal_access_token = PersonalAccessToken {
+            id,
+            user_id,
+            name,
+            scopes,
+            hash,
+            created_at: now,
+            expiration,
+            revoked_at: None,
+        };
+
+        Some((token, personal_access_token))
+    }
+
+    /// Creates a personal access token from its stored hash, which is not revoked
+    /// until `revoked_at` is set.
+    pub fn from_hash(
+        id: Uuid,
+        user_id: UserId,
+        name: String,
+        scopes: Vec<Scope>,
+        hash: String,
+        created_at: DateTime<Utc>,
+        expiration: Option<Expiration>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            scopes,
+            hash,
+            created_at,
+            expiration,
+            revoked_at: None,
+        }
+    }
+
+    /// Returns whether the submitted token is formatted as a personal access token.
+    pub fn is_personal_access_token(token: &Secret<String>) -> bool {
+        FORMAT.is_formatted(token)
+    }
+
+    /// Returns the id of the personal access token the submitted token claims to be,
+    /// or None when the token is malformed.
+    pub fn id_of(token: &Secret<String>) -> Option<Uuid> {
+        FORMAT.id_of(token)
+    }
+
+    /// Returns the start of the token, by which users can recognize the token without revealing it.
+    pub fn prefix(&self) -> String {
+        let id = self.id.simple().to_string();
+        format!("{}{}", TOKEN_PREFIX, &id[..VISIBLE_ID_LENGTH])
+    }
+
+    pub fn hash_string(&self) -> &str {
+        &self.hash
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.is_revoked() && !self.expiration.is_some_and(|expiration| expiration.has_passed())
+    }
+
+    /// Verifies whether the submitted token is this personal access token.
+    /// A revoked or expired token never matches.
+    pub fn matches(&self, submitted_token: &Secret<String>) -> bool {
+        self.is_active() && FORMAT.matches(self.id, &self.hash, submitted_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::{ExposeSecret, Secret};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn generate(lifetime: Option<Duration>) -> (Secret<String>, PersonalAccessToken) {
+        PersonalAccessToken::generate(Uuid::new_v4().into(), "ci".to_string(), vec![Scope::Read], lifetime)
+            .expect("Failed to generate personal access token")
+    }
+
+    #[test]
+    fn test_generate() {
+        let (token, personal_access_token) = generate(None);
+
+        assert!(PersonalAccessToken::is_personal_access_token(&token));
+        assert_eq!(PersonalAccessToken::id_of(&token), Some(personal_access_token.id));
+        assert!(token.expose_secret().starts_with(&personal_access_token.prefix()));
+        assert_eq!(personal_access_token.prefix().len(), TOKEN_PREFIX.len() + VISIBLE_ID_LENGTH);
+        assert!(!personal_access_token.hash_string().contains(token.expose_secret().as_str()));
+        assert!(personal_access_token.has_scope(Scope::Read));
+        assert!(!personal_access_token.has_scope(Scope::Write));
+    }
+
+    #[test]
+    fn test_matches() {
+        let (token, mut personal_access_token) = generate(Some(Duration::days(1)));
+        let (other_token, _) = generate(None);
+
+        assert!(personal_access_token.matches(&token));
+        assert!(!personal_access_token.matches(&other_token));
+        assert!(!personal_access_token.matches(&Secret::new("not a token".to_string())));
+
+        // the secret of the token only matches with its own id
+        let (_, secret) = token.expose_secret().strip_prefix(TOKEN_PREFIX).unwrap().split_once(SEPARATOR).unwrap();
+        let forged_token = Secret::new(format!("{}{}{}{}", TOKEN_PREFIX, Uuid::new_v4().simple(), SEPARATOR, secret));
+        assert!(!personal_access_token.matches(&forged_token));
+
+        personal_access_token.revoked_at = Some(Utc::now());
+        assert!(!personal_access_token.matches(&token));
+    }
+
+    #[test]
+    fn test_expired_token_does_not_match() {
+        let (token, personal_access_token) = generate(Some(Duration::seconds(-1)));
+        assert!(!personal_access_token.matches(&token));
+    }
+
+    #[test]
+    fn test_generate_rejects_lifetime_which_overflows() {
+        let lifetime = Some(Duration::days(u32::MAX.into()));
+        let token = PersonalAccessToken::generate(Uuid::new_v4().into(), "ci".to_string(), vec![Scope::Read], lifetime);
+        assert!(token.is_none());
+    }
+
+    #[test]
+    fn test_scope_names() {
+        for scope in [Scope::Read, Scope::Write] {
+            assert_eq!(Scope::from_name(scope.name()), Some(scope));
+            assert_eq!(serde_json::to_string(&scope).unwrap(), format!("\"{}\"", scope));
+        }
+        assert_eq!(Scope::from_name("admin"), None);
+    }
+}
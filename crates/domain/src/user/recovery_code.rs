@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use password_hash::{PasswordHash, SaltString};
+use rand::Rng;
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use security::hash::error::Error;
+use security::hash::scheme::{get_latest_scheme, get_scheme, Scheme};
+
+use crate::user::password::MatchError;
+use crate::user::user_id::UserId;
+
+/// The amount of recovery codes a user receives at once.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// The amount of characters of a recovery code, excluding the separator.
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// Characters which cannot be mistaken for one another when written down.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// RecoveryCode is a single-use code a user with two-factor authentication can enter instead of
+/// a one-time password, e.g. after losing their phone. Only the hash of the code is stored,
+/// which is created with the same hash scheme as passwords.
+pub struct RecoveryCode {
+    pub id: Uuid,
+    pub user_id: UserId,
+    hash: Secret<String>,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl RecoveryCode {
+
+    /// Generates a new set of recovery codes for the user. Returns the codes to show to the user
+    /// once, together with the recovery codes to store.
+    /// Note: this is an expensive operation i.e. can block the event loop.
+    pub fn generate(user_id: UserId) -> password_hash::Result<(Vec<Secret<String>>, Vec<RecoveryCode>)> {
+        let now = Utc::now();
+        let scheme = get_latest_scheme();
+
+        let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        let mut recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        for _ in 0..RECOVERY_CODE_COUNT {
+            let code = random_code();
+            let salt = SaltString::generate(&mut rand::thread_rng());
+            let hash = scheme.hash(normalize(&code), &salt)?;
+
+            recovery_codes.push(RecoveryCode {
+                id: Uuid::new_v4(),
+                user_id,
+                hash: Secret::new(hash.to_string()),
+                created_at: now,
+                used_at: None,
+            });
+            codes.push(code);
+        }
+
+        Ok((codes, recovery_codes))
+    }
+
+    pub fn from_hash(
+        id: Uuid,
+        user_id: UserId,
+        hash: Secret<String>,
+        created_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> password_hash::Result<Self> {
+        PasswordHash::new(hash.expose_secret())?;
+
+        Ok(Self {
+            id,
+            user_id,
+            hash,
+            created_at,
+            used_at,
+        })
+    }
+
+    pub fn hash_string(&self) -> &Secret<String> {
+        &self.hash
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+
+    /// Verifies whether the submitted code is this recovery code, ignoring casing, whitespace and
+    /// separators. A used recovery code never matches.
+    /// Note: this is an expensive operation i.e. can block the event loop.
+    pub fn matches(&self, submitted_code: &Secret<String>) -> Result<bool, MatchError> {
+        if self.is_used() {
+            return Ok(false)
+        }
+
+        let hash = PasswordHash::new(self.hash.expose_secret())
+            .map_err(MatchError::ErrorWhileMatching)?;
+        let scheme = get_scheme(&hash.algorithm)
+            .ok_or(MatchError::NoHashSchemeForPassword(hash.algorithm.to_string()))?;
+
+        match scheme.validate(&normalize(submitted_code), &hash) {
+            Ok(_) => Ok(true),
+            Err(Error::PasswordInvalid) => Ok(false),
+            Err(Error::Other(e)) => Err(MatchError::ErrorWhileMatching(e)),
+        }
+    }
+}
+
+/// Returns a random code formatted as two groups of five characters, e.g. `ab3de-fg7hj`.
+fn random_code() -> Secret<String> {
+    let mut rng = rand::thread_rng();
+    let mut code = String::with_capacity(RECOVERY_CODE_LENGTH + 1);
+    for i in 0..RECOVERY_CODE_LENGTH {
+        if i == RECOVERY_CODE_LENGTH / 2 {
+            code.push('-');
+        }
+
+        let index = rng.gen_range(0..RECOVERY_CODE_ALPHABET.len());
+        code.push(RECOVERY_CODE_ALPHABET[index] as char);
+    }
+
+    Secret::new(code)
+}
+
+fn normalize(code: &Secret<String>) -> Secret<String> {
+    Secret::new(
+        code.expose_secret()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::{ExposeSecret, Secret};
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let (codes, recovery_codes) = RecoveryCode::generate(Uuid::new_v4().into()).unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(recovery_codes.len(), RECOVERY_CODE_COUNT);
+
+        let code = codes[0].expose_secret();
+        assert_eq!(code.len(), RECOVERY_CODE_LENGTH + 1);
+        assert_eq!(code.chars().nth(RECOVERY_CODE_LENGTH / 2), Some('-'));
+        assert!(!recovery_codes[0].hash_string().expose_secret().contains(code.as_str()));
+    }
+
+    #[test]
+    fn test_matches() {
+        let (codes, mut recovery_codes) = RecoveryCode::generate(Uuid::new_v4().into()).unwrap();
+        let recovery_code = &mut recovery_codes[0];
+
+        assert_eq!(recovery_code.matches(&codes[0]), Ok(true));
+        assert_eq!(recovery_code.matches(&codes[1]), Ok(false));
+
+        // codes may be entered without separator and in upper case
+        let entered_code = codes[0].expose_secret().replace('-', " ").to_uppercase();
+        assert_eq!(recovery_code.matches(&Secret::new(entered_code)), Ok(true));
+
+        recovery_code.used_at = Some(Utc::now());
+        assert_eq!(recovery_code.matches(&codes[0]), Ok(false));
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use rand::distributions::{Alphanumeric, DistString};
+use secrecy::Secret;
+use uuid::Uuid;
+
+use security::hash::token_hash::{hash_token, token_matches};
+
+use crate::user::user_id::UserId;
+
+/// Every client id of a service account starts with this prefix, so they can be told apart from
+/// the ids of other clients.
+pub const CLIENT_ID_PREFIX: &str = "sa_";
+
+/// The amount of random characters of a client secret.
+const CLIENT_SECRET_LENGTH: usize = 40;
+
+/// ServiceAccount is a non-human user, e.g. another service, which obtains access tokens with the
+/// OAuth2 client credentials grant instead of logging in. It is a user like any other, so it can
+/// be a member of teams and have a system role, but it has no password nor sessions.
+/// Only the hash of the client secret is stored, the secret is random so a fast hash suffices.
+#[derive(Debug, Clone)]
+pub struct ServiceAccount {
+    pub user_id: UserId,
+    pub client_id: String,
+    secret_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ServiceAccount {
+
+    /// Generates the client credentials of a new service account for the user.
+    /// Returns the client secret to hand to the service, together with the service account to store.
+    pub fn generate(user_id: UserId) -> (Secret<String>, Self) {
+        let client_secret = Secret::new(Alphanumeric.sample_string(&mut rand::thread_rng(), CLIENT_SECRET_LENGTH));
+
+        let service_account = ServiceAccount {
+            user_id,
+            client_id: format!("{}{}", CLIENT_ID_PREFIX, Uuid::new_v4().simple()),
+            secret_hash: hash_token(&client_secret),
+            created_at: Utc::now(),
+        };
+
+        (client_secret, service_account)
+    }
+
+    pub fn from_hash(
+        user_id: UserId,
+        client_id: String,
+        secret_hash: String,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            user_id,
+            client_id,
+            secret_hash,
+            created_at,
+        }
+    }
+
+    pub fn secret_hash_string(&self) -> &str {
+        &self.secret_hash
+    }
+
+    /// Verifies whether the submitted client secret is the secret of this service account.
+    pub fn secret_matches(&self, submitted_secret: &Secret<String>) -> bool {
+        token_matches(submitted_secret, &self.secret_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::{ExposeSecret, Secret};
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let (client_secret, service_account) = ServiceAccount::generate(Uuid::new_v4().into());
+        let (other_client_secret, other_service_account) = ServiceAccount::generate(Uuid::new_v4().into());
+
+        assert!(service_account.client_id.starts_with(CLIENT_ID_PREFIX));
+        assert_ne!(service_account.client_id, other_service_account.client_id);
+        assert_eq!(client_secret.expose_secret().len(), CLIENT_SECRET_LENGTH);
+        assert!(!service_account.secret_hash_string().contains(client_secret.expose_secret().as_str()));
+
+        assert!(service_account.secret_matches(&client_secret));
+        assert!(!service_account.secret_matches(&other_client_secret));
+        assert!(!service_account.secret_matches(&Secret::new(String::new())));
+    }
+}
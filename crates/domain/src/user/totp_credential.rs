@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+
+use security::otp::totp::TotpSecret;
+
+use crate::user::user_id::UserId;
+
+/// TotpCredential is the TOTP secret a user shares with their authenticator app. A credential
+/// only protects the account once it has been confirmed with a first code, which proves that
+/// the authenticator app of the user was set up correctly.
+#[derive(Debug, Clone)]
+pub struct TotpCredential {
+    pub user_id: UserId,
+    pub secret: TotpSecret,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+
+    /// The time step of the last accepted code. Codes of this or earlier time steps are rejected,
+    /// so that a code that got observed cannot be used again.
+    pub last_used_step: Option<i64>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum Error {
+    #[error("The one-time password is invalid")]
+    InvalidCode,
+
+    #[error("The one-time password has already been used")]
+    CodeAlreadyUsed,
+
+    #[error("Two-factor authentication has already been confirmed")]
+    AlreadyConfirmed,
+}
+
+impl TotpCredential {
+
+    /// Creates an unconfirmed credential with a newly generated secret.
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            user_id,
+            secret: TotpSecret::generate(),
+            created_at: Utc::now(),
+            confirmed_at: None,
+            last_used_step: None,
+        }
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    /// Confirms the credential with the first code of the authenticator app of the user.
+    pub fn confirm(&mut self, code: &str, now: DateTime<Utc>) -> Result<(), Error> {
+        if self.is_confirmed() {
+            return Err(Error::AlreadyConfirmed)
+        }
+
+        self.verify(code, now)?;
+        self.confirmed_at = Some(now);
+        Ok(())
+    }
+
+    /// Verifies the code and records its time step, so that it cannot be used again.
+    pub fn verify(&mut self, code: &str, now: DateTime<Utc>) -> Result<(), Error> {
+        let step = self.secret.verify(code, now).ok_or(Error::InvalidCode)?;
+
+        if self.last_used_step.is_some_and(|last_used_step| step <= last_used_step) {
+            return Err(Error::CodeAlreadyUsed)
+        }
+
+        self.last_used_step = Some(step);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_confirm() {
+        let mut credential = TotpCredential::new(Uuid::new_v4().into());
+        let now = Utc::now();
+        assert!(!credential.is_confirmed());
+
+        assert_eq!(credential.confirm("000000x", now), Err(Error::InvalidCode));
+        assert!(!credential.is_confirmed());
+
+        let code = credential.secret.code_at(now);
+        assert_eq!(credential.confirm(&code, now), Ok(()));
+        assert!(credential.is_confirmed());
+
+        let code = credential.secret.code_at(now + Duration::seconds(30));
+        assert_eq!(credential.confirm(&code, now + Duration::seconds(30)), Err(Error::AlreadyConfirmed));
+    }
+
+    #[test]
+    fn test_verify_rejects_used_codes() {
+        let mut credential = TotpCredential::new(Uuid::new_v4().into());
+        let now = Utc::now();
+
+        let code = credential.secret.code_at(now);
+        assert_eq!(credential.verify(&code, now), Ok(()));
+        assert_eq!(credential.verify(&code, now), Err(Error::CodeAlreadyUsed));
+
+        // the code of the previous time step is still valid, but older than the code that was used
+        let previous_code = credential.secret.code_at(now - Duration::seconds(30));
+        assert_eq!(credential.verify(&previous_code, now), Err(Error::CodeAlreadyUsed));
+
+        let next_code = credential.secret.code_at(now + Duration::seconds(30));
+        assert_eq!(credential.verify(&next_code, now + Duration::seconds(30)), Ok(()));
+    }
+}
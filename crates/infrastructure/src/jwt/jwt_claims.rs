@@ -0,0 +1,172 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use security::token::token::Token;
+
+use crate::jwt::jwt_error::{JwtDecryptionError, JwtEncryptionError};
+
+/// JwtClaims are the claims of a JWT, named like the claims in `PasetoClaims`.
+/// The time based claims are NumericDates, seconds since the epoch, as required by the JWT spec.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JwtClaims {
+    pub jti: String,
+    pub sub: String,
+    pub aud: String,
+    pub iss: String,
+    pub exp: i64,
+    pub nbf: i64,
+    pub iat: i64,
+
+    /// data contains the custom claims of the token.
+    pub data: Value,
+}
+
+impl JwtClaims {
+
+    /// Returns the claims of the given token.
+    pub(crate) fn of<'a, T: Token<'a>>(token: &'a T) -> Result<Self, JwtEncryptionError> {
+        Ok(Self {
+            jti: token.get_id().to_string(),
+            sub: token.get_subject().to_string(),
+            aud: token.get_audience().to_string(),
+            iss: token.get_issuer().to_string(),
+            exp: token.get_expiration().timestamp(),
+            nbf: token.get_not_before().timestamp(),
+            iat: token.get_issued_at().timestamp(),
+            data: serde_json::to_value(token.get_custom_claims())?,
+        })
+    }
+
+    /// Creates the token from the claims, after validating them against the expected issuer and audience.
+    pub(crate) fn into_token<'a, T: Token<'a>>(
+        self,
+        expected_issuer: &str,
+        expected_audience: &str,
+        validate_expiration: bool
+    ) -> Result<T, JwtDecryptionError> {
+        if self.iss != expected_issuer {
+            return Err(JwtDecryptionError::UnexpectedIssuer)
+        }
+
+        if self.aud != expected_audience {
+            return Err(JwtDecryptionError::UnexpectedAudience)
+        }
+
+        let id = Uuid::try_parse(self.jti.as_str())?;
+        let expiration = from_numeric_date(self.exp)?;
+        let not_before = from_numeric_date(self.nbf)?;
+        let issued_at = from_numeric_date(self.iat)?;
+
+        let now = Utc::now();
+        if now < not_before || now < issued_at {
+            return Err(JwtDecryptionError::TokenNotYetActive)
+        }
+
+        if validate_expiration && now > expiration {
+            return Err(JwtDecryptionError::TokenExpired)
+        }
+
+        let custom_claims: T::CustomClaims = serde_json::from_value(self.data)
+            .map_err(JwtDecryptionError::UnexpectedCustomClaims)?;
+
+        Ok(T::new(
+            id,
+            self.sub,
+            self.aud,
+            self.iss,
+            expiration,
+            not_before,
+            issued_at,
+            custom_claims
+        ))
+    }
+}
+
+fn from_numeric_date(seconds: i64) -> Result<DateTime<Utc>, JwtDecryptionError> {
+    DateTime::from_timestamp(seconds, 0).ok_or(JwtDecryptionError::InvalidTimestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    use domain::sessions::tokens::AccessToken;
+    use domain::sessions::user_session_token::UserSessionToken;
+    use security::token::token::Token;
+
+    use crate::jwt::jwt_claims::JwtClaims;
+    use crate::jwt::jwt_error::JwtDecryptionError;
+    use crate::test_token::{access_token, AUDIENCE, ISSUER};
+
+    fn valid_claims() -> JwtClaims {
+        JwtClaims::of(&access_token(Duration::minutes(5))).expect("Failed to get claims of token")
+    }
+
+    fn into_token(claims: JwtClaims, validate_expiration: bool) -> Result<UserSessionToken<AccessToken>, JwtDecryptionError> {
+        claims.into_token(ISSUER, AUDIENCE, validate_expiration)
+    }
+
+    #[test]
+    fn test_claims_round_trip() {
+        let token = access_token(Duration::minutes(5));
+        let claims = JwtClaims::of(&token).unwrap();
+
+        let decoded = into_token(claims, true).unwrap();
+        assert_eq!(decoded.get_id(), token.get_id());
+        assert_eq!(decoded.get_subject(), token.get_subject());
+        assert_eq!(decoded.get_custom_claims(), token.get_custom_claims());
+        // NumericDates only have a precision of seconds
+        assert_eq!(decoded.get_expiration().timestamp(), token.get_expiration().timestamp());
+    }
+
+    #[test]
+    fn test_unexpected_issuer_and_audience_are_rejected() {
+        let mut claims = valid_claims();
+        claims.iss = "other issuer".to_string();
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::UnexpectedIssuer)));
+
+        let mut claims = valid_claims();
+        claims.aud = "other audience".to_string();
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::UnexpectedAudience)));
+    }
+
+    #[test]
+    fn test_tokens_which_are_not_yet_active_are_rejected() {
+        let in_a_minute = (Utc::now() + Duration::minutes(1)).timestamp();
+
+        let mut claims = valid_claims();
+        claims.nbf = in_a_minute;
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::TokenNotYetActive)));
+
+        let mut claims = valid_claims();
+        claims.iat = in_a_minute;
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::TokenNotYetActive)));
+    }
+
+    #[test]
+    fn test_expired_tokens_are_only_rejected_when_validating_expiration() {
+        let mut claims = valid_claims();
+        claims.exp = (Utc::now() - Duration::seconds(1)).timestamp();
+
+        assert!(matches!(into_token(claims.clone(), true), Err(JwtDecryptionError::TokenExpired)));
+        assert!(into_token(claims, false).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_claims_are_rejected() {
+        let mut claims = valid_claims();
+        claims.exp = i64::MAX;
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::InvalidTimestamp)));
+
+        let mut claims = valid_claims();
+        claims.jti = "not a uuid".to_string();
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::CannotParseIdentifier(_))));
+
+        let mut claims = valid_claims();
+        claims.data = json!({ "user_id": "not a uuid" });
+        assert!(matches!(into_token(claims, true), Err(JwtDecryptionError::UnexpectedCustomClaims(_))));
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt::{Debug, Formatter};
+
+use lib_util::errors::errors::format_error_chain;
+
+#[derive(thiserror::Error)]
+pub enum JwtEncryptionError {
+    #[error(transparent)]
+    JwtError(#[from] jsonwebtoken::errors::Error),
+
+    #[error(transparent)]
+    SerializeError(#[from] serde_json::Error),
+}
+
+impl Debug for JwtEncryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
+
+#[derive(thiserror::Error)]
+pub enum JwtDecryptionError {
+
+    #[error("Token validation failed because token `not before` > now")]
+    TokenNotYetActive,
+
+    #[error("Token validation failed because token `expiration` < now")]
+    TokenExpired,
+
+    #[error("Token validation failed because token `issuer` is not the expected issuer")]
+    UnexpectedIssuer,
+
+    #[error("Token validation failed because token `audience` is not the expected audience")]
+    UnexpectedAudience,
+
+    #[error("Token validation failed because the token was signed by an unknown key")]
+    UnknownKeyId,
+
+    #[error("Token validation failed because the signature does not match the token")]
+    InvalidSignature,
+
+    #[error("Token claims contain a timestamp that is out of range")]
+    InvalidTimestamp,
+
+    #[error("Token was verified successfully but its custom claims are not those of the expected token")]
+    UnexpectedCustomClaims(#[source] serde_json::Error),
+
+    #[error(transparent)]
+    CannotParseIdentifier(#[from] uuid::Error),
+
+    #[error(transparent)]
+    JwtError(#[from] jsonwebtoken::errors::Error),
+
+    #[error(transparent)]
+    SerializeError(#[from] serde_json::Error),
+}
+
+impl Debug for JwtDecryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
@@ -0,0 +1,272 @@
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use pasetors::keys::{AsymmetricPublicKey, AsymmetricSecretKey};
+use pasetors::paserk::Id;
+use pasetors::version4::V4;
+use secrecy::{ExposeSecret, Secret};
+
+use security::encryption::decryptor::Decryptor;
+use security::encryption::encryptor::Encryptor;
+use security::token::token::Token;
+use security::token::token_encryptor::{EncryptedToken, TokenEncryptor};
+
+use crate::jwt::jwt_claims::JwtClaims;
+use crate::jwt::jwt_error::{JwtDecryptionError, JwtEncryptionError};
+use crate::paseto::paserk::format_paserk;
+use crate::paseto::public_paseto_token_signer::PaserkPublicKey;
+
+/// The PKCS#8 v1 DER prefix of an Ed25519 private key, which is followed by its 32 byte seed.
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20
+];
+
+/// Signs tokens as JWT, for systems that do not support PASETO. Tokens are either signed with
+/// a shared secret (HS256) or with an Ed25519 key (EdDSA), in which case the PASERK id of the
+/// public key is stored as `kid` in the header, like `PublicPasetoV4TokenSigner` does in the footer.
+#[derive(Clone)]
+pub struct JwtTokenEncryptor {
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    key_id: Option<String>,
+
+    /// The keys of which the signed tokens are accepted, by their key id.
+    decoding_keys: Vec<(Option<String>, DecodingKey)>,
+    public_keys: Vec<PaserkPublicKey>,
+
+    /// The issuer that decoded tokens must have been issued by.
+    pub issuer: String,
+
+    /// The audience that decoded tokens must have been issued for.
+    pub audience: String,
+}
+
+impl JwtTokenEncryptor {
+
+    /// Creates an encryptor that signs and verifies tokens with the given secret, using HS256.
+    pub fn hs256(secret: &Secret<String>, issuer: String, audience: String) -> Self {
+        let secret = secret.expose_secret().as_bytes();
+
+        Self {
+            algorithm: Algorithm::HS256,
+            encoding_key: EncodingKey::from_secret(secret),
+            key_id: None,
+            decoding_keys: vec![(None, DecodingKey::from_secret(secret))],
+            public_keys: vec![],
+            issuer,
+            audience,
+        }
+    }
+
+    /// Creates an encryptor that signs tokens with the given Ed25519 key, using EdDSA. Tokens signed
+    /// by the signing key or by any of the verification keys are accepted.
+    pub fn eddsa(
+        signing_key: &AsymmetricSecretKey<V4>,
+        verification_keys: &[AsymmetricPublicKey<V4>],
+        issuer: String,
+        audience: String
+    ) -> Result<Self, pasetors::errors::Error> {
+        let public_key = AsymmetricPublicKey::<V4>::try_from(signing_key)?;
+
+        // the secret key consists of the 32 byte seed followed by the public key
+        let mut pkcs8 = ED25519_PKCS8_PREFIX.to_vec();
+        pkcs8.extend_from_slice(&signing_key.as_bytes()[..32]);
+
+        let mut public_keys = vec![];
+        let mut decoding_keys = vec![];
+        for key in std::iter::once(&public_key).chain(verification_keys) {
+            let key_id = format_paserk(&Id::from(key))?;
+            decoding_keys.push((Some(key_id.clone()), DecodingKey::from_ed_der(key.as_bytes())));
+            public_keys.push(PaserkPublicKey {
+                kid: key_id,
+                key: format_paserk(key)?,
+            });
+        }
+
+        Ok(Self {
+            algorithm: Algorithm::EdDSA,
+            encoding_key: EncodingKey::from_ed_der(&pkcs8),
+            key_id: Some(format_paserk(&Id::from(&public_key))?),
+            decoding_keys,
+            public_keys,
+            issuer,
+            audience,
+        })
+    }
+
+    /// Returns every public key of which the signed tokens are accepted, formatted as PASERK.
+    /// There are none when the tokens are signed with a shared secret.
+    pub fn public_keys(&self) -> Vec<PaserkPublicKey> {
+        self.public_keys.clone()
+    }
+
+    /// Decodes the token like `decrypt`, except that tokens of which the expiration has passed
+    /// are not rejected. Should only be used to inspect expired tokens, never to authenticate.
+    pub fn decrypt_ignoring_expiration<'a, T: Token<'a>>(
+        &self,
+        signed_token: &Secret<String>
+    ) -> Result<T, JwtDecryptionError> {
+        self.decode_token(signed_token, false)
+    }
+
+    fn decode_token<'a, T: Token<'a>>(
+        &self,
+        signed_token: &Secret<String>,
+        validate_expiration: bool
+    ) -> Result<T, JwtDecryptionError> {
+        let header = decode_header(signed_token.expose_secret())?;
+
+        let (_, decoding_key) = self.decoding_keys.iter()
+            .find(|(key_id, _)| *key_id == header.kid)
+            .ok_or(JwtDecryptionError::UnknownKeyId)?;
+
+        // The claims are validated by JwtClaims, to tell why the validation failed.
+        let mut validation = Validation::new(self.algorithm);
+        validation.validate_exp = false;
+        validation.validate_nbf = false;
+        validation.validate_aud = false;
+        validation.required_spec_claims.clear();
+
+        let claims = decode::<JwtClaims>(signed_token.expose_secret(), decoding_key, &validation)
+            .map_err(|e| match e.kind() {
+                ErrorKind::InvalidSignature => JwtDecryptionError::InvalidSignature,
+                _ => e.into(),
+            })?
+            .claims;
+        claims.into_token(&self.issuer, &self.audience, validate_expiration)
+    }
+}
+
+impl<'a, T: Token<'a>> Encryptor<'a, T, EncryptedToken> for JwtTokenEncryptor {
+    type EncryptionError = JwtEncryptionError;
+
+    fn encrypt(&self, token: &'a T) -> Result<EncryptedToken, Self::EncryptionError> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.key_id.clone();
+
+        let signed_token = encode(&header, &JwtClaims::of(token)?, &self.encoding_key)?;
+
+        Ok(EncryptedToken {
+            token: Secret::new(signed_token),
+            expires_at: *token.get_expiration()
+        })
+    }
+}
+
+impl<'a, T: Token<'a>> Decryptor<T> for JwtTokenEncryptor {
+    type DecryptionError = JwtDecryptionError;
+
+    fn decrypt(&self, signed_token: &Secret<String>) -> Result<T, Self::DecryptionError> {
+        self.decode_token(signed_token, true)
+    }
+}
+
+impl <'a, T: Token<'a>> TokenEncryptor<'a, T> for JwtTokenEncryptor {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use jsonwebtoken::decode_header;
+    use pasetors::keys::{AsymmetricKeyPair, Generate};
+    use pasetors::version4::V4;
+    use secrecy::{ExposeSecret, Secret};
+
+    use domain::sessions::tokens::AccessToken;
+    use domain::sessions::user_session_token::UserSessionToken;
+    use security::encryption::decryptor::Decryptor;
+    use security::encryption::encryptor::Encryptor;
+    use security::token::token::Token;
+
+    use crate::jwt::jwt_error::JwtDecryptionError;
+    use crate::jwt::jwt_token_encryptor::JwtTokenEncryptor;
+    use crate::test_token::{access_token, AUDIENCE, ISSUER};
+
+    fn hs256(secret: &str) -> JwtTokenEncryptor {
+        JwtTokenEncryptor::hs256(&Secret::new(secret.to_string()), ISSUER.to_string(), AUDIENCE.to_string())
+    }
+
+    fn eddsa(key_pair: &AsymmetricKeyPair<V4>, previous_key_pairs: &[&AsymmetricKeyPair<V4>]) -> JwtTokenEncryptor {
+        let verification_keys = previous_key_pairs.iter()
+            .map(|key_pair| key_pair.public.clone())
+            .collect::<Vec<_>>();
+
+        JwtTokenEncryptor::eddsa(&key_pair.secret, &verification_keys, ISSUER.to_string(), AUDIENCE.to_string())
+            .expect("Failed to create eddsa encryptor")
+    }
+
+    fn generate_key_pair() -> AsymmetricKeyPair<V4> {
+        AsymmetricKeyPair::<V4>::generate().expect("Failed to generate key pair")
+    }
+
+    fn decrypt(encryptor: &JwtTokenEncryptor, token: &Secret<String>) -> Result<UserSessionToken<AccessToken>, JwtDecryptionError> {
+        encryptor.decrypt(token)
+    }
+
+    #[test]
+    fn test_hs256_round_trip() {
+        let encryptor = hs256("a secret of at least thirty-two bytes");
+        let token = access_token(Duration::minutes(5));
+
+        let signed_token = encryptor.encrypt(&token).unwrap().token;
+        assert_eq!(decrypt(&encryptor, &signed_token).unwrap().get_id(), token.get_id());
+        assert!(encryptor.public_keys().is_empty());
+    }
+
+    #[test]
+    fn test_hs256_tokens_signed_with_other_secret_are_rejected() {
+        let token = access_token(Duration::minutes(5));
+        let signed_token = hs256("a secret of at least thirty-two bytes").encrypt(&token).unwrap().token;
+
+        let other_encryptor = hs256("another secret of at least thirty-two bytes");
+        assert!(matches!(decrypt(&other_encryptor, &signed_token), Err(JwtDecryptionError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_eddsa_round_trip() {
+        let encryptor = eddsa(&generate_key_pair(), &[]);
+        let token = access_token(Duration::minutes(5));
+
+        // decoding with the public key verifies the private key was encoded with the right PKCS#8 prefix
+        let signed_token = encryptor.encrypt(&token).unwrap().token;
+        assert_eq!(decrypt(&encryptor, &signed_token).unwrap().get_id(), token.get_id());
+
+        let header = decode_header(signed_token.expose_secret()).unwrap();
+        assert_eq!(header.kid, Some(encryptor.public_keys()[0].kid.clone()));
+    }
+
+    #[test]
+    fn test_eddsa_tokens_of_verification_keys_are_accepted() {
+        let previous_key_pair = generate_key_pair();
+        let token = access_token(Duration::minutes(5));
+        let signed_token = eddsa(&previous_key_pair, &[]).encrypt(&token).unwrap().token;
+
+        let rotated_encryptor = eddsa(&generate_key_pair(), &[&previous_key_pair]);
+        assert_eq!(decrypt(&rotated_encryptor, &signed_token).unwrap().get_id(), token.get_id());
+        assert_eq!(rotated_encryptor.public_keys().len(), 2);
+
+        let other_encryptor = eddsa(&generate_key_pair(), &[]);
+        assert!(matches!(decrypt(&other_encryptor, &signed_token), Err(JwtDecryptionError::UnknownKeyId)));
+    }
+
+    #[test]
+    fn test_tokens_without_key_id_are_rejected_by_eddsa() {
+        let signed_token = hs256("a secret of at least thirty-two bytes")
+            .encrypt(&access_token(Duration::minutes(5)))
+            .unwrap()
+            .token;
+
+        let encryptor = eddsa(&generate_key_pair(), &[]);
+        assert!(matches!(decrypt(&encryptor, &signed_token), Err(JwtDecryptionError::UnknownKeyId)));
+    }
+
+    #[test]
+    fn test_expired_tokens_can_only_be_decrypted_ignoring_expiration() {
+        let encryptor = eddsa(&generate_key_pair(), &[]);
+        let token = access_token(-Duration::seconds(1));
+        let signed_token = encryptor.encrypt(&token).unwrap().token;
+
+        assert!(matches!(decrypt(&encryptor, &signed_token), Err(JwtDecryptionError::TokenExpired)));
+
+        let decrypted: UserSessionToken<AccessToken> = encryptor.decrypt_ignoring_expiration(&signed_token).unwrap();
+        assert_eq!(decrypted.get_id(), token.get_id());
+    }
+}
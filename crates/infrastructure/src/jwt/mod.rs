@@ -0,0 +1,3 @@
+pub mod jwt_claims;
+pub mod jwt_error;
+pub mod jwt_token_encryptor;
@@ -1 +1,5 @@
+pub mod jwt;
 pub mod paseto;
+
+#[cfg(test)]
+pub(crate) mod test_token;
@@ -1,2 +1,7 @@
+pub(crate) mod paserk;
 pub mod paseto_claims;
+pub mod paseto_error;
+pub mod paseto_secret_encryptor;
 pub mod paseto_token_encryptor;
+pub mod public_paseto_token_signer;
+pub mod symmetric_keyring;
@@ -0,0 +1,9 @@
+use pasetors::errors::Error;
+use pasetors::paserk::FormatAsPaserk;
+
+/// Formats the given key or key id as PASERK.
+pub(crate) fn format_paserk(value: &impl FormatAsPaserk) -> Result<String, Error> {
+    let mut paserk = String::new();
+    value.fmt(&mut paserk).map_err(|_| Error::PaserkParsing)?;
+    Ok(paserk)
+}
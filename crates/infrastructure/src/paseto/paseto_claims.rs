@@ -1,4 +1,10 @@
+use chrono::{DateTime, Utc};
+use pasetors::claims::Claims;
+use uuid::Uuid;
 
+use security::token::token::Token;
+
+use crate::paseto::paseto_error::{PasetoV4DecryptionError, PasetoV4EncryptionError};
 
 pub struct PasetoClaims;
 
@@ -26,4 +32,97 @@ impl PasetoClaims {
 
     /// CUSTOM_CLAIMS is the key we use to append custom claims
     pub const CUSTOM_CLAIMS: &'static str = "data";
-}
\ No newline at end of file
+
+    /// KEY_ID is the key used to store the PASERK id of the key a token was signed with, within the footer.
+    pub const KEY_ID: &'static str = "kid";
+
+    /// Returns the claims of the given token.
+    pub(crate) fn of<'a, T: Token<'a>>(token: &'a T) -> Result<Claims, PasetoV4EncryptionError> {
+        let mut claims = Claims::new()?;
+        claims.token_identifier(token.get_id().to_string().as_str())?;
+        claims.subject(token.get_subject())?;
+        claims.audience(token.get_audience())?;
+        claims.issuer(token.get_issuer())?;
+        claims.expiration(&token.get_expiration().to_rfc3339())?;
+        claims.not_before(&token.get_not_before().to_rfc3339())?;
+        claims.issued_at(&token.get_issued_at().to_rfc3339())?;
+
+        let additional_data = serde_json::to_value(token.get_custom_claims())?;
+        claims.add_additional(PasetoClaims::CUSTOM_CLAIMS, additional_data)?;
+
+        Ok(claims)
+    }
+
+    /// Creates the token from the given claims, after validating them against the expected issuer and audience.
+    /// With `accept_legacy_audience`, tokens issued for a user id instead of the audience are accepted as well.
+    /// The time based claims are validated here rather than through ClaimsValidationRules,
+    /// as those always reject expired tokens and do not tell why the validation failed.
+    pub(crate) fn into_token<'a, T: Token<'a>>(
+        claims: &Claims,
+        expected_issuer: &str,
+        expected_audience: &str,
+        accept_legacy_audience: bool,
+        validate_expiration: bool
+    ) -> Result<T, PasetoV4DecryptionError> {
+        let id = get_claim(claims, PasetoClaims::TOKEN_IDENTIFIER)?;
+        let id = Uuid::try_parse(id.as_str())?;
+
+        let subject = get_claim(claims, PasetoClaims::SUBJECT)?;
+        let audience = get_claim(claims, PasetoClaims::AUDIENCE)?;
+        let issuer = get_claim(claims, PasetoClaims::ISSUER)?;
+
+        if issuer != expected_issuer {
+            return Err(PasetoV4DecryptionError::UnexpectedIssuer)
+        }
+
+        let legacy_audience = accept_legacy_audience && Uuid::try_parse(&audience).is_ok();
+        if audience != expected_audience && !legacy_audience {
+            return Err(PasetoV4DecryptionError::UnexpectedAudience)
+        }
+
+        let expiration = get_claim(claims, PasetoClaims::EXPIRATION)?;
+        let expiration = DateTime::parse_from_rfc3339(expiration.as_str())?.to_utc();
+
+        let not_before = get_claim(claims, PasetoClaims::NOT_BEFORE)?;
+        let not_before = DateTime::parse_from_rfc3339(not_before.as_str())?.to_utc();
+
+        let issued_at = get_claim(claims, PasetoClaims::ISSUED_AT)?;
+        let issued_at = DateTime::parse_from_rfc3339(issued_at.as_str())?.to_utc();
+
+        let now = Utc::now();
+        if now < not_before || now < issued_at {
+            return Err(PasetoV4DecryptionError::TokenNotYetActive)
+        }
+
+        if validate_expiration && now > expiration {
+            return Err(PasetoV4DecryptionError::TokenExpired)
+        }
+
+        let custom_claims = claims.get_claim(PasetoClaims::CUSTOM_CLAIMS)
+            .ok_or(PasetoV4DecryptionError::MissingClaims)?;
+        let custom_claims: T::CustomClaims = serde_json::from_value(custom_claims.clone())
+            .map_err(PasetoV4DecryptionError::UnexpectedCustomClaims)?;
+
+        Ok(T::new(
+            id,
+            subject,
+            audience,
+            issuer,
+            expiration,
+            not_before,
+            issued_at,
+            custom_claims
+        ))
+    }
+}
+
+fn get_claim(claims: &Claims, claim_name: &str) -> Result<String, PasetoV4DecryptionError> {
+    let claim = claims.get_claim(claim_name)
+        .ok_or(PasetoV4DecryptionError::MissingClaims)?;
+
+    let claim = claim.as_str()
+        .ok_or(PasetoV4DecryptionError::MissingClaims)?
+        .to_string();
+
+    Ok(claim)
+}
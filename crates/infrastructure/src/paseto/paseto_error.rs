@@ -0,0 +1,67 @@
+use std::fmt::{Debug, Formatter};
+
+use lib_util::errors::errors::format_error_chain;
+
+#[derive(thiserror::Error)]
+pub enum PasetoV4EncryptionError {
+    #[error(transparent)]
+    PasetoError(#[from] pasetors::errors::Error),
+
+    #[error(transparent)]
+    SerializeError(#[from] serde_json::Error),
+}
+
+impl Debug for PasetoV4EncryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
+
+#[derive(thiserror::Error)]
+pub enum PasetoV4DecryptionError {
+
+    #[error("Token validation failed because token `not before` > now")]
+    TokenNotYetActive,
+
+    #[error("Token validation failed because token `expiration` < now")]
+    TokenExpired,
+
+    #[error("Token validation failed because token `issuer` is not the expected issuer")]
+    UnexpectedIssuer,
+
+    #[error("Token validation failed because token `audience` is not the expected audience")]
+    UnexpectedAudience,
+
+    #[error("Token validation failed because the token footer does not contain a `kid`")]
+    MissingKeyId,
+
+    #[error("Token validation failed because the token was protected by an unknown key")]
+    UnknownKeyId,
+
+    #[error("Token validation failed because the token was protected by a retired key")]
+    KeyRetired,
+
+    #[error("Token was decrypted successfully but did not contain the correct amount of claims")]
+    MissingClaims,
+
+    #[error("Token was decrypted successfully but its custom claims are not those of the expected token")]
+    UnexpectedCustomClaims(#[source] serde_json::Error),
+
+    #[error(transparent)]
+    CannotParseIdentifier(#[from] uuid::Error),
+
+    #[error(transparent)]
+    CannotParse(#[from] chrono::format::ParseError),
+
+    #[error(transparent)]
+    PasetoError(#[from] pasetors::errors::Error),
+
+    #[error(transparent)]
+    SerializeError(#[from] serde_json::Error),
+}
+
+impl Debug for PasetoV4DecryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        format_error_chain(self, f)
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+use pasetors::footer::Footer;
+use pasetors::token::UntrustedToken;
+use pasetors::version4::{LocalToken, V4};
+use pasetors::Local;
+use secrecy::{ExposeSecret, Secret};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use security::encryption::decryptor::Decryptor;
+use security::encryption::encryptor::Encryptor;
+
+use crate::paseto::paseto_error::{PasetoV4DecryptionError, PasetoV4EncryptionError};
+use crate::paseto::paseto_token_encryptor::key_id;
+use crate::paseto::symmetric_keyring::SymmetricKeyring;
+
+/// Encrypts data that is stored at rest, such as the TOTP secrets of users, as PASETO v4.local
+/// tokens with the active key of the keyring. The data is serialized as JSON and the PASERK id of
+/// the key is stored as `kid` in the footer, like `LocalPasetoV4TokenEncryptor` does for tokens.
+///
+/// The purpose is bound to the encrypted data as implicit assertion, so that data encrypted for
+/// one purpose cannot be decrypted for another, even though the same keys are used.
+pub struct LocalPasetoV4SecretEncryptor<T> {
+    pub keyring: SymmetricKeyring,
+    pub purpose: String,
+    data: PhantomData<fn() -> T>,
+}
+
+impl<T> LocalPasetoV4SecretEncryptor<T> {
+    pub fn new(keyring: SymmetricKeyring, purpose: &str) -> Self {
+        Self {
+            keyring,
+            purpose: purpose.to_string(),
+            data: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for LocalPasetoV4SecretEncryptor<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.keyring.clone(), &self.purpose)
+    }
+}
+
+impl<T> Debug for LocalPasetoV4SecretEncryptor<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalPasetoV4SecretEncryptor")
+            .field("purpose", &self.purpose)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: Serialize> Encryptor<'a, T, Secret<String>> for LocalPasetoV4SecretEncryptor<T> {
+    type EncryptionError = PasetoV4EncryptionError;
+
+    fn encrypt(&self, data: &'a T) -> Result<Secret<String>, Self::EncryptionError> {
+        let payload = Secret::new(serde_json::to_vec(data)?);
+
+        let mut footer = Footer::new();
+        footer.key_id(self.keyring.active_key_id());
+
+        let encrypted_data = LocalToken::encrypt(
+            self.keyring.active_key(),
+            payload.expose_secret(),
+            Some(footer.to_string()?.as_bytes()),
+            Some(self.purpose.as_bytes())
+        )?;
+
+        Ok(Secret::new(encrypted_data))
+    }
+}
+
+impl<T: DeserializeOwned> Decryptor<T> for LocalPasetoV4SecretEncryptor<T> {
+    type DecryptionError = PasetoV4DecryptionError;
+
+    fn decrypt(&self, encrypted_data: &Secret<String>) -> Result<T, Self::DecryptionError> {
+        let local_v4_token = UntrustedToken::<Local, V4>::try_from(encrypted_data.expose_secret())?;
+
+        // stored data outlives the retirement of keys, so it can be decrypted by retired keys as well
+        let key_id = key_id(&local_v4_token)?.ok_or(PasetoV4DecryptionError::MissingKeyId)?;
+        let key = self.keyring.find_including_retired(&key_id)?;
+
+        let decrypted_data = LocalToken::decrypt(
+            key,
+            &local_v4_token,
+            Some(local_v4_token.untrusted_footer()),
+            Some(self.purpose.as_bytes())
+        )?;
+
+        Ok(serde_json::from_str(decrypted_data.payload())?)
+    }
+}
+
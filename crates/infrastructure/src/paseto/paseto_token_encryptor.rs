@@ -1,44 +1,48 @@
-use std::fmt::{Debug, Formatter};
-
-use chrono::DateTime;
-use pasetors::claims::{Claims, ClaimsValidationRules};
-use pasetors::keys::SymmetricKey;
+use pasetors::claims::Claims;
+use pasetors::footer::Footer;
 use pasetors::local;
 use pasetors::token::UntrustedToken;
-use pasetors::version4::V4;
+use pasetors::version4::{LocalToken, V4};
+use pasetors::Local;
 use secrecy::{ExposeSecret, Secret};
-use uuid::Uuid;
 
 use security::encryption::decryptor::Decryptor;
 use security::encryption::encryptor::Encryptor;
 use security::token::token::Token;
 use security::token::token_encryptor::{EncryptedToken, TokenEncryptor};
-use lib_util::errors::errors::format_error_chain;
 
 use crate::paseto::paseto_claims::PasetoClaims;
+use crate::paseto::paseto_error::{PasetoV4DecryptionError, PasetoV4EncryptionError};
+use crate::paseto::symmetric_keyring::SymmetricKeyring;
 
+/// Encrypts tokens as PASETO v4.local tokens with the active key of the keyring. The PASERK id
+/// of the key is stored as `kid` in the footer of every encrypted token, which is used to find
+/// the key to decrypt the token with.
 #[derive(Clone)]
 pub struct LocalPasetoV4TokenEncryptor {
-    pub symmetrick_key: SymmetricKey<V4>,
+    pub keyring: SymmetricKeyring,
+
+    /// The issuer that decrypted tokens must have been issued by.
+    pub issuer: String,
+
+    /// The audience that decrypted tokens must have been issued for.
+    pub audience: String,
+
+    /// Whether decrypted tokens may have been issued for the id of their user instead of the audience,
+    /// as tokens were before the audience became configurable.
+    pub accept_legacy_audience: bool,
 }
 
 impl<'a, T: Token<'a>> Encryptor<'a, T, EncryptedToken> for LocalPasetoV4TokenEncryptor {
-    type EncryptionError = LocalPasetoV4EncryptionError;
+    type EncryptionError = PasetoV4EncryptionError;
 
     fn encrypt(&self, token: &'a T) -> Result<EncryptedToken, Self::EncryptionError> {
-        let mut claims = Claims::new()?;
-        claims.token_identifier(token.get_id().to_string().as_str())?;
-        claims.subject(token.get_subject())?;
-        claims.audience(token.get_audience())?;
-        claims.issuer(token.get_issuer())?;
-        claims.expiration(&token.get_expiration().to_rfc3339())?;
-        claims.not_before(&token.get_not_before().to_rfc3339())?;
-        claims.issued_at(&token.get_issued_at().to_rfc3339())?;
-
-        let additional_data = serde_json::to_value(token.get_custom_claims())?;
-        claims.add_additional(PasetoClaims::CUSTOM_CLAIMS, additional_data)?;
-
-        let encrypted_token = local::encrypt(&self.symmetrick_key, &claims, None, None)
+        let claims = PasetoClaims::of(token)?;
+
+        let mut footer = Footer::new();
+        footer.key_id(self.keyring.active_key_id());
+
+        let encrypted_token = local::encrypt(self.keyring.active_key(), &claims, Some(&footer), None)
             .map_err(Self::EncryptionError::PasetoError)?;
 
         return Ok(EncryptedToken {
@@ -48,114 +52,64 @@ impl<'a, T: Token<'a>> Encryptor<'a, T, EncryptedToken> for LocalPasetoV4TokenEn
     }
 }
 
-#[derive(thiserror::Error)]
-pub enum LocalPasetoV4EncryptionError {
-    #[error(transparent)]
-    PasetoError(#[from] pasetors::errors::Error),
-
-    #[error(transparent)]
-    SerializeError(#[from] serde_json::Error),
-}
-
-impl Debug for LocalPasetoV4EncryptionError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        format_error_chain(self, f)
-    }
-}
-
 impl<'a, T: Token<'a>> Decryptor<T> for LocalPasetoV4TokenEncryptor {
-    type DecryptionError = LocalPasetoV4DecryptionError;
+    type DecryptionError = PasetoV4DecryptionError;
 
     fn decrypt(&self, encrypted_token: &Secret<String>) -> Result<T, Self::DecryptionError> {
-        let local_v4_token = UntrustedToken::try_from(encrypted_token.expose_secret())?;
-
-        // Decryption will return an error of ClaimValidation when the 'not before' > now.
-        // this is duo to the ClaimValidationRules and cannot be turned off.
-        // TODO write pull request to https://github.com/brycx/pasetors to fix it.
-        let decrypted_token = local::decrypt(
-            &self.symmetrick_key,
-            &local_v4_token,
-            &ClaimsValidationRules::new(),
-            None,
-            None
-        ).map_err(|e| match e {
-            pasetors::errors::Error::InvalidClaim => LocalPasetoV4DecryptionError::TokenNotYetActive,
-            e => LocalPasetoV4DecryptionError::PasetoError(e),
-        })?;
-
-        let claims = decrypted_token.payload_claims()
-            .ok_or(LocalPasetoV4DecryptionError::MissingClaims)?;
-
-        let id = get_claim(claims, PasetoClaims::TOKEN_IDENTIFIER)?;
-        let id = Uuid::try_parse(id.as_str())?;
-
-        let subject = get_claim(claims, PasetoClaims::SUBJECT)?;
-        let audience = get_claim(claims, PasetoClaims::AUDIENCE)?;
-        let issuer = get_claim(claims, PasetoClaims::ISSUER)?;
-
-        let expiration = get_claim(claims, PasetoClaims::EXPIRATION)?;
-        let expiration = DateTime::parse_from_rfc3339(expiration.as_str())?.to_utc();
-
-        let not_before = get_claim(claims, PasetoClaims::NOT_BEFORE)?;
-        let not_before = DateTime::parse_from_rfc3339(not_before.as_str())?.to_utc();
-
-        let issued_at = get_claim(claims, PasetoClaims::ISSUED_AT)?;
-        let issued_at = DateTime::parse_from_rfc3339(issued_at.as_str())?.to_utc();
-
-        let custom_claims = claims.get_claim(PasetoClaims::CUSTOM_CLAIMS)
-            .ok_or(LocalPasetoV4DecryptionError::MissingClaims)?;
-        let custom_claims: T::CustomClaims = serde_json::from_value(custom_claims.clone())?;
-
-        return Ok(T::new(
-            id,
-            subject,
-            audience,
-            issuer,
-            expiration,
-            not_before,
-            issued_at,
-            custom_claims
-        ))
+        self.decrypt_token(encrypted_token, true)
     }
 }
 
-#[derive(thiserror::Error)]
-pub enum LocalPasetoV4DecryptionError {
-
-    #[error("Token validation failed because token `not before` > now")]
-    TokenNotYetActive,
-
-    #[error("Token was decrypted successfully but did not contain the correct amount of claims")]
-    MissingClaims,
-
-    #[error(transparent)]
-    CannotParseIdentifier(#[from] uuid::Error),
+impl LocalPasetoV4TokenEncryptor {
 
-    #[error(transparent)]
-    CannotParse(#[from] chrono::format::ParseError),
-
-    #[error(transparent)]
-    PasetoError(#[from] pasetors::errors::Error),
+    /// Decrypts the token like `decrypt`, except that tokens of which the expiration has passed
+    /// are not rejected. Should only be used to inspect expired tokens, never to authenticate.
+    pub fn decrypt_ignoring_expiration<'a, T: Token<'a>>(
+        &self,
+        encrypted_token: &Secret<String>
+    ) -> Result<T, PasetoV4DecryptionError> {
+        self.decrypt_token(encrypted_token, false)
+    }
 
-    #[error(transparent)]
-    SerializeError(#[from] serde_json::Error),
+    fn decrypt_token<'a, T: Token<'a>>(
+        &self,
+        encrypted_token: &Secret<String>,
+        validate_expiration: bool
+    ) -> Result<T, PasetoV4DecryptionError> {
+        let local_v4_token = UntrustedToken::<Local, V4>::try_from(encrypted_token.expose_secret())?;
+
+        let decrypted_token = match key_id(&local_v4_token)? {
+            Some(key_id) => {
+                let key = self.keyring.find(&key_id)?;
+                LocalToken::decrypt(key, &local_v4_token, None, None)?
+            }
+            // tokens encrypted before keys were identified do not have a key id,
+            // which can be decrypted by any of the keys that have not been retired yet.
+            None => self.keyring.usable_keys()
+                .into_iter()
+                .find_map(|key| LocalToken::decrypt(key, &local_v4_token, None, None).ok())
+                .ok_or(PasetoV4DecryptionError::UnknownKeyId)?,
+        };
+
+        let claims = Claims::from_string(decrypted_token.payload())?;
+        PasetoClaims::into_token(&claims, &self.issuer, &self.audience, self.accept_legacy_audience, validate_expiration)
+    }
 }
 
-impl Debug for LocalPasetoV4DecryptionError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        format_error_chain(self, f)
+/// Returns the key id in the footer of the token, if any.
+pub(crate) fn key_id(token: &UntrustedToken<Local, V4>) -> Result<Option<String>, PasetoV4DecryptionError> {
+    if token.untrusted_footer().is_empty() {
+        return Ok(None)
     }
-}
 
-fn get_claim(claims: &Claims, claim_name: &str) -> Result<String, LocalPasetoV4DecryptionError> {
-    let claim = claims.get_claim(claim_name)
-        .ok_or(LocalPasetoV4DecryptionError::MissingClaims)?;
+    let mut footer = Footer::new();
+    footer.parse_bytes(token.untrusted_footer())?;
 
-    let claim = claim.as_str()
-        .ok_or(LocalPasetoV4DecryptionError::MissingClaims)?
-        .to_string();
+    let key_id = footer.get_claim(PasetoClaims::KEY_ID)
+        .and_then(|key_id| key_id.as_str())
+        .map(|key_id| key_id.to_string());
 
-    Ok(claim)
+    Ok(key_id)
 }
 
-impl <'a, T: Token<'a>> TokenEncryptor<'a, T> for LocalPasetoV4TokenEncryptor {}
\ No newline at end of file
+impl <'a, T: Token<'a>> TokenEncryptor<'a, T> for LocalPasetoV4TokenEncryptor {}
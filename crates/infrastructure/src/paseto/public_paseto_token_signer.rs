@@ -0,0 +1,232 @@
+use pasetors::claims::Claims;
+use pasetors::footer::Footer;
+use pasetors::keys::{AsymmetricPublicKey, AsymmetricSecretKey};
+use pasetors::paserk::Id;
+use pasetors::public;
+use pasetors::token::UntrustedToken;
+use pasetors::version4::{PublicToken, V4};
+use pasetors::Public;
+use secrecy::{ExposeSecret, Secret};
+
+use security::encryption::decryptor::Decryptor;
+use security::encryption::encryptor::Encryptor;
+use security::token::token::Token;
+use security::token::token_encryptor::{EncryptedToken, TokenEncryptor};
+
+use crate::paseto::paserk::format_paserk;
+use crate::paseto::paseto_claims::PasetoClaims;
+use crate::paseto::paseto_error::{PasetoV4DecryptionError, PasetoV4EncryptionError};
+
+/// Signs tokens as PASETO v4.public tokens with Ed25519 keys, such that anyone with the public key
+/// can verify them without being able to create them. The PASERK id of the public key is stored
+/// as `kid` in the footer of every signed token.
+#[derive(Clone)]
+pub struct PublicPasetoV4TokenSigner {
+    /// The key new tokens are signed with.
+    signing_key: AsymmetricSecretKey<V4>,
+    key_id: Id,
+
+    /// The public keys of which the signed tokens are accepted, by their PASERK id.
+    verification_keys: Vec<(String, AsymmetricPublicKey<V4>)>,
+    public_keys: Vec<PaserkPublicKey>,
+
+    /// The issuer that verified tokens must have been issued by.
+    pub issuer: String,
+
+    /// The audience that verified tokens must have been issued for.
+    pub audience: String,
+}
+
+/// A public key formatted as PASERK, together with its PASERK id.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaserkPublicKey {
+    pub kid: String,
+    pub key: String,
+}
+
+impl<'a, T: Token<'a>> Encryptor<'a, T, EncryptedToken> for PublicPasetoV4TokenSigner {
+    type EncryptionError = PasetoV4EncryptionError;
+
+    fn encrypt(&self, token: &'a T) -> Result<EncryptedToken, Self::EncryptionError> {
+        let claims = PasetoClaims::of(token)?;
+
+        let mut footer = Footer::new();
+        footer.key_id(&self.key_id);
+
+        let signed_token = public::sign(&self.signing_key, &claims, Some(&footer), None)?;
+
+        Ok(EncryptedToken {
+            token: Secret::new(signed_token),
+            expires_at: *token.get_expiration()
+        })
+    }
+}
+
+impl<'a, T: Token<'a>> Decryptor<T> for PublicPasetoV4TokenSigner {
+    type DecryptionError = PasetoV4DecryptionError;
+
+    fn decrypt(&self, signed_token: &Secret<String>) -> Result<T, Self::DecryptionError> {
+        self.verify_token(signed_token, true)
+    }
+}
+
+impl PublicPasetoV4TokenSigner {
+
+    /// Creates a signer that signs tokens with the given Ed25519 key. Tokens signed by the signing
+    /// key or by any of the verification keys, e.g. previous signing keys, are accepted.
+    pub fn new(
+        signing_key: AsymmetricSecretKey<V4>,
+        verification_keys: &[AsymmetricPublicKey<V4>],
+        issuer: String,
+        audience: String
+    ) -> Result<Self, pasetors::errors::Error> {
+        let public_key = AsymmetricPublicKey::<V4>::try_from(&signing_key)?;
+
+        let mut public_keys = vec![];
+        let mut keys = vec![];
+        for key in std::iter::once(&public_key).chain(verification_keys) {
+            let key_id = format_paserk(&Id::from(key))?;
+            keys.push((key_id.clone(), key.clone()));
+            public_keys.push(PaserkPublicKey {
+                kid: key_id,
+                key: format_paserk(key)?,
+            });
+        }
+
+        Ok(Self {
+            key_id: Id::from(&public_key),
+            signing_key,
+            verification_keys: keys,
+            public_keys,
+            issuer,
+            audience,
+        })
+    }
+
+    /// Returns every public key of which the signed tokens are accepted, formatted as PASERK.
+    pub fn public_keys(&self) -> Vec<PaserkPublicKey> {
+        self.public_keys.clone()
+    }
+
+    /// Verifies the token like `decrypt`, except that tokens of which the expiration has passed
+    /// are not rejected. Should only be used to inspect expired tokens, never to authenticate.
+    pub fn decrypt_ignoring_expiration<'a, T: Token<'a>>(
+        &self,
+        signed_token: &Secret<String>
+    ) -> Result<T, PasetoV4DecryptionError> {
+        self.verify_token(signed_token, false)
+    }
+
+    fn verify_token<'a, T: Token<'a>>(
+        &self,
+        signed_token: &Secret<String>,
+        validate_expiration: bool
+    ) -> Result<T, PasetoV4DecryptionError> {
+        let public_v4_token = UntrustedToken::<Public, V4>::try_from(signed_token.expose_secret())?;
+
+        if public_v4_token.untrusted_footer().is_empty() {
+            return Err(PasetoV4DecryptionError::MissingKeyId)
+        }
+
+        let mut footer = Footer::new();
+        footer.parse_bytes(public_v4_token.untrusted_footer())?;
+        let kid = footer.get_claim(PasetoClaims::KEY_ID)
+            .and_then(|kid| kid.as_str())
+            .ok_or(PasetoV4DecryptionError::MissingKeyId)?;
+
+        let (_, public_key) = self.verification_keys.iter()
+            .find(|(key_id, _)| key_id == kid)
+            .ok_or(PasetoV4DecryptionError::UnknownKeyId)?;
+
+        let verified_token = PublicToken::verify(public_key, &public_v4_token, None, None)?;
+
+        let claims = Claims::from_string(verified_token.payload())?;
+        PasetoClaims::into_token(&claims, &self.issuer, &self.audience, false, validate_expiration)
+    }
+}
+
+impl <'a, T: Token<'a>> TokenEncryptor<'a, T> for PublicPasetoV4TokenSigner {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use pasetors::claims::Claims;
+    use pasetors::keys::{AsymmetricKeyPair, Generate};
+    use pasetors::public;
+    use pasetors::version4::V4;
+    use secrecy::Secret;
+
+    use domain::sessions::tokens::AccessToken;
+    use domain::sessions::user_session_token::UserSessionToken;
+    use security::encryption::decryptor::Decryptor;
+    use security::encryption::encryptor::Encryptor;
+    use security::token::token::Token;
+
+    use crate::paseto::paseto_error::PasetoV4DecryptionError;
+    use crate::paseto::public_paseto_token_signer::PublicPasetoV4TokenSigner;
+    use crate::test_token::{access_token, AUDIENCE, ISSUER};
+
+    fn signer(key_pair: &AsymmetricKeyPair<V4>, previous_key_pairs: &[&AsymmetricKeyPair<V4>]) -> PublicPasetoV4TokenSigner {
+        let verification_keys = previous_key_pairs.iter()
+            .map(|key_pair| key_pair.public.clone())
+            .collect::<Vec<_>>();
+
+        PublicPasetoV4TokenSigner::new(key_pair.secret.clone(), &verification_keys, ISSUER.to_string(), AUDIENCE.to_string())
+            .expect("Failed to create signer")
+    }
+
+    fn generate_key_pair() -> AsymmetricKeyPair<V4> {
+        AsymmetricKeyPair::<V4>::generate().expect("Failed to generate key pair")
+    }
+
+    fn verify(signer: &PublicPasetoV4TokenSigner, token: &Secret<String>) -> Result<UserSessionToken<AccessToken>, PasetoV4DecryptionError> {
+        signer.decrypt(token)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let signer = signer(&generate_key_pair(), &[]);
+        let token = access_token(Duration::minutes(5));
+
+        let signed_token = signer.encrypt(&token).unwrap().token;
+        assert_eq!(verify(&signer, &signed_token).unwrap().get_id(), token.get_id());
+    }
+
+    #[test]
+    fn test_tokens_of_verification_keys_are_accepted() {
+        let previous_key_pair = generate_key_pair();
+        let token = access_token(Duration::minutes(5));
+        let signed_token = signer(&previous_key_pair, &[]).encrypt(&token).unwrap().token;
+
+        let rotated_signer = signer(&generate_key_pair(), &[&previous_key_pair]);
+        assert_eq!(verify(&rotated_signer, &signed_token).unwrap().get_id(), token.get_id());
+        assert_eq!(rotated_signer.public_keys().len(), 2);
+        assert_eq!(rotated_signer.public_keys()[1], signer(&previous_key_pair, &[]).public_keys()[0]);
+
+        let other_signer = signer(&generate_key_pair(), &[]);
+        assert!(matches!(verify(&other_signer, &signed_token), Err(PasetoV4DecryptionError::UnknownKeyId)));
+    }
+
+    #[test]
+    fn test_tokens_without_key_id_are_rejected() {
+        let key_pair = generate_key_pair();
+        let mut claims = Claims::new().unwrap();
+        claims.issuer(ISSUER).unwrap();
+        let signed_token = public::sign(&key_pair.secret, &claims, None, None).unwrap();
+
+        let signer = signer(&key_pair, &[]);
+        assert!(matches!(verify(&signer, &Secret::new(signed_token)), Err(PasetoV4DecryptionError::MissingKeyId)));
+    }
+
+    #[test]
+    fn test_expired_tokens_can_only_be_verified_ignoring_expiration() {
+        let signer = signer(&generate_key_pair(), &[]);
+        let token = access_token(-Duration::seconds(1));
+        let signed_token = signer.encrypt(&token).unwrap().token;
+
+        assert!(matches!(verify(&signer, &signed_token), Err(PasetoV4DecryptionError::TokenExpired)));
+
+        let verified: UserSessionToken<AccessToken> = signer.decrypt_ignoring_expiration(&signed_token).unwrap();
+        assert_eq!(verified.get_id(), token.get_id());
+    }
+}
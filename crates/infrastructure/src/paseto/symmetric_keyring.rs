@@ -0,0 +1,162 @@
+use chrono::{DateTime, Utc};
+use pasetors::keys::SymmetricKey;
+use pasetors::paserk::Id;
+use pasetors::version4::V4;
+
+use crate::paseto::paserk::format_paserk;
+use crate::paseto::paseto_error::PasetoV4DecryptionError;
+
+/// SymmetricKeyring holds the key new tokens are encrypted with, together with previous keys of which
+/// tokens are still accepted until their retirement. Keys are identified by their PASERK id (`k4.lid`).
+#[derive(Clone)]
+pub struct SymmetricKeyring {
+    /// The key new tokens are encrypted with.
+    active_key: SymmetricKey<V4>,
+    active_key_id: Id,
+    active_kid: String,
+
+    /// Previous keys, which are only used to decrypt tokens, by their PASERK id.
+    retired_keys: Vec<(String, RetiredSymmetricKey)>,
+}
+
+/// RetiredSymmetricKey is a previously active key, of which tokens are accepted until `retire_at`.
+/// Rotating keys without forcing users to sign in again requires `retire_at` to be at least
+/// the lifetime of a refresh token after the key stopped being active.
+#[derive(Clone)]
+pub struct RetiredSymmetricKey {
+    pub key: SymmetricKey<V4>,
+    pub retire_at: DateTime<Utc>,
+}
+
+impl SymmetricKeyring {
+
+    /// Creates a keyring of which new tokens are encrypted with the active key,
+    /// the PASERK ids of the keys are derived once here rather than on every decryption.
+    pub fn new(
+        active_key: SymmetricKey<V4>,
+        retired_keys: Vec<RetiredSymmetricKey>
+    ) -> Result<Self, pasetors::errors::Error> {
+        let active_key_id = Id::from(&active_key);
+        let retired_keys = retired_keys.into_iter()
+            .map(|retired_key| Ok((format_paserk(&Id::from(&retired_key.key))?, retired_key)))
+            .collect::<Result<Vec<_>, pasetors::errors::Error>>()?;
+
+        Ok(Self {
+            active_kid: format_paserk(&active_key_id)?,
+            active_key_id,
+            active_key,
+            retired_keys,
+        })
+    }
+
+    /// Returns the key new tokens are encrypted with.
+    pub fn active_key(&self) -> &SymmetricKey<V4> {
+        &self.active_key
+    }
+
+    /// Returns the id of the active key.
+    pub fn active_key_id(&self) -> &Id {
+        &self.active_key_id
+    }
+
+    /// Returns the key of which the PASERK id equals the given key id,
+    /// unless the key is unknown or has been retired.
+    pub(crate) fn find(&self, key_id: &str) -> Result<&SymmetricKey<V4>, PasetoV4DecryptionError> {
+        self.find_key(key_id, true)
+    }
+
+    /// Returns the key of which the PASERK id equals the given key id, even if the key has been
+    /// retired. Data that is stored encrypted outlives the tokens a key was retired for, and
+    /// should stay readable until it has been encrypted again with the active key.
+    pub(crate) fn find_including_retired(&self, key_id: &str) -> Result<&SymmetricKey<V4>, PasetoV4DecryptionError> {
+        self.find_key(key_id, false)
+    }
+
+    fn find_key(&self, key_id: &str, reject_retired: bool) -> Result<&SymmetricKey<V4>, PasetoV4DecryptionError> {
+        if self.active_kid == key_id {
+            return Ok(&self.active_key)
+        }
+
+        for (retired_kid, retired_key) in &self.retired_keys {
+            if retired_kid != key_id {
+                continue
+            }
+
+            if reject_retired && Utc::now() > retired_key.retire_at {
+                return Err(PasetoV4DecryptionError::KeyRetired)
+            }
+
+            return Ok(&retired_key.key)
+        }
+
+        Err(PasetoV4DecryptionError::UnknownKeyId)
+    }
+
+    /// Returns the keys that have not been retired yet, starting with the active key.
+    pub(crate) fn usable_keys(&self) -> Vec<&SymmetricKey<V4>> {
+        let now = Utc::now();
+        let retired_keys = self.retired_keys.iter()
+            .filter(|(_, retired_key)| now <= retired_key.retire_at)
+            .map(|(_, retired_key)| &retired_key.key);
+
+        std::iter::once(&self.active_key)
+            .chain(retired_keys)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use pasetors::keys::{Generate, SymmetricKey};
+    use pasetors::paserk::Id;
+    use pasetors::version4::V4;
+
+    use crate::paseto::paserk::format_paserk;
+    use crate::paseto::paseto_error::PasetoV4DecryptionError;
+    use crate::paseto::symmetric_keyring::{RetiredSymmetricKey, SymmetricKeyring};
+
+    fn generate_key() -> SymmetricKey<V4> {
+        SymmetricKey::<V4>::generate().expect("Failed to generate key")
+    }
+
+    fn kid(key: &SymmetricKey<V4>) -> String {
+        format_paserk(&Id::from(key)).expect("Failed to format key id")
+    }
+
+    #[test]
+    fn test_finds_active_and_retired_keys_by_id() {
+        let active_key = generate_key();
+        let retired_key = generate_key();
+        let keyring = SymmetricKeyring::new(active_key.clone(), vec![RetiredSymmetricKey {
+            key: retired_key.clone(),
+            retire_at: Utc::now() + Duration::hours(1),
+        }]).unwrap();
+
+        assert_eq!(keyring.find(&kid(&active_key)).unwrap().as_bytes(), active_key.as_bytes());
+        assert_eq!(keyring.find(&kid(&retired_key)).unwrap().as_bytes(), retired_key.as_bytes());
+        assert_eq!(format_paserk(keyring.active_key_id()).unwrap(), kid(&active_key));
+        assert_eq!(keyring.usable_keys().len(), 2);
+    }
+
+    #[test]
+    fn test_retired_keys_are_only_found_including_retired() {
+        let retired_key = generate_key();
+        let keyring = SymmetricKeyring::new(generate_key(), vec![RetiredSymmetricKey {
+            key: retired_key.clone(),
+            retire_at: Utc::now() - Duration::seconds(1),
+        }]).unwrap();
+
+        assert!(matches!(keyring.find(&kid(&retired_key)), Err(PasetoV4DecryptionError::KeyRetired)));
+        assert_eq!(keyring.find_including_retired(&kid(&retired_key)).unwrap().as_bytes(), retired_key.as_bytes());
+        assert_eq!(keyring.usable_keys().len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let keyring = SymmetricKeyring::new(generate_key(), vec![]).unwrap();
+
+        assert!(matches!(keyring.find(&kid(&generate_key())), Err(PasetoV4DecryptionError::UnknownKeyId)));
+        assert!(matches!(keyring.find_including_retired("not a key id"), Err(PasetoV4DecryptionError::UnknownKeyId)));
+    }
+}
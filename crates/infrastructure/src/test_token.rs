@@ -0,0 +1,33 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use domain::sessions::tokens::AccessToken;
+use domain::sessions::user_session_token::UserSessionToken;
+use security::token::token::Token;
+
+pub(crate) const ISSUER: &str = "issuer";
+pub(crate) const AUDIENCE: &str = "audience";
+
+/// Returns an access token which was issued a minute ago and expires after the given duration from now.
+pub(crate) fn access_token(expires_in: Duration) -> UserSessionToken<AccessToken> {
+    let now = Utc::now();
+    token_issued_at(now - Duration::minutes(1), now + expires_in)
+}
+
+/// Returns an access token issued at the given time, which is also the time it becomes active.
+pub(crate) fn token_issued_at(issued_at: DateTime<Utc>, expiration: DateTime<Utc>) -> UserSessionToken<AccessToken> {
+    UserSessionToken::new(
+        Uuid::new_v4(),
+        AccessToken::subject().to_string(),
+        AUDIENCE.to_string(),
+        ISSUER.to_string(),
+        expiration,
+        issued_at,
+        issued_at,
+        AccessToken {
+            user_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            refresh_token_id: Uuid::new_v4(),
+        },
+    )
+}
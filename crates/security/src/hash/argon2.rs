@@ -1,7 +1,12 @@
-use argon2::{Algorithm, Argon2, Params, PasswordHasher, Version};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use argon2::{Algorithm, Argon2, KeyId, Params, ParamsBuilder, PasswordHasher, Version};
 use argon2::password_hash::SaltString;
+use password_hash::errors::InvalidValue;
 use password_hash::{PasswordHash, PasswordVerifier};
 use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
 use crate::hash::error::Error;
 use crate::hash::scheme::Scheme;
 
@@ -14,8 +19,12 @@ use crate::hash::scheme::Scheme;
 /// https://cheatsheetseries.owasp.org/cheatsheets/Password_Storage_Cheat_Sheet.html#argon2id
 /// 
 
-#[derive(Debug)]
-pub struct Argon2Scheme;
+#[derive(Debug, Clone)]
+pub struct Argon2Scheme {
+    params: Argon2Params,
+    pepper: Option<Pepper>,
+    retired_peppers: Vec<Pepper>,
+}
 
 /// M_COST equals the minimum memory size that should be used
 const M_COST: u32 = 19456; // 19MB
@@ -26,25 +35,220 @@ const T_COST: u32 = 2;
 /// P_COST equals the degree of parallelism that should be used.
 const P_COST: u32 = 1;
 
+/// The scheme new hashes are created with, configured once at startup.
+static CONFIGURED_SCHEME: OnceLock<Argon2Scheme> = OnceLock::new();
+
+/// Argon2Params are the costs of hashing with Argon2. Raising them makes hashes harder to crack,
+/// at the expense of the time and memory it takes to hash a password.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct Argon2Params {
+    /// m_cost is the memory size in KiB.
+    pub m_cost: u32,
+
+    /// t_cost is the number of iterations.
+    pub t_cost: u32,
+
+    /// p_cost is the degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Self {
+            m_cost: M_COST,
+            t_cost: T_COST,
+            p_cost: P_COST,
+        }
+    }
+}
+
+impl Argon2Params {
+
+    /// Returns whether a hash made with these parameters is easier to crack than one made with
+    /// the other parameters, i.e. when any of the costs is lower.
+    pub fn is_weaker_than(&self, other: &Argon2Params) -> bool {
+        self.m_cost < other.m_cost || self.t_cost < other.t_cost || self.p_cost < other.p_cost
+    }
+
+    fn to_params(self) -> Result<Params, argon2::Error> {
+        Params::new(self.m_cost, self.t_cost, self.p_cost, None)
+    }
+}
+
+/// Pepper is a secret mixed into every hash as the secret of Argon2, which is only part of the
+/// configuration and never stored with the hashes. Without it, a leaked hash cannot be cracked.
+/// The version is stored in the hash as its key id, which allows rotating the pepper.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Pepper {
+    pub version: u32,
+    pub key: Secret<String>,
+}
+
+impl Pepper {
+    fn key_id(&self) -> KeyId {
+        // a u32 is always within the 8 bytes a key id can be
+        KeyId::new(&self.version.to_be_bytes()).unwrap()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Argon2ConfigurationError {
+    #[error("Invalid Argon2 parameters: {0}")]
+    InvalidParams(argon2::Error),
+
+    #[error("Pepper of version {0} has an empty key")]
+    EmptyPepper(u32),
+
+    #[error("Multiple peppers have version {0}")]
+    DuplicatePepperVersion(u32),
+
+    #[error("Argon2 is already configured with different parameters or peppers")]
+    AlreadyConfigured,
+}
+
+/// Configures the scheme of which new hashes are created, should be called once at startup
+/// before anything is hashed. Configuring the same scheme again has no effect.
+pub fn configure_argon2(scheme: Argon2Scheme) -> Result<(), Argon2ConfigurationError> {
+    scheme.params.to_params().map_err(Argon2ConfigurationError::InvalidParams)?;
+
+    let mut versions = HashSet::new();
+    for pepper in scheme.peppers() {
+        if pepper.key.expose_secret().is_empty() {
+            return Err(Argon2ConfigurationError::EmptyPepper(pepper.version))
+        }
+
+        if !versions.insert(pepper.version) {
+            return Err(Argon2ConfigurationError::DuplicatePepperVersion(pepper.version))
+        }
+    }
+
+    let configured_scheme = CONFIGURED_SCHEME.get_or_init(|| scheme.clone());
+    match configured_scheme.is_configured_like(&scheme) {
+        true => Ok(()),
+        false => Err(Argon2ConfigurationError::AlreadyConfigured),
+    }
+}
+
+impl Argon2Scheme {
+    pub fn new(params: Argon2Params) -> Self {
+        Self {
+            params,
+            pepper: None,
+            retired_peppers: vec![],
+        }
+    }
+
+    /// Returns the scheme which peppers new hashes with the given pepper. Hashes peppered with
+    /// one of the retired peppers can still be validated, but need to be hashed again.
+    pub fn with_pepper(self, pepper: Pepper, retired_peppers: Vec<Pepper>) -> Self {
+        Self {
+            pepper: Some(pepper),
+            retired_peppers,
+            ..self
+        }
+    }
+
+    /// Returns the configured scheme, or one with the default parameters when none is configured.
+    pub fn configured() -> Self {
+        CONFIGURED_SCHEME.get()
+            .cloned()
+            .unwrap_or_else(|| Self::new(Argon2Params::default()))
+    }
+
+    pub fn params(&self) -> &Argon2Params {
+        &self.params
+    }
+
+    fn peppers(&self) -> impl Iterator<Item = &Pepper> {
+        self.pepper.iter().chain(self.retired_peppers.iter())
+    }
+
+    fn is_configured_like(&self, other: &Argon2Scheme) -> bool {
+        let same_pepper = |a: &Pepper, b: &Pepper| a.version == b.version && a.key.expose_secret() == b.key.expose_secret();
+
+        self.params == other.params
+            && self.peppers().count() == other.peppers().count()
+            && self.peppers().zip(other.peppers()).all(|(a, b)| same_pepper(a, b))
+            && self.pepper.is_some() == other.pepper.is_some()
+    }
+
+    /// Returns the Argon2 instance which creates hashes, peppered with the current pepper.
+    fn argon2(&self) -> password_hash::Result<Argon2<'_>> {
+        let mut params = ParamsBuilder::new();
+        params
+            .m_cost(self.params.m_cost)
+            .t_cost(self.params.t_cost)
+            .p_cost(self.params.p_cost);
+
+        let Some(pepper) = &self.pepper else {
+            let params = params.build().map_err(|_| password_hash::Error::ParamsMaxExceeded)?;
+            return Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+        };
+
+        let params = params
+            .keyid(pepper.key_id())
+            .build()
+            .map_err(|_| password_hash::Error::ParamsMaxExceeded)?;
+
+        Argon2::new_with_secret(pepper.key.expose_secret().as_bytes(), Algorithm::Argon2id, Version::V0x13, params)
+            .map_err(|_| password_hash::Error::Crypto)
+    }
+
+    /// Returns the Argon2 instance which validates the hash, peppered with the pepper
+    /// of which the version is the key id of the hash.
+    fn argon2_of(&self, params_of_hash: &Params) -> password_hash::Result<Argon2<'_>> {
+        let key_id = params_of_hash.keyid();
+        if key_id.is_empty() {
+            return Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params_of_hash.clone()))
+        }
+
+        // hashes of peppers which are no longer configured cannot be validated
+        let pepper = self.peppers()
+            .find(|pepper| pepper.key_id().as_bytes() == key_id)
+            .ok_or(password_hash::Error::ParamValueInvalid(InvalidValue::Malformed))?;
+
+        Argon2::new_with_secret(pepper.key.expose_secret().as_bytes(), Algorithm::Argon2id, Version::V0x13, params_of_hash.clone())
+            .map_err(|_| password_hash::Error::Crypto)
+    }
+}
+
 impl Scheme for Argon2Scheme {
     fn hash<'a>(&self, to_hash: Secret<String>, salt_string: &'a SaltString) -> password_hash::Result<PasswordHash<'a>> {
-        let password_hash = Argon2::new(
-            Algorithm::Argon2id,
-            Version::V0x13,
-            Params::new(M_COST, T_COST, P_COST, None).unwrap(),
-        )
+        let password_hash = self.argon2()?
             .hash_password(to_hash.expose_secret().as_bytes(), salt_string)?;
 
         Ok(password_hash)
     }
 
     fn validate(&self, to_hash: &Secret<String>, password_hash: &PasswordHash) -> Result<(), Error> {
-		Argon2::default()
+		// the hash is verified with the parameters it was created with, which are part of the hash
+		let params_of_hash = Params::try_from(password_hash)?;
+		self.argon2_of(&params_of_hash)?
 			.verify_password(to_hash.expose_secret().as_bytes(), &password_hash)
 			.map_err(Error::from)?;
 		
         Ok(())
     }
+
+    fn needs_rehash(&self, password_hash: &PasswordHash) -> bool {
+        let Ok(params) = Params::try_from(password_hash) else {
+            return true
+        };
+
+        let params_of_hash = Argon2Params {
+            m_cost: params.m_cost(),
+            t_cost: params.t_cost(),
+            p_cost: params.p_cost(),
+        };
+
+        let current_key_id = self.pepper.as_ref().map(|pepper| pepper.key_id());
+        let has_current_pepper = params.keyid() == current_key_id.as_ref().map_or(&[][..], |key_id| key_id.as_bytes());
+
+        password_hash.version != Some(Version::V0x13.into())
+            || params_of_hash.is_weaker_than(&self.params)
+            || !has_current_pepper
+    }
 }
 
 #[cfg(test)]
@@ -52,7 +256,7 @@ mod tests {
 	use password_hash::SaltString;
 	use secrecy::{ExposeSecret, Secret};
 	use uuid::Uuid;
-	use crate::hash::argon2::Argon2Scheme;
+	use crate::hash::argon2::{configure_argon2, Argon2ConfigurationError, Argon2Params, Argon2Scheme, Pepper};
 	use crate::hash::scheme::Scheme;
 
 
@@ -69,32 +273,134 @@ mod tests {
 		let pw1 = password();
 		let pw1_salt = salt();
 
-		let hashed_pw1 = Argon2Scheme.hash(pw1.clone(), &pw1_salt)
+		let hashed_pw1 = Argon2Scheme::configured().hash(pw1.clone(), &pw1_salt)
 			.unwrap();
 
 		// Test pw1 equals its own hash
-		let result = Argon2Scheme.validate(&pw1, &hashed_pw1);
+		let result = Argon2Scheme::configured().validate(&pw1, &hashed_pw1);
 		assert!(result.is_ok());
 
 		// Test if pw2 does not equals pw1 hash
 		let pw2 = password();
 		assert_ne!(pw1.expose_secret(), pw2.expose_secret(), "pw1 and pw2 should not be equal");
 
-		let result = Argon2Scheme.validate(&pw2, &hashed_pw1);
+		let result = Argon2Scheme::configured().validate(&pw2, &hashed_pw1);
 		assert!(result.is_err());
 		// assert_eq!(result.err().unwrap(), PasswordInvalid);
 
 		// Test if pw2 equals its own hash
 		let pw2_salt = salt();
-		let hashed_pw2 = Argon2Scheme.hash(pw2.clone(), &pw2_salt)
+		let hashed_pw2 = Argon2Scheme::configured().hash(pw2.clone(), &pw2_salt)
 			.unwrap();
 
-		let result = Argon2Scheme.validate(&pw2, &hashed_pw2);
+		let result = Argon2Scheme::configured().validate(&pw2, &hashed_pw2);
 		assert!(result.is_ok());
 
 		// Test if pw1 does not equal pw2 hash
-		let result = Argon2Scheme.validate(&pw1, &hashed_pw2);
+		let result = Argon2Scheme::configured().validate(&pw1, &hashed_pw2);
 		assert!(result.is_err());
 		// assert_eq!(result.err().unwrap(), PasswordInvalid);
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_hash_uses_params_of_scheme() {
+		let params = Argon2Params { m_cost: 8192, t_cost: 1, p_cost: 1 };
+		let scheme = Argon2Scheme::new(params);
+		let pw = password();
+		let salt = salt();
+
+		let hash = scheme.hash(pw.clone(), &salt).unwrap();
+		assert!(hash.to_string().contains("m=8192,t=1,p=1"));
+
+		// hashes are verified with their own params, regardless of the scheme
+		assert!(Argon2Scheme::configured().validate(&pw, &hash).is_ok());
+	}
+
+	#[test]
+	fn test_needs_rehash_when_params_are_weaker() {
+		let weak_scheme = Argon2Scheme::new(Argon2Params { m_cost: 8192, t_cost: 1, p_cost: 1 });
+		let strong_scheme = Argon2Scheme::new(Argon2Params { m_cost: 8192, t_cost: 2, p_cost: 1 });
+		let salt = salt();
+
+		let weak_hash = weak_scheme.hash(password(), &salt).unwrap();
+		let strong_hash = strong_scheme.hash(password(), &salt).unwrap();
+
+		assert!(strong_scheme.needs_rehash(&weak_hash));
+		assert!(!strong_scheme.needs_rehash(&strong_hash));
+
+		// stronger hashes are kept, e.g. after lowering the params
+		assert!(!weak_scheme.needs_rehash(&strong_hash));
+	}
+
+	#[test]
+	fn test_params_are_weaker_when_any_cost_is_lower() {
+		let params = Argon2Params::default();
+
+		assert!(!params.is_weaker_than(&params));
+		assert!(Argon2Params { m_cost: params.m_cost - 1, ..params }.is_weaker_than(&params));
+		assert!(Argon2Params { t_cost: params.t_cost + 1, p_cost: params.p_cost - 1, ..params }.is_weaker_than(&params));
+		assert!(!Argon2Params { m_cost: params.m_cost + 1, ..params }.is_weaker_than(&params));
+	}
+
+	fn weak_params() -> Argon2Params {
+		Argon2Params { m_cost: 8192, t_cost: 1, p_cost: 1 }
+	}
+
+	fn pepper(version: u32, key: &str) -> Pepper {
+		Pepper { version, key: Secret::new(key.to_string()) }
+	}
+
+	#[test]
+	fn test_peppered_hash_requires_pepper() {
+		let peppered_scheme = Argon2Scheme::new(weak_params()).with_pepper(pepper(1, "pepper"), vec![]);
+		let pw = password();
+		let salt = salt();
+
+		let hash = peppered_scheme.hash(pw.clone(), &salt).unwrap();
+		assert!(hash.to_string().contains("keyid="));
+		assert!(peppered_scheme.validate(&pw, &hash).is_ok());
+		assert!(peppered_scheme.validate(&password(), &hash).is_err());
+		assert!(!peppered_scheme.needs_rehash(&hash));
+
+		// without the pepper, or with another pepper of the same version, the hash cannot be validated
+		assert!(Argon2Scheme::new(weak_params()).validate(&pw, &hash).is_err());
+		let other_pepper_scheme = Argon2Scheme::new(weak_params()).with_pepper(pepper(1, "other pepper"), vec![]);
+		assert!(other_pepper_scheme.validate(&pw, &hash).is_err());
+	}
+
+	#[test]
+	fn test_hashes_of_old_peppers_need_rehash() {
+		let pw = password();
+		let salt = salt();
+		let unpeppered_hash = Argon2Scheme::new(weak_params()).hash(pw.clone(), &salt).unwrap();
+		let old_hash = Argon2Scheme::new(weak_params())
+			.with_pepper(pepper(1, "old pepper"), vec![])
+			.hash(pw.clone(), &salt)
+			.unwrap();
+
+		let rotated_scheme = Argon2Scheme::new(weak_params())
+			.with_pepper(pepper(2, "new pepper"), vec![pepper(1, "old pepper")]);
+
+		// hashes of the retired pepper and without pepper can still be validated, but need a rehash
+		assert!(rotated_scheme.validate(&pw, &old_hash).is_ok());
+		assert!(rotated_scheme.needs_rehash(&old_hash));
+		assert!(rotated_scheme.validate(&pw, &unpeppered_hash).is_ok());
+		assert!(rotated_scheme.needs_rehash(&unpeppered_hash));
+
+		let new_hash = rotated_scheme.hash(pw.clone(), &salt).unwrap();
+		assert!(rotated_scheme.validate(&pw, &new_hash).is_ok());
+		assert!(!rotated_scheme.needs_rehash(&new_hash));
+
+		// removing the pepper requires peppered hashes to be replaced as well
+		assert!(Argon2Scheme::new(weak_params()).needs_rehash(&new_hash));
+	}
+
+	#[test]
+	fn test_configure_rejects_invalid_peppers() {
+		let scheme = Argon2Scheme::new(weak_params()).with_pepper(pepper(1, ""), vec![]);
+		assert!(matches!(configure_argon2(scheme), Err(Argon2ConfigurationError::EmptyPepper(1))));
+
+		let scheme = Argon2Scheme::new(weak_params()).with_pepper(pepper(1, "pepper"), vec![pepper(1, "old pepper")]);
+		assert!(matches!(configure_argon2(scheme), Err(Argon2ConfigurationError::DuplicatePepperVersion(1))));
+	}
+}
@@ -0,0 +1,136 @@
+use base64::alphabet::BCRYPT;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use password_hash::{Ident, Output, ParamsString, PasswordHash, Salt, SaltString};
+use secrecy::{ExposeSecret, Secret};
+use crate::hash::error::Error;
+use crate::hash::scheme::Scheme;
+
+/// BCRYPT_IDENT identifies bcrypt hashes once converted into the PHC string format.
+pub const BCRYPT_IDENT: Ident = Ident::new_unwrap("bcrypt");
+
+/// The base64 encoding bcrypt uses for its salt and hash, which uses another alphabet than PHC strings.
+const BCRYPT_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &BCRYPT,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::RequireNone)
+        .with_decode_allow_trailing_bits(true),
+);
+
+/// The length of the encoded salt of a bcrypt hash.
+const SALT_LENGTH: usize = 22;
+
+/// The length of the encoded hash of a bcrypt hash.
+const HASH_LENGTH: usize = 31;
+
+/// BcryptScheme verifies bcrypt hashes of users imported from legacy systems. It never creates
+/// hashes, passwords it verifies are expected to be hashed again with the latest scheme.
+///
+/// Bcrypt hashes have their own format, e.g. `$2b$12$<salt><hash>`, which `to_phc_string` converts
+/// into a PHC string like `$bcrypt$variant=2b,cost=12$<salt>$<hash>`, as the schemes only accept those.
+#[derive(Debug)]
+pub struct BcryptScheme;
+
+impl BcryptScheme {
+
+    /// Converts a hash in the bcrypt format into the PHC string format,
+    /// or returns None when the hash is not a bcrypt hash.
+    pub fn to_phc_string(hash: &str) -> Option<String> {
+        let mut parts = hash.strip_prefix('$')?.splitn(3, '$');
+        let variant = parts.next().filter(|variant| matches!(*variant, "2a" | "2b" | "2x" | "2y"))?;
+        let cost: u32 = parts.next().filter(|cost| cost.len() == 2)?.parse().ok()?;
+        let salt_and_hash = parts.next().filter(|s| s.len() == SALT_LENGTH + HASH_LENGTH && s.is_ascii())?;
+        let (salt, hash) = salt_and_hash.split_at(SALT_LENGTH);
+
+        let mut params = ParamsString::new();
+        params.add_str("variant", variant).ok()?;
+        params.add_decimal("cost", cost).ok()?;
+
+        let output = BCRYPT_BASE64.decode(hash).ok()?;
+        let password_hash = PasswordHash {
+            algorithm: BCRYPT_IDENT,
+            version: None,
+            params,
+            salt: Some(Salt::from_b64(salt).ok()?),
+            hash: Some(Output::new(&output).ok()?),
+        };
+
+        Some(password_hash.to_string())
+    }
+
+    /// Converts a PHC string created by `to_phc_string` back into the bcrypt format.
+    fn to_bcrypt_format(password_hash: &PasswordHash) -> Option<String> {
+        if password_hash.algorithm != BCRYPT_IDENT {
+            return None
+        }
+
+        let variant = password_hash.params.get_str("variant")?;
+        let cost = password_hash.params.get_decimal("cost")?;
+        let salt = password_hash.salt?;
+        let hash = BCRYPT_BASE64.encode(password_hash.hash?.as_bytes());
+
+        Some(format!("${}${:02}${}{}", variant, cost, salt.as_str(), hash))
+    }
+}
+
+impl Scheme for BcryptScheme {
+    fn hash<'a>(&self, _: Secret<String>, _: &'a SaltString) -> password_hash::Result<PasswordHash<'a>> {
+        // bcrypt is only supported to verify the passwords of imported users
+        Err(password_hash::Error::Algorithm)
+    }
+
+    fn validate(&self, password: &Secret<String>, password_hash: &PasswordHash) -> Result<(), Error> {
+        let hash = Self::to_bcrypt_format(password_hash)
+            .ok_or(Error::Other(password_hash::Error::PhcStringField))?;
+
+        match bcrypt::verify(password.expose_secret(), &hash) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Error::PasswordInvalid),
+            Err(_) => Err(Error::Other(password_hash::Error::Crypto)),
+        }
+    }
+
+    fn needs_rehash(&self, _: &PasswordHash) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use password_hash::PasswordHash;
+    use secrecy::Secret;
+    use crate::hash::bcrypt::{BcryptScheme, BCRYPT_IDENT};
+    use crate::hash::error::Error;
+    use crate::hash::scheme::Scheme;
+
+    /// The bcrypt hash of `legacy password` with a cost of 4.
+    const HASH: &str = "$2b$04$KBCwKxOzLha2MUDgW0PjXeWKWdSoZ0jRTMFN//AhIbZlYynU0YrlW";
+
+    #[test]
+    fn test_to_phc_string_round_trip() {
+        let phc_string = BcryptScheme::to_phc_string(HASH).unwrap();
+        assert!(phc_string.starts_with("$bcrypt$variant=2b,cost=4$KBCwKxOzLha2MUDgW0PjXe$"));
+
+        let password_hash = PasswordHash::new(&phc_string).unwrap();
+        assert_eq!(password_hash.algorithm, BCRYPT_IDENT);
+        assert_eq!(BcryptScheme::to_bcrypt_format(&password_hash).unwrap(), HASH);
+
+        assert!(BcryptScheme::to_phc_string("$argon2id$v=19$m=19456,t=2,p=1$c2FsdHNhbHQ$aGFzaA").is_none());
+        assert!(BcryptScheme::to_phc_string("$2b$04$tooshort").is_none());
+    }
+
+    #[test]
+    fn test_validate() {
+        let phc_string = BcryptScheme::to_phc_string(HASH).unwrap();
+        let password_hash = PasswordHash::new(&phc_string).unwrap();
+
+        assert!(BcryptScheme.validate(&Secret::new("legacy password".to_string()), &password_hash).is_ok());
+        assert!(matches!(
+            BcryptScheme.validate(&Secret::new("other password".to_string()), &password_hash),
+            Err(Error::PasswordInvalid)
+        ));
+        assert!(BcryptScheme.needs_rehash(&password_hash));
+    }
+}
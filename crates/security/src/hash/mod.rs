@@ -1,3 +1,6 @@
 pub mod argon2;
 pub mod error;
 pub mod scheme;
+pub mod bcrypt;
+pub mod pbkdf2;
+pub mod token_hash;
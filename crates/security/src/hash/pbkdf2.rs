@@ -0,0 +1,64 @@
+use password_hash::{Ident, PasswordHash, PasswordVerifier, SaltString};
+use pbkdf2::Pbkdf2;
+use secrecy::{ExposeSecret, Secret};
+use crate::hash::error::Error;
+use crate::hash::scheme::Scheme;
+
+/// PBKDF2_SHA256_IDENT identifies PBKDF2-SHA256 hashes in the PHC string format,
+/// e.g. `$pbkdf2-sha256$i=600000,l=32$<salt>$<hash>`.
+pub const PBKDF2_SHA256_IDENT: Ident = Ident::new_unwrap("pbkdf2-sha256");
+
+/// Pbkdf2Sha256Scheme verifies PBKDF2-SHA256 hashes of users imported from legacy systems. It never
+/// creates hashes, passwords it verifies are expected to be hashed again with the latest scheme.
+#[derive(Debug)]
+pub struct Pbkdf2Sha256Scheme;
+
+impl Scheme for Pbkdf2Sha256Scheme {
+    fn hash<'a>(&self, _: Secret<String>, _: &'a SaltString) -> password_hash::Result<PasswordHash<'a>> {
+        // PBKDF2 is only supported to verify the passwords of imported users
+        Err(password_hash::Error::Algorithm)
+    }
+
+    fn validate(&self, password: &Secret<String>, password_hash: &PasswordHash) -> Result<(), Error> {
+        if password_hash.algorithm != PBKDF2_SHA256_IDENT {
+            return Err(Error::Other(password_hash::Error::Algorithm))
+        }
+
+        Pbkdf2
+            .verify_password(password.expose_secret().as_bytes(), password_hash)
+            .map_err(Error::from)
+    }
+
+    fn needs_rehash(&self, _: &PasswordHash) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use password_hash::PasswordHash;
+    use secrecy::Secret;
+    use crate::hash::error::Error;
+    use crate::hash::pbkdf2::Pbkdf2Sha256Scheme;
+    use crate::hash::scheme::Scheme;
+
+    /// The PBKDF2-SHA256 hash of `legacy password` with 1000 iterations.
+    const HASH: &str = "$pbkdf2-sha256$i=1000,l=32$c2FsdHNhbHRzYWx0$KBsyyEc464z6vc0vmylEJvYjcmuf+3lVAVWJ5sf4wXM";
+
+    #[test]
+    fn test_validate() {
+        let password_hash = PasswordHash::new(HASH).unwrap();
+
+        assert!(Pbkdf2Sha256Scheme.validate(&Secret::new("legacy password".to_string()), &password_hash).is_ok());
+        assert!(matches!(
+            Pbkdf2Sha256Scheme.validate(&Secret::new("other password".to_string()), &password_hash),
+            Err(Error::PasswordInvalid)
+        ));
+    }
+
+    #[test]
+    fn test_never_hashes() {
+        let salt = password_hash::SaltString::generate(&mut rand::thread_rng());
+        assert!(Pbkdf2Sha256Scheme.hash(Secret::new("password".to_string()), &salt).is_err());
+    }
+}
@@ -3,8 +3,10 @@ use enum_dispatch::enum_dispatch;
 use password_hash::{Ident, PasswordHash, SaltString};
 use secrecy::Secret;
 use crate::hash::argon2::Argon2Scheme;
+use crate::hash::bcrypt::{BcryptScheme, BCRYPT_IDENT};
 use crate::hash::error::Error;
-use crate::hash::scheme::SchemaDispatch::Argon2ID;
+use crate::hash::pbkdf2::{Pbkdf2Sha256Scheme, PBKDF2_SHA256_IDENT};
+use crate::hash::scheme::SchemaDispatch::{Argon2ID, Bcrypt, Pbkdf2Sha256};
 
 
 /// DEFAULT_SCHEME is the hash scheme all passwords should be using
@@ -17,26 +19,50 @@ pub trait Scheme {
     fn hash<'a>(&self, to_hash: Secret<String>, salt_string: &'a SaltString) -> password_hash::Result<PasswordHash<'a>>;
 
     fn validate(&self, password: &Secret<String>, password_hash: &PasswordHash) -> Result<(), Error>;
+
+    /// needs_rehash checks if the hash was created with weaker parameters than this scheme uses,
+    /// in which case the password should be hashed again.
+    fn needs_rehash(&self, password_hash: &PasswordHash) -> bool;
 }
 
 #[enum_dispatch(Scheme)]
 pub enum SchemaDispatch {
-    Argon2ID(Argon2Scheme)
+    Argon2ID(Argon2Scheme),
+
+    /// Bcrypt only verifies hashes of users imported from legacy systems.
+    Bcrypt(BcryptScheme),
+
+    /// Pbkdf2Sha256 only verifies hashes of users imported from legacy systems.
+    Pbkdf2Sha256(Pbkdf2Sha256Scheme),
 }
 
 /// get_scheme returns the scheme which represents equals the Ident
 pub fn get_scheme(scheme_name: &Ident) -> Option<SchemaDispatch> {
     match *scheme_name {
-        ARGON2ID_IDENT => Some(Argon2ID(Argon2Scheme)),
+        ARGON2ID_IDENT => Some(Argon2ID(Argon2Scheme::configured())),
+        BCRYPT_IDENT => Some(Bcrypt(BcryptScheme)),
+        PBKDF2_SHA256_IDENT => Some(Pbkdf2Sha256(Pbkdf2Sha256Scheme)),
         _ => None
     }
 }
 
+/// to_phc_string converts hashes of legacy systems which are not in the PHC string format,
+/// e.g. bcrypt hashes, into the PHC string format. Other hashes are returned unchanged.
+pub fn to_phc_string(hash: String) -> String {
+    BcryptScheme::to_phc_string(&hash).unwrap_or(hash)
+}
+
 /// is_latest_schema checks if the schema of the hash uses the latest DEFAULT_SCHEME
 pub fn is_latest_schema(schema_of_hash: &Ident) -> bool {
     return *schema_of_hash == DEFAULT_SCHEME
 }
 
+/// is_outdated checks if the hash should be replaced by a hash of the latest scheme,
+/// either because it uses another scheme or because it was created with weaker parameters.
+pub fn is_outdated(password_hash: &PasswordHash) -> bool {
+    !is_latest_schema(&password_hash.algorithm) || get_latest_scheme().needs_rehash(password_hash)
+}
+
 /// get_latest_schema returns the current DEFAULT SCHEMA
 pub fn get_latest_scheme() -> impl Scheme {
     return get_scheme(&DEFAULT_SCHEME).unwrap()
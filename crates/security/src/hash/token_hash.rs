@@ -0,0 +1,36 @@
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use secrecy::{ExposeSecret, Secret};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Hashes a randomly generated token with SHA-256, for tokens which are verified on every request
+/// e.g. personal access tokens. Unlike passwords, random tokens have enough entropy that they cannot
+/// be guessed from their hash, so the slow password hash schemes are not needed.
+pub fn hash_token(token: &Secret<String>) -> String {
+    let hash = Sha256::digest(token.expose_secret().as_bytes());
+    STANDARD_NO_PAD.encode(hash)
+}
+
+/// Verifies in constant time whether the token matches the hash.
+pub fn token_matches(token: &Secret<String>, hash: &str) -> bool {
+    hash_token(token).as_bytes().ct_eq(hash.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    #[test]
+    fn test_token_matches_its_hash() {
+        let token = Secret::new("pat_token".to_string());
+        let hash = hash_token(&token);
+
+        assert_eq!(hash, "TvRZ0b82cDjAN7OWuYq2PgI+IFT7ixuyv7tC4DlWyEQ");
+        assert!(token_matches(&token, &hash));
+        assert!(!token_matches(&Secret::new("pat_other_token".to_string()), &hash));
+        assert!(!token_matches(&token, ""));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod encryption;
 pub mod hash;
+pub mod otp;
 pub mod token;
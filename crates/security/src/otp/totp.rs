@@ -0,0 +1,205 @@
+use std::fmt::{Debug, Formatter};
+
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use secrecy::{ExposeSecret, Secret};
+use serde::de::Error as DeserializeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use totp_rs::{Algorithm, TOTP};
+
+/// The length of generated secrets in bytes, as recommended by RFC 4226.
+const SECRET_LENGTH: usize = 20;
+
+/// The shortest secret accepted in bytes, as required by RFC 4226.
+const MIN_SECRET_LENGTH: usize = 16;
+
+const DIGITS: usize = 6;
+
+/// The duration of a time step in seconds, during which a code is valid.
+const STEP: u64 = 30;
+
+/// The amount of time steps before and after the current time step of which codes are accepted,
+/// to account for clock drift and the time it takes the user to enter the code.
+const ALLOWED_SKEW: i64 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TotpError {
+    #[error("TOTP secret is not valid base32")]
+    InvalidEncoding,
+
+    #[error("TOTP secret must be at least {MIN_SECRET_LENGTH} bytes long")]
+    SecretTooShort,
+
+    #[error(transparent)]
+    InvalidUri(#[from] totp_rs::TotpUrlError),
+}
+
+/// TotpSecret is the secret shared with the authenticator app of a user, from which the
+/// time-based one-time passwords (TOTP) of RFC 6238 are generated. Codes consist of 6 digits
+/// and are generated with HMAC-SHA1 every 30 seconds, which every authenticator app supports.
+pub struct TotpSecret(Secret<Vec<u8>>);
+
+impl TotpSecret {
+
+    /// Generates a new random secret.
+    pub fn generate() -> Self {
+        let mut secret = vec![0u8; SECRET_LENGTH];
+        rand::thread_rng().fill_bytes(&mut secret);
+        Self(Secret::new(secret))
+    }
+
+    pub fn from_base32(secret: &str) -> Result<Self, TotpError> {
+        let secret = totp_rs::Secret::Encoded(secret.to_string())
+            .to_bytes()
+            .map_err(|_| TotpError::InvalidEncoding)?;
+
+        if secret.len() < MIN_SECRET_LENGTH {
+            return Err(TotpError::SecretTooShort)
+        }
+
+        Ok(Self(Secret::new(secret)))
+    }
+
+    /// Returns the secret encoded as base32, which users can enter in their authenticator app.
+    pub fn to_base32(&self) -> Secret<String> {
+        Secret::new(self.totp().get_secret_base32())
+    }
+
+    /// Returns the `otpauth://` URI of the secret, which authenticator apps can scan as QR code.
+    pub fn otpauth_uri(&self, issuer: &str, account_name: &str) -> Result<Secret<String>, TotpError> {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            DIGITS,
+            0,
+            STEP,
+            self.0.expose_secret().clone(),
+            Some(issuer.to_string()),
+            account_name.to_string(),
+        )?;
+
+        Ok(Secret::new(totp.get_url()))
+    }
+
+    /// Returns the code for the given time.
+    pub fn code_at(&self, time: DateTime<Utc>) -> String {
+        self.totp().generate(time.timestamp().max(0) as u64)
+    }
+
+    /// Verifies the code at the given time, accepting the codes of adjacent time steps as well.
+    /// Returns the time step the code belongs to, so codes can be rejected when used more than once.
+    pub fn verify(&self, code: &str, time: DateTime<Utc>) -> Option<i64> {
+        let totp = self.totp();
+        let current_step = time.timestamp() / STEP as i64;
+
+        (current_step - ALLOWED_SKEW..=current_step + ALLOWED_SKEW)
+            .filter(|step| *step >= 0)
+            .find(|step| totp.check(code, *step as u64 * STEP))
+    }
+
+    fn totp(&self) -> TOTP {
+        // the secret length is validated when the secret is created, which is the only check that can fail
+        TOTP::new_unchecked(
+            Algorithm::SHA1,
+            DIGITS,
+            0,
+            STEP,
+            self.0.expose_secret().clone(),
+            None,
+            String::new(),
+        )
+    }
+}
+
+impl Clone for TotpSecret {
+    fn clone(&self) -> Self {
+        Self(Secret::new(self.0.expose_secret().clone()))
+    }
+}
+
+impl Debug for TotpSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpSecret([REDACTED])")
+    }
+}
+
+impl Serialize for TotpSecret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_base32().expose_secret())
+    }
+}
+
+impl<'de> Deserialize<'de> for TotpSecret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let secret = String::deserialize(deserializer)?;
+        TotpSecret::from_base32(&secret).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration};
+    use secrecy::{ExposeSecret, Secret};
+
+    use super::*;
+
+    /// The SHA1 secret of the test vectors in RFC 6238, appendix B.
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret(Secret::new(b"12345678901234567890".to_vec()))
+    }
+
+    #[test]
+    fn test_code_at_matches_rfc_test_vectors() {
+        // the test vectors have 8 digits, of which the last 6 are the 6 digit code
+        let test_vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ];
+
+        for (time, expected) in test_vectors {
+            let time = DateTime::from_timestamp(time, 0).unwrap();
+            assert_eq!(rfc_secret().code_at(time), expected, "{}", time);
+        }
+    }
+
+    #[test]
+    fn test_verify_accepts_adjacent_time_steps() {
+        let secret = TotpSecret::generate();
+        let now = Utc::now();
+        let current_step = now.timestamp() / STEP as i64;
+
+        assert_eq!(secret.verify(&secret.code_at(now), now), Some(current_step));
+
+        let previous_code = secret.code_at(now - Duration::seconds(STEP as i64));
+        assert_eq!(secret.verify(&previous_code, now), Some(current_step - 1));
+
+        let old_code = secret.code_at(now - Duration::seconds(3 * STEP as i64));
+        assert_eq!(secret.verify(&old_code, now), None);
+
+        assert_eq!(secret.verify("not a code", now), None);
+    }
+
+    #[test]
+    fn test_base32_round_trip() {
+        let secret = TotpSecret::generate();
+        let encoded = secret.to_base32();
+
+        let decoded = TotpSecret::from_base32(encoded.expose_secret()).unwrap();
+        assert_eq!(decoded.0.expose_secret(), secret.0.expose_secret());
+
+        assert!(matches!(TotpSecret::from_base32("not base32!"), Err(TotpError::InvalidEncoding)));
+        assert!(matches!(TotpSecret::from_base32("GEZDGNBV"), Err(TotpError::SecretTooShort)));
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let secret = TotpSecret::generate();
+        let uri = secret.otpauth_uri("rust_backend_setup", "admin").unwrap();
+
+        assert!(uri.expose_secret().starts_with("otpauth://totp/rust_backend_setup:admin?"));
+        assert!(uri.expose_secret().contains(&format!("secret={}", secret.to_base32().expose_secret())));
+        assert!(secret.otpauth_uri("invalid:issuer", "admin").is_err());
+    }
+}
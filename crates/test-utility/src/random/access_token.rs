@@ -1,6 +1,7 @@
 
 use security::token::token::Token;
 use domain::sessions::user_session_token::UserSessionToken;
+use domain::sessions::session_policy::SessionPolicy;
 use domain::sessions::tokens::{AccessToken, RefreshToken};
 
 pub fn random_access_token_for(refresh_token: UserSessionToken<RefreshToken>) -> UserSessionToken<AccessToken> {
@@ -8,5 +9,5 @@ pub fn random_access_token_for(refresh_token: UserSessionToken<RefreshToken>) ->
         user_id: refresh_token.custom_claims.user_id.clone(),
         session_id: refresh_token.custom_claims.session_id.clone(),
         refresh_token_id: refresh_token.get_id().clone(),
-    }.into()
+    }.issue(&SessionPolicy::default())
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
+use chrono::Utc;
 use uuid::Uuid;
 use security::token::token::Token;
 use domain::sessions::user_session_token::UserSessionToken;
+use domain::sessions::session_policy::SessionPolicy;
 use domain::sessions::tokens::RefreshToken;
 use domain::user::user_id::UserId;
 
@@ -9,7 +11,7 @@ pub fn random_refresh_token(user_id: UserId, session_id: &Uuid) -> UserSessionTo
         user_id: user_id.0,
         session_id: session_id.clone(),
         parent_id: None,
-    }.into()
+    }.issue(&SessionPolicy::default(), Utc::now())
 }
 
 pub fn random_refresh_token_from(token: &UserSessionToken<RefreshToken>) -> UserSessionToken<RefreshToken> {
@@ -17,5 +19,5 @@ pub fn random_refresh_token_from(token: &UserSessionToken<RefreshToken>) -> User
         user_id: token.custom_claims.user_id.clone(),
         session_id: token.custom_claims.session_id.clone(),
         parent_id: Some(token.get_id().clone()),
-    }.into()
+    }.issue(&SessionPolicy::default(), Utc::now())
 }
\ No newline at end of file
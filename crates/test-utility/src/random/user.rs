@@ -11,6 +11,7 @@ pub fn random_new_user(password: Secret<String>, salt_string: &SaltString) -> Ne
         id: Uuid::new_v4().into(),
         username: random_string(),
         password: Password::new(password, salt_string).expect("Failed to random new password"),
+        email: None,
         system_role: None,
     }
 }
\ No newline at end of file
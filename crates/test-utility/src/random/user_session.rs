@@ -1,9 +1,23 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use rand::Rng;
 use uuid::Uuid;
 
+use domain::sessions::device::Device;
+use domain::sessions::session_policy::SessionPolicy;
 use domain::sessions::state::newly_created::NewlyCreated;
+use domain::sessions::user_agent::UserAgent;
 use domain::sessions::user_session::UserSession;
 use domain::user::user_id::UserId;
 
 pub fn random_newly_created_user_session(user_id: UserId) -> UserSession<NewlyCreated> {
-    UserSession::<NewlyCreated>::new(user_id)
-}
\ No newline at end of file
+    UserSession::<NewlyCreated>::new(user_id, random_device(), &SessionPolicy::default())
+}
+
+pub fn random_device() -> Device {
+    let user_agent = UserAgent::new(&format!("Mozilla/5.0 (X11; Linux x86_64) Chrome/{}", Uuid::new_v4().simple()))
+        .expect("Failed to parse random user agent");
+    let ip_address = IpAddr::V4(Ipv4Addr::from(rand::thread_rng().gen::<u32>()));
+
+    Device::new(Some(user_agent), Some(ip_address))
+}